@@ -33,7 +33,7 @@ impl<'app> MyPrinter<'app> {
         for symbol in &self.symbols {
             print!("{:?} ", symbol);
         }
-        println!("");
+        println!();
     }
 
     fn print_strings(&self) {
@@ -41,6 +41,6 @@ impl<'app> MyPrinter<'app> {
         for string in &self.strings {
             print!("{:?} ", string);
         }
-        println!("");
+        println!();
     }
 }
@@ -0,0 +1,70 @@
+//! A benchmark of the hashers that can be selected for the symbol table, on an
+//! identifier-like workload. Run it with `cargo run --release --example hashers`, and
+//! add `--features siphash` to measure the table with SipHash.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hint::black_box;
+use std::time::Instant;
+
+use fxhash::FxBuildHasher;
+use gregtatum_symbol_table::SymbolTable;
+
+const ITERATIONS: usize = 20;
+
+fn identifiers() -> Vec<String> {
+    let prefixes = ["get", "set", "is", "has", "to", "from", "new", "with"];
+    let names = [
+        "len", "index", "name", "value", "symbol", "table", "node", "span",
+    ];
+    let mut identifiers = Vec::new();
+    for i in 0..50 {
+        for prefix in prefixes {
+            for name in names {
+                identifiers.push(format!("{prefix}_{name}_{i}"));
+                identifiers.push(name.to_string());
+            }
+        }
+    }
+    identifiers
+}
+
+fn time(name: &str, f: impl Fn() -> usize) {
+    let start = Instant::now();
+    let mut total = 0;
+    for _ in 0..ITERATIONS {
+        total += black_box(f());
+    }
+    println!("{name:>20}: {:?} ({total} strings)", start.elapsed());
+}
+
+fn hash_map<S: BuildHasher + Default>(identifiers: &[String]) -> usize {
+    let mut map: HashMap<&str, usize, S> = HashMap::default();
+    for identifier in identifiers {
+        let len = map.len();
+        map.entry(identifier).or_insert(len);
+    }
+    map.len()
+}
+
+fn main() {
+    let identifiers = identifiers();
+
+    time("HashMap fxhash", || hash_map::<FxBuildHasher>(&identifiers));
+    time("HashMap SipHash", || hash_map::<RandomState>(&identifiers));
+    time(
+        if cfg!(feature = "siphash") {
+            "SymbolTable SipHash"
+        } else {
+            "SymbolTable fxhash"
+        },
+        || {
+            let symbol_table = SymbolTable::new();
+            for identifier in &identifiers {
+                symbol_table.get(identifier.as_str());
+            }
+            symbol_table.len()
+        },
+    );
+}
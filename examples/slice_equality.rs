@@ -0,0 +1,57 @@
+//! A small benchmark of slice equality, which falls back to comparing the strings of
+//! the slices. Run it with `cargo run --release --example slice_equality`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use gregtatum_symbol_table::{Symbol, SymbolTable};
+
+const ITERATIONS: usize = 200;
+
+fn main() {
+    let symbol_table = SymbolTable::new();
+    let identifiers = [
+        "a",
+        "len",
+        "index",
+        "symbol_table",
+        "identifier_name",
+        "some_much_longer_identifier_name",
+        "an_extremely_long_identifier_that_is_longer_than_sixty_four_bytes_long",
+    ];
+    let source: String =
+        identifiers
+            .iter()
+            .cycle()
+            .take(2000)
+            .fold(String::new(), |mut source, identifier| {
+                source.push_str(identifier);
+                source.push(' ');
+                source
+            });
+    let source = symbol_table.get(source);
+
+    let mut tokens: Vec<Symbol> = Vec::new();
+    let mut start = 0;
+    for word in source.str().split(' ').filter(|word| !word.is_empty()) {
+        let word_start = start + source.str()[start..].find(word).unwrap();
+        tokens.push(source.slice(word_start..word_start + word.len()).unwrap());
+        start = word_start + word.len();
+    }
+
+    let time = |name: &str, f: &dyn Fn(Symbol, Symbol) -> bool| {
+        let start = Instant::now();
+        let mut matches = 0;
+        for _ in 0..ITERATIONS {
+            for a in &tokens[..identifiers.len()] {
+                for b in &tokens {
+                    matches += f(black_box(*a), black_box(*b)) as usize;
+                }
+            }
+        }
+        println!("{name:>12}: {:?} ({matches} matches)", start.elapsed());
+    };
+
+    time("Symbol ==", &|a, b| a == b);
+    time("str ==", &|a, b| a.str() == b.str());
+}
@@ -0,0 +1,268 @@
+//! Conversions between symbols and the buffers of Apache Arrow dictionary arrays and
+//! Apache Parquet dictionary pages, so that interned columns can move in and out of
+//! columnar formats without going through a `String` per row. This requires the `arrow`
+//! feature.
+//!
+//! These work on the raw buffers that the formats specify, rather than on the types of
+//! the `arrow` and `parquet` crates, so that the crates aren't dependencies. Nulls
+//! aren't supported, so the keys of null rows need to be filtered out first.
+
+use std::fmt;
+
+use crate::{SymbolColumn, SymbolIndex, SymbolTable};
+
+/// The buffers of an Arrow `Utf8` array. The string at position `i` is the UTF-8 data
+/// from `offsets[i]` to `offsets[i + 1]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArrowUtf8Array {
+    /// The offsets into the data, with one more offset than there are strings.
+    pub offsets: Vec<i32>,
+    /// The UTF-8 data of every string, concatenated.
+    pub data: Vec<u8>,
+}
+
+impl ArrowUtf8Array {
+    fn from_strings<'a>(strings: impl Iterator<Item = &'a str>) -> Self {
+        let mut array = ArrowUtf8Array {
+            offsets: vec![0],
+            data: Vec::new(),
+        };
+        for string in strings {
+            array.data.extend_from_slice(string.as_bytes());
+            array.offsets.push(to_i32(array.data.len()));
+        }
+        array
+    }
+}
+
+/// The buffers of an Arrow dictionary array with `Int32` keys and `Utf8` values.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArrowDictionary {
+    /// The position of each row's value.
+    pub keys: Vec<i32>,
+    /// The values of the dictionary.
+    pub values: ArrowUtf8Array,
+}
+
+/// The error for buffers that don't follow the Arrow or Parquet format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColumnarError {
+    /// The offsets are decreasing, negative, or out of range of the data.
+    InvalidOffsets,
+    /// A value isn't valid UTF-8.
+    InvalidUtf8,
+    /// A Parquet page ended in the middle of a value.
+    Truncated,
+    /// A key is out of range of the values.
+    KeyOutOfRange {
+        /// The row of the key.
+        row: usize,
+    },
+}
+
+impl fmt::Display for ColumnarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColumnarError::InvalidOffsets => write!(f, "The offsets are invalid."),
+            ColumnarError::InvalidUtf8 => write!(f, "A value isn't valid UTF-8."),
+            ColumnarError::Truncated => write!(f, "The page ended in the middle of a value."),
+            ColumnarError::KeyOutOfRange { row } => {
+                write!(f, "The key at row {row} is out of range of the values.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ColumnarError {}
+
+fn to_i32(value: usize) -> i32 {
+    i32::try_from(value).expect("Arrow Utf8 arrays are limited to i32 offsets.")
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Converts every string of the table to the values of an Arrow dictionary array, in
+    /// index order. A buffer of indexes into the table can then be used as the keys of
+    /// the dictionary array as is.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let keys: Vec<i32> = ["GET", "POST", "GET"]
+    ///     .iter()
+    ///     .map(|method| symbol_table.get(*method).index() as i32)
+    ///     .collect();
+    ///
+    /// let values = symbol_table.to_arrow_values();
+    /// assert_eq!(values.offsets, [0, 3, 7]);
+    /// assert_eq!(values.data, b"GETPOST");
+    /// assert_eq!(keys, [0, 1, 0]);
+    /// ```
+    pub fn to_arrow_values(&self) -> ArrowUtf8Array {
+        ArrowUtf8Array::from_strings(self.iter())
+    }
+}
+
+impl<'strings> SymbolColumn<'strings> {
+    /// Converts the column to a compact Arrow dictionary array, which only has the values
+    /// that are used by the column.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SymbolColumn, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let mut column = SymbolColumn::new(&symbol_table);
+    /// column.extend(["b", "a", "b"]);
+    ///
+    /// let array = column.to_arrow_dictionary();
+    /// assert_eq!(array.keys, [0, 1, 0]);
+    /// assert_eq!(array.values.data, b"ba");
+    ///
+    /// let other_table = SymbolTable::new();
+    /// let values = &array.values;
+    /// let round_trip =
+    ///     SymbolColumn::from_arrow_dictionary(&other_table, &array.keys, &values.offsets, &values.data)
+    ///         .unwrap();
+    /// assert_eq!(round_trip.iter().collect::<Vec<_>>(), ["b", "a", "b"]);
+    /// ```
+    pub fn to_arrow_dictionary(&self) -> ArrowDictionary {
+        let dictionary = self.to_dictionary();
+        ArrowDictionary {
+            keys: dictionary.keys.iter().map(|&key| key as i32).collect(),
+            values: ArrowUtf8Array::from_strings(dictionary.values.iter().map(|value| value.str())),
+        }
+    }
+
+    /// Creates a column from the buffers of an Arrow dictionary array with `Int32` keys
+    /// and `Utf8` values, interning each value once. The values don't need to be unique.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn from_arrow_dictionary(
+        symbol_table: &'strings SymbolTable<'strings>,
+        keys: &[i32],
+        offsets: &[i32],
+        data: &[u8],
+    ) -> Result<Self, ColumnarError> {
+        let mut values = Vec::with_capacity(offsets.len().saturating_sub(1));
+        for range in offsets.windows(2) {
+            let (Ok(start), Ok(end)) = (usize::try_from(range[0]), usize::try_from(range[1]))
+            else {
+                return Err(ColumnarError::InvalidOffsets);
+            };
+            let bytes = data.get(start..end).ok_or(ColumnarError::InvalidOffsets)?;
+            let string = std::str::from_utf8(bytes).map_err(|_| ColumnarError::InvalidUtf8)?;
+            values.push(symbol_table.get(string).index());
+        }
+        column_from_keys(symbol_table, keys.iter().map(|&key| key as usize), &values)
+    }
+
+    /// Converts the column to a Parquet dictionary page with the `PLAIN` encoding of
+    /// `BYTE_ARRAY` values, where each value is a little-endian `u32` length followed by
+    /// its bytes. The returned keys are the position of each row's value in the page,
+    /// which a Parquet writer can encode in the data pages.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SymbolColumn, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let mut column = SymbolColumn::new(&symbol_table);
+    /// column.extend(["on", "off", "on"]);
+    ///
+    /// let (keys, page) = column.to_parquet_dictionary_page();
+    /// assert_eq!(keys, [0, 1, 0]);
+    /// assert_eq!(page, b"\x02\0\0\0on\x03\0\0\0off");
+    ///
+    /// let round_trip = SymbolColumn::from_parquet_dictionary_page(&symbol_table, &keys, &page);
+    /// assert_eq!(round_trip.unwrap().indexes(), column.indexes());
+    /// ```
+    pub fn to_parquet_dictionary_page(&self) -> (Vec<u32>, Vec<u8>) {
+        let dictionary = self.to_dictionary();
+        let mut page = Vec::new();
+        for value in &dictionary.values {
+            let len = u32::try_from(value.str().len()).expect("Parquet values fit in a u32.");
+            page.extend_from_slice(&len.to_le_bytes());
+            page.extend_from_slice(value.str().as_bytes());
+        }
+        (dictionary.keys, page)
+    }
+
+    /// Creates a column from a Parquet dictionary page with the `PLAIN` encoding of
+    /// `BYTE_ARRAY` values, and the decoded keys of the data pages.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn from_parquet_dictionary_page(
+        symbol_table: &'strings SymbolTable<'strings>,
+        keys: &[u32],
+        mut page: &[u8],
+    ) -> Result<Self, ColumnarError> {
+        let mut values = Vec::new();
+        while !page.is_empty() {
+            let len = page.get(..4).ok_or(ColumnarError::Truncated)?;
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            let bytes = page.get(4..4 + len).ok_or(ColumnarError::Truncated)?;
+            let string = std::str::from_utf8(bytes).map_err(|_| ColumnarError::InvalidUtf8)?;
+            values.push(symbol_table.get(string).index());
+            page = &page[4 + len..];
+        }
+        column_from_keys(symbol_table, keys.iter().map(|&key| key as usize), &values)
+    }
+}
+
+fn column_from_keys<'strings>(
+    symbol_table: &'strings SymbolTable<'strings>,
+    keys: impl Iterator<Item = usize>,
+    values: &[SymbolIndex],
+) -> Result<SymbolColumn<'strings>, ColumnarError> {
+    let indexes = keys
+        .enumerate()
+        .map(|(row, key)| {
+            values
+                .get(key)
+                .copied()
+                .ok_or(ColumnarError::KeyOutOfRange { row })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(SymbolColumn::from_indexes(symbol_table, indexes).expect("The values were interned."))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_invalid_buffers() {
+        let symbol_table = SymbolTable::new();
+        let from_arrow = |keys: &[i32], offsets: &[i32], data: &[u8]| {
+            SymbolColumn::from_arrow_dictionary(&symbol_table, keys, offsets, data).err()
+        };
+        assert_eq!(
+            from_arrow(&[], &[0, 2, 1], b"ab"),
+            Some(ColumnarError::InvalidOffsets)
+        );
+        assert_eq!(
+            from_arrow(&[], &[0, 3], b"ab"),
+            Some(ColumnarError::InvalidOffsets)
+        );
+        assert_eq!(
+            from_arrow(&[], &[-1, 0], b""),
+            Some(ColumnarError::InvalidOffsets)
+        );
+        assert_eq!(
+            from_arrow(&[], &[0, 1], b"\xff"),
+            Some(ColumnarError::InvalidUtf8)
+        );
+        assert_eq!(
+            from_arrow(&[0, -1], &[0, 1], b"a"),
+            Some(ColumnarError::KeyOutOfRange { row: 1 })
+        );
+        // Duplicate values map to the same index.
+        let column =
+            SymbolColumn::from_arrow_dictionary(&symbol_table, &[0, 1], &[0, 1, 2], b"aa").unwrap();
+        assert_eq!(column.indexes()[0], column.indexes()[1]);
+
+        let from_parquet = |page: &[u8]| {
+            SymbolColumn::from_parquet_dictionary_page(&symbol_table, &[], page).err()
+        };
+        assert_eq!(from_parquet(b"\x01\0\0"), Some(ColumnarError::Truncated));
+        assert_eq!(from_parquet(b"\x02\0\0\0a"), Some(ColumnarError::Truncated));
+        assert_eq!(from_parquet(b""), None);
+    }
+}
@@ -0,0 +1,194 @@
+//! A symbol table that interns references into an external buffer, rather than copying
+//! the strings.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use fxhash::FxHashMap;
+
+use crate::SymbolIndex;
+
+/// A symbol table that interns `&'src str` references to a source buffer that the caller
+/// keeps alive, such as a memory-mapped file. Unlike the [`SymbolTable`](crate::SymbolTable),
+/// no strings are copied, which makes it a good fit for single-pass parsers where
+/// copying every token would be the dominant cost.
+///
+/// ```
+/// use gregtatum_symbol_table::BorrowedSymbolTable;
+///
+/// let source = String::from("let x = x + 1");
+/// let symbol_table = BorrowedSymbolTable::new();
+///
+/// let tokens: Vec<_> = source
+///     .split(' ')
+///     .map(|token| symbol_table.get(token))
+///     .collect();
+///
+/// assert_eq!(tokens[1], tokens[3]);
+/// assert_eq!(tokens[1], "x");
+/// assert_eq!(symbol_table.len(), 5);
+///
+/// // The interned strings point into the source buffer.
+/// assert!(std::ptr::eq(tokens[0].str(), &source[0..3]));
+/// ```
+#[derive(Default)]
+pub struct BorrowedSymbolTable<'src> {
+    strings: RefCell<Vec<&'src str>>,
+    indexes: RefCell<FxHashMap<&'src str, SymbolIndex>>,
+}
+
+impl<'src> BorrowedSymbolTable<'src> {
+    /// Creates a new, empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns a reference to a string if an equal string hasn't been interned yet. The
+    /// returned symbol isn't bound to the table, only to the source buffer.
+    pub fn get(&self, string: &'src str) -> BorrowedSymbol<'src> {
+        if let Some(symbol) = self.maybe_get(string) {
+            return symbol;
+        }
+        let mut strings = self.strings.borrow_mut();
+        let index = strings.len();
+        strings.push(string);
+        self.indexes.borrow_mut().insert(string, index);
+        BorrowedSymbol { index, string }
+    }
+
+    /// Gets a symbol for a string only if it has already been interned.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::BorrowedSymbolTable;
+    ///
+    /// let symbol_table = BorrowedSymbolTable::new();
+    /// symbol_table.get("hello");
+    /// assert_eq!(symbol_table.maybe_get("hello").unwrap(), "hello");
+    /// assert_eq!(symbol_table.maybe_get("world"), None);
+    /// ```
+    pub fn maybe_get(&self, string: &str) -> Option<BorrowedSymbol<'src>> {
+        let index = *self.indexes.borrow().get(string)?;
+        Some(BorrowedSymbol {
+            index,
+            string: self.strings.borrow()[index],
+        })
+    }
+
+    /// Check if the table has a string.
+    pub fn has(&self, string: &str) -> bool {
+        self.indexes.borrow().contains_key(string)
+    }
+
+    /// Resolves the string for an index.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::BorrowedSymbolTable;
+    ///
+    /// let symbol_table = BorrowedSymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    /// assert_eq!(symbol_table.resolve(hello.index()), Some("hello"));
+    /// assert_eq!(symbol_table.resolve(1), None);
+    /// ```
+    pub fn resolve(&self, index: SymbolIndex) -> Option<&'src str> {
+        self.strings.borrow().get(index).copied()
+    }
+
+    /// Get the amount of strings in the table.
+    pub fn len(&self) -> usize {
+        self.strings.borrow().len()
+    }
+
+    /// Returns true if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate through all of the strings in index order.
+    pub fn iter(&self) -> impl Iterator<Item = &'src str> + '_ {
+        (0..self.len()).filter_map(|index| self.resolve(index))
+    }
+}
+
+/// A symbol from a [`BorrowedSymbolTable`]. It holds the interned reference to the
+/// source buffer, so resolving it is free, and it isn't bound to the lifetime of the
+/// table.
+#[derive(Copy, Clone)]
+pub struct BorrowedSymbol<'src> {
+    index: SymbolIndex,
+    string: &'src str,
+}
+
+impl<'src> BorrowedSymbol<'src> {
+    /// Returns the interned reference into the source buffer.
+    pub fn str(&self) -> &'src str {
+        self.string
+    }
+
+    /// The index of the string in the [`BorrowedSymbolTable`].
+    pub fn index(&self) -> SymbolIndex {
+        self.index
+    }
+}
+
+/// Cheap equality checks. Symbols from the same table are equal when their indexes are.
+impl<'src> PartialEq for BorrowedSymbol<'src> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && std::ptr::eq(self.string, other.string)
+    }
+}
+
+impl<'src> PartialEq<&str> for BorrowedSymbol<'src> {
+    fn eq(&self, other: &&str) -> bool {
+        self.string == *other
+    }
+}
+
+impl<'src> fmt::Display for BorrowedSymbol<'src> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.string)
+    }
+}
+
+impl<'src> fmt::Debug for BorrowedSymbol<'src> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.string)
+    }
+}
+
+impl<'src> AsRef<str> for BorrowedSymbol<'src> {
+    fn as_ref(&self) -> &str {
+        self.string
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_borrowed_symbols_outlive_table() {
+        let source = String::from("a b a");
+        let symbols: Vec<BorrowedSymbol> = {
+            let symbol_table = BorrowedSymbolTable::new();
+            source
+                .split(' ')
+                .map(|token| symbol_table.get(token))
+                .collect()
+        };
+        assert_eq!(symbols[0], symbols[2]);
+        assert_ne!(symbols[0], symbols[1]);
+        assert_eq!(symbols[1].index(), 1);
+        assert_eq!(format!("{} {:?}", symbols[0], symbols[1]), "a \"b\"");
+    }
+
+    #[test]
+    fn test_borrowed_iter() {
+        let symbol_table = BorrowedSymbolTable::new();
+        assert!(symbol_table.is_empty());
+        for token in "c b c a".split(' ') {
+            symbol_table.get(token);
+        }
+        assert!(symbol_table.has("a"));
+        assert_eq!(symbol_table.iter().collect::<Vec<_>>(), ["c", "b", "a"]);
+    }
+}
@@ -0,0 +1,54 @@
+//! Case-insensitive comparisons of symbols, for languages whose identifiers ignore case.
+
+use crate::Symbol;
+
+/// Folds the case of a character by uppercasing and then lowercasing it, which
+/// approximates Unicode full case folding with the case mappings of the standard
+/// library, so that `ß` folds to `ss` and `ς` folds to `σ`.
+fn fold(ch: char) -> impl Iterator<Item = char> {
+    ch.to_uppercase().flat_map(char::to_lowercase)
+}
+
+impl<'strings> Symbol<'strings> {
+    /// Compares the strings while ignoring case, without allocating. ASCII strings are
+    /// compared byte by byte, and other strings are compared by their case folded
+    /// characters. Case folding is approximated with the case mappings of the standard
+    /// library, which agrees with full Unicode case folding, such as `"ß"` being equal
+    /// to `"SS"`, except for a handful of characters like the dotless `ı`, which is
+    /// equal to `i` here.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let select = symbol_table.get("SELECT");
+    /// assert!(select.eq_ignore_case("select"));
+    /// assert!(symbol_table.get("Straße").eq_ignore_case("STRASSE"));
+    /// assert!(!select.eq_ignore_case("selects"));
+    /// ```
+    pub fn eq_ignore_case(&self, other: impl AsRef<str>) -> bool {
+        let (a, b) = (self.str(), other.as_ref());
+        if a.is_ascii() && b.is_ascii() {
+            return a.eq_ignore_ascii_case(b);
+        }
+        a.chars().flat_map(fold).eq(b.chars().flat_map(fold))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::SymbolTable;
+
+    #[test]
+    fn test_eq_ignore_case() {
+        let symbol_table = SymbolTable::new();
+        let sigma = symbol_table.get("ΟΔΟΣ");
+        assert!(sigma.eq_ignore_case("οδος"));
+        assert!(sigma.eq_ignore_case("οδοσ"));
+        assert!(sigma.eq_ignore_case(symbol_table.get("Οδος")));
+        assert!(!sigma.eq_ignore_case("οδο"));
+        // Accents aren't ignored, but ligatures fold to their letters.
+        assert!(!symbol_table.get("é").eq_ignore_case("e"));
+        assert!(symbol_table.get("ﬁ").eq_ignore_case("FI"));
+    }
+}
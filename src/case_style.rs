@@ -0,0 +1,171 @@
+//! Conversions of identifiers between naming conventions, such as for code generators,
+//! where the results are interned and cached per symbol.
+
+use crate::Symbol;
+
+/// A naming convention for identifiers, see [`Symbol::to_case`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CaseStyle {
+    /// `snake_case`
+    Snake,
+    /// `SCREAMING_SNAKE_CASE`
+    ScreamingSnake,
+    /// `kebab-case`
+    Kebab,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    Pascal,
+}
+
+/// Splits an identifier into words, at separators like `_`, `-`, and spaces, and at
+/// changes of case. Runs of uppercase letters are a single word, like `HTTP` in
+/// `HTTPServer`, and digits belong to the word before them.
+fn split_words(identifier: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let chars: Vec<(usize, char)> = identifier.char_indices().collect();
+    for (i, &(offset, ch)) in chars.iter().enumerate() {
+        if !ch.is_alphanumeric() {
+            if let Some(start) = start.take() {
+                words.push(&identifier[start..offset]);
+            }
+            continue;
+        }
+        let Some(word_start) = start else {
+            start = Some(offset);
+            continue;
+        };
+        let previous = chars[i - 1].1;
+        let next = chars.get(i + 1).map(|&(_, next)| next);
+        let is_boundary = ch.is_uppercase()
+            && (previous.is_lowercase()
+                || previous.is_numeric()
+                // The last uppercase letter of a run starts the next word, like the `S`
+                // in `HTTPServer`.
+                || (previous.is_uppercase() && next.is_some_and(char::is_lowercase)));
+        if is_boundary {
+            words.push(&identifier[word_start..offset]);
+            start = Some(offset);
+        }
+    }
+    if let Some(start) = start {
+        words.push(&identifier[start..]);
+    }
+    words
+}
+
+fn capitalize(word: &str, out: &mut String) {
+    let mut chars = word.chars();
+    if let Some(first) = chars.next() {
+        out.extend(first.to_uppercase());
+        out.push_str(&chars.as_str().to_lowercase());
+    }
+}
+
+fn convert(identifier: &str, style: CaseStyle) -> String {
+    let mut out = String::with_capacity(identifier.len());
+    for (i, word) in split_words(identifier).into_iter().enumerate() {
+        match style {
+            CaseStyle::Snake | CaseStyle::ScreamingSnake | CaseStyle::Kebab if i != 0 => {
+                out.push(if style == CaseStyle::Kebab { '-' } else { '_' });
+            }
+            _ => {}
+        }
+        match style {
+            CaseStyle::Snake | CaseStyle::Kebab => out.push_str(&word.to_lowercase()),
+            CaseStyle::ScreamingSnake => out.push_str(&word.to_uppercase()),
+            CaseStyle::Camel if i == 0 => out.push_str(&word.to_lowercase()),
+            CaseStyle::Camel | CaseStyle::Pascal => capitalize(word, &mut out),
+        }
+    }
+    out
+}
+
+impl<'strings> Symbol<'strings> {
+    /// Converts an identifier to a naming convention, and interns the result. The words
+    /// of the identifier are split at `_`, `-`, spaces, and changes of case, so any
+    /// convention can be converted to any other. Conversions of full symbols are cached
+    /// in the table, so converting the same identifier again is a lookup.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{CaseStyle, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let name = symbol_table.get("parseHTTPResponse2");
+    /// assert_eq!(name.to_case(CaseStyle::Snake), "parse_http_response2");
+    /// assert_eq!(name.to_case(CaseStyle::ScreamingSnake), "PARSE_HTTP_RESPONSE2");
+    /// assert_eq!(name.to_case(CaseStyle::Kebab), "parse-http-response2");
+    /// assert_eq!(name.to_case(CaseStyle::Pascal), "ParseHttpResponse2");
+    /// assert_eq!(
+    ///     symbol_table.get("user_id").to_case(CaseStyle::Camel),
+    ///     "userId"
+    /// );
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn to_case(&self, style: CaseStyle) -> Symbol<'strings> {
+        let symbol_table = self.symbol_table;
+        if self.range.is_some() {
+            return symbol_table.get(convert(self.str(), style));
+        }
+        let key = (self.index, style);
+        if let Some(&index) = symbol_table.case_styles.borrow().get(&key) {
+            return Symbol::new(symbol_table, index);
+        }
+        let converted = symbol_table.get(convert(self.str(), style));
+        symbol_table
+            .case_styles
+            .borrow_mut()
+            .insert(key, converted.index);
+        converted
+    }
+
+    /// Converts an identifier to `snake_case`, see [`to_case`](Symbol::to_case).
+    pub fn to_snake_case(&self) -> Symbol<'strings> {
+        self.to_case(CaseStyle::Snake)
+    }
+
+    /// Converts an identifier to `SCREAMING_SNAKE_CASE`, see [`to_case`](Symbol::to_case).
+    pub fn to_screaming_snake_case(&self) -> Symbol<'strings> {
+        self.to_case(CaseStyle::ScreamingSnake)
+    }
+
+    /// Converts an identifier to `kebab-case`, see [`to_case`](Symbol::to_case).
+    pub fn to_kebab_case(&self) -> Symbol<'strings> {
+        self.to_case(CaseStyle::Kebab)
+    }
+
+    /// Converts an identifier to `camelCase`, see [`to_case`](Symbol::to_case).
+    pub fn to_camel_case(&self) -> Symbol<'strings> {
+        self.to_case(CaseStyle::Camel)
+    }
+
+    /// Converts an identifier to `PascalCase`, see [`to_case`](Symbol::to_case).
+    pub fn to_pascal_case(&self) -> Symbol<'strings> {
+        self.to_case(CaseStyle::Pascal)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SymbolTable;
+
+    #[test]
+    fn test_case_conversions_are_cached() {
+        let mut symbol_table = SymbolTable::new();
+        symbol_table.get("other");
+        let name = symbol_table.get("XMLHttpRequest");
+        assert_eq!(name.to_snake_case(), "xml_http_request");
+        assert_eq!(name.to_camel_case(), "xmlHttpRequest");
+        assert_eq!(symbol_table.get("__a--b  c__").to_pascal_case(), "ABC");
+        assert_eq!(symbol_table.get("").to_kebab_case(), "");
+        assert_eq!(symbol_table.case_styles.borrow().len(), 4);
+
+        let name_index = name.index();
+        let remapping = symbol_table.reindex_by_frequency();
+        let name = Symbol::new(&symbol_table, remapping.get(name_index).unwrap());
+        assert_eq!(name.to_screaming_snake_case(), "XML_HTTP_REQUEST");
+        assert_eq!(name.to_snake_case(), "xml_http_request");
+    }
+}
@@ -0,0 +1,229 @@
+//! A dictionary-encoded column of strings, for analytics over categorical data.
+
+use fxhash::FxHashMap;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// A column of strings that is stored as a list of indexes into a [`SymbolTable`], which
+/// acts as the dictionary. Equal values share an index, so grouping and counting only
+/// compare integers.
+///
+/// ```
+/// use gregtatum_symbol_table::{SymbolColumn, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// let mut status = SymbolColumn::new(&symbol_table);
+/// status.extend(["ok", "error", "ok", "ok", "timeout"]);
+///
+/// let counts: Vec<_> = status
+///     .value_counts()
+///     .into_iter()
+///     .map(|(value, count)| (value.str(), count))
+///     .collect();
+/// assert_eq!(counts, [("ok", 3), ("error", 1), ("timeout", 1)]);
+///
+/// let groups = status.group_by();
+/// assert_eq!(groups[0].0, "ok");
+/// assert_eq!(groups[0].1, [0, 2, 3]);
+/// ```
+#[derive(Clone)]
+pub struct SymbolColumn<'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    indexes: Vec<SymbolIndex>,
+}
+
+/// A compact dictionary encoding of a [`SymbolColumn`], where the values only contain
+/// the strings that are used by the column, in the order of their first appearance, and
+/// each key is the position of a row's value. This is the layout of dictionary arrays
+/// in columnar formats.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dictionary<'strings> {
+    /// The position in the values of each row.
+    pub keys: Vec<u32>,
+    /// The unique values of the column.
+    pub values: Vec<Symbol<'strings>>,
+}
+
+impl<'strings> SymbolColumn<'strings> {
+    /// Creates an empty column that uses the table as its dictionary.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        SymbolColumn {
+            symbol_table,
+            indexes: Vec::new(),
+        }
+    }
+
+    /// Creates a column from indexes into the table. Returns `None` if an index is out of
+    /// range of the table.
+    pub fn from_indexes(
+        symbol_table: &'strings SymbolTable<'strings>,
+        indexes: Vec<SymbolIndex>,
+    ) -> Option<Self> {
+        if indexes.iter().any(|&index| index >= symbol_table.len()) {
+            return None;
+        }
+        Some(SymbolColumn {
+            symbol_table,
+            indexes,
+        })
+    }
+
+    /// Interns a value and appends it to the column.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn push(&mut self, value: &str) {
+        self.indexes.push(self.symbol_table.get(value).index());
+    }
+
+    /// The value of a row.
+    pub fn get(&self, row: usize) -> Option<Symbol<'strings>> {
+        let index = *self.indexes.get(row)?;
+        Some(Symbol::new(self.symbol_table, index))
+    }
+
+    /// The number of rows.
+    pub fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    /// Returns true if the column has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    /// The index of each row's value in the table.
+    pub fn indexes(&self) -> &[SymbolIndex] {
+        &self.indexes
+    }
+
+    /// The table that is used as the dictionary.
+    pub fn symbol_table(&self) -> &'strings SymbolTable<'strings> {
+        self.symbol_table
+    }
+
+    /// Iterates over the value of each row.
+    pub fn iter(&self) -> impl Iterator<Item = Symbol<'strings>> + '_ {
+        let symbol_table = self.symbol_table;
+        self.indexes
+            .iter()
+            .map(move |&index| Symbol::new(symbol_table, index))
+    }
+
+    /// Counts the rows of each distinct value, sorted by the count, most common first.
+    /// Values with equal counts are in the order of their first appearance.
+    pub fn value_counts(&self) -> Vec<(Symbol<'strings>, usize)> {
+        let mut counts: Vec<(Symbol<'strings>, usize)> = self
+            .group_by()
+            .into_iter()
+            .map(|(value, rows)| (value, rows.len()))
+            .collect();
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        counts
+    }
+
+    /// Groups the rows by value, returning each distinct value with its row numbers, in
+    /// the order of the value's first appearance.
+    pub fn group_by(&self) -> Vec<(Symbol<'strings>, Vec<usize>)> {
+        let mut positions: FxHashMap<SymbolIndex, usize> = FxHashMap::default();
+        let mut groups: Vec<(Symbol<'strings>, Vec<usize>)> = Vec::new();
+        for (row, &index) in self.indexes.iter().enumerate() {
+            let position = *positions.entry(index).or_insert_with(|| {
+                groups.push((Symbol::new(self.symbol_table, index), Vec::new()));
+                groups.len() - 1
+            });
+            groups[position].1.push(row);
+        }
+        groups
+    }
+
+    /// Converts the column to a compact [`Dictionary`] that only has the values that are
+    /// used.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SymbolColumn, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("unused");
+    /// let mut column = SymbolColumn::new(&symbol_table);
+    /// column.extend(["b", "a", "b"]);
+    ///
+    /// let dictionary = column.to_dictionary();
+    /// assert_eq!(dictionary.keys, [0, 1, 0]);
+    /// assert_eq!(dictionary.values, ["b", "a"]);
+    ///
+    /// let values: Vec<&str> = dictionary.values.iter().map(|value| value.str()).collect();
+    /// let round_trip = SymbolColumn::from_dictionary(&symbol_table, &dictionary.keys, &values);
+    /// assert_eq!(round_trip.unwrap().indexes(), column.indexes());
+    /// ```
+    pub fn to_dictionary(&self) -> Dictionary<'strings> {
+        let mut positions: FxHashMap<SymbolIndex, u32> = FxHashMap::default();
+        let mut values = Vec::new();
+        let keys = self
+            .indexes
+            .iter()
+            .map(|&index| {
+                *positions.entry(index).or_insert_with(|| {
+                    values.push(Symbol::new(self.symbol_table, index));
+                    (values.len() - 1) as u32
+                })
+            })
+            .collect();
+        Dictionary { keys, values }
+    }
+
+    /// Creates a column from dictionary-encoded data, such as a dictionary array from a
+    /// columnar format, interning each value once. Returns `None` if a key is out of
+    /// range of the values.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn from_dictionary(
+        symbol_table: &'strings SymbolTable<'strings>,
+        keys: &[u32],
+        values: &[&str],
+    ) -> Option<Self> {
+        let mut interned: Vec<SymbolIndex> = Vec::with_capacity(values.len());
+        for value in values {
+            interned.push(symbol_table.get(*value).index());
+        }
+        let indexes = keys
+            .iter()
+            .map(|&key| interned.get(key as usize).copied())
+            .collect::<Option<_>>()?;
+        Some(SymbolColumn {
+            symbol_table,
+            indexes,
+        })
+    }
+}
+
+impl<'a, 'strings> Extend<&'a str> for SymbolColumn<'strings> {
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    fn extend<T: IntoIterator<Item = &'a str>>(&mut self, values: T) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_column() {
+        let symbol_table = SymbolTable::new();
+        let mut column = SymbolColumn::new(&symbol_table);
+        assert!(column.is_empty());
+        assert!(column.value_counts().is_empty());
+        assert_eq!(column.to_dictionary().keys, []);
+
+        column.extend(["x", "y", "y"]);
+        assert_eq!(column.len(), 3);
+        assert_eq!(column.get(2).unwrap(), "y");
+        assert_eq!(column.get(3), None);
+        assert_eq!(column.iter().collect::<Vec<_>>(), ["x", "y", "y"]);
+        assert_eq!(column.value_counts()[0].0, "y");
+
+        assert!(SymbolColumn::from_indexes(&symbol_table, vec![0, 2]).is_none());
+        assert!(SymbolColumn::from_indexes(&symbol_table, vec![1, 0]).is_some());
+        assert!(SymbolColumn::from_dictionary(&symbol_table, &[0, 1], &["x"]).is_none());
+    }
+}
@@ -0,0 +1,256 @@
+//! Deltas of newly interned strings, for keeping the tables of two processes in sync.
+
+use std::fmt;
+
+use fxhash::FxHashMap;
+
+use crate::{SymbolIndex, SymbolTable};
+
+/// A point in the history of a [`SymbolTable`], which is the number of strings it had
+/// when [`SymbolTable::mark`](struct.SymbolTable.html#method.mark) was called.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mark(usize);
+
+impl Mark {
+    /// Creates a mark at an index, such as one received from another process.
+    pub fn at(index: SymbolIndex) -> Mark {
+        Mark(index)
+    }
+
+    /// The index of the first string interned after the mark.
+    pub fn index(&self) -> SymbolIndex {
+        self.0
+    }
+}
+
+/// The strings that were interned after a [`Mark`], in order. Applying the delta to a
+/// table that is in sync up to the mark gives the strings the same indexes as in the
+/// table that created the delta.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Delta {
+    start: SymbolIndex,
+    strings: Vec<String>,
+}
+
+impl Delta {
+    /// Creates a delta from its parts, such as when it is received from another process.
+    pub fn new(start: SymbolIndex, strings: Vec<String>) -> Delta {
+        Delta { start, strings }
+    }
+
+    /// The index of the first string in the delta.
+    pub fn start(&self) -> SymbolIndex {
+        self.start
+    }
+
+    /// The index after the last string in the delta, which is where the next delta
+    /// starts.
+    pub fn end(&self) -> SymbolIndex {
+        self.start + self.strings.len()
+    }
+
+    /// The new strings, in order.
+    pub fn strings(&self) -> &[String] {
+        &self.strings
+    }
+
+    /// Returns true if the delta has no strings.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+/// The error for applying a [`Delta`] that doesn't line up with a [`SymbolTable`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeltaError {
+    /// The delta starts after the end of the table, so some strings are missing.
+    Gap {
+        /// The start of the delta.
+        start: SymbolIndex,
+        /// The number of strings in the table.
+        len: usize,
+    },
+    /// The delta has a different string at an index that the table already has.
+    Mismatch {
+        /// The index of the string.
+        index: SymbolIndex,
+    },
+    /// The delta has a new string that the table already has at another index.
+    Duplicate {
+        /// The index that the delta has for the string.
+        index: SymbolIndex,
+        /// The index that the table has for the string.
+        existing: SymbolIndex,
+    },
+}
+
+impl fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeltaError::Gap { start, len } => write!(
+                f,
+                "The delta starts at index {start}, but the table only has {len} strings."
+            ),
+            DeltaError::Mismatch { index } => write!(
+                f,
+                "The delta has a different string at index {index} than the table."
+            ),
+            DeltaError::Duplicate { index, existing } => write!(
+                f,
+                "The delta has a string at index {index} that the table has at index {existing}."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DeltaError {}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Marks the current end of the table, to later get the strings interned after it
+    /// with [`delta_since`](struct.SymbolTable.html#method.delta_since).
+    pub fn mark(&self) -> Mark {
+        Mark(self.len())
+    }
+
+    /// Gets the strings that were interned after a mark, so that they can be shipped to
+    /// another process and applied with
+    /// [`apply_delta`](struct.SymbolTable.html#method.apply_delta).
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let frontend = SymbolTable::new();
+    /// let backend = SymbolTable::new();
+    ///
+    /// let mark = frontend.mark();
+    /// let main = frontend.get("main");
+    /// let args = frontend.get("args");
+    /// let delta = frontend.delta_since(mark);
+    /// assert_eq!(delta.strings(), ["main", "args"]);
+    ///
+    /// backend.apply_delta(&delta).unwrap();
+    /// assert_eq!(backend.get("args").index(), args.index());
+    ///
+    /// // The next delta starts where the last one ended.
+    /// let mark = frontend.mark();
+    /// frontend.get("env");
+    /// backend.apply_delta(&frontend.delta_since(mark)).unwrap();
+    /// assert_eq!(backend.len(), 3);
+    /// ```
+    pub fn delta_since(&self, mark: Mark) -> Delta {
+        let start = mark.0.min(self.len());
+        Delta {
+            start,
+            strings: (start..self.len())
+                .map(|index| self.str(index).to_owned())
+                .collect(),
+        }
+    }
+
+    /// Interns the strings of a delta at the same indexes that they have in the table
+    /// that created it. Strings that the table already has at the same indexes are
+    /// skipped, so applying a delta twice is harmless. This fails without changing the
+    /// table if the delta doesn't line up with it. A table that interns strings on its
+    /// own, rather than only applying deltas, will fall out of sync. Strings in a
+    /// [redacted namespace](struct.SymbolTable.html#method.redact_namespace) are only
+    /// stored in their redacted form, like they are by
+    /// [`get`](struct.SymbolTable.html#method.get).
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{Delta, DeltaError, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("a");
+    ///
+    /// let delta = Delta::new(0, vec!["a".into(), "b".into()]);
+    /// symbol_table.apply_delta(&delta).unwrap();
+    /// symbol_table.apply_delta(&delta).unwrap();
+    /// assert_eq!(symbol_table.len(), 2);
+    ///
+    /// let gap = Delta::new(3, vec!["d".into()]);
+    /// assert_eq!(
+    ///     symbol_table.apply_delta(&gap),
+    ///     Err(DeltaError::Gap { start: 3, len: 2 })
+    /// );
+    /// ```
+    pub fn apply_delta(&self, delta: &Delta) -> Result<(), DeltaError> {
+        let len = self.len();
+        if delta.start > len {
+            return Err(DeltaError::Gap {
+                start: delta.start,
+                len,
+            });
+        }
+        let mut new_strings: FxHashMap<&str, SymbolIndex> = FxHashMap::default();
+        for (index, string) in (delta.start..).zip(&delta.strings) {
+            if index < len {
+                if self.lookup_index(string) != Some(index) {
+                    return Err(DeltaError::Mismatch { index });
+                }
+            } else if let Some(existing) = self
+                .lookup_index(string)
+                .or_else(|| new_strings.get(string.as_str()).copied())
+            {
+                return Err(DeltaError::Duplicate { index, existing });
+            } else {
+                new_strings.insert(string, index);
+            }
+        }
+        let start = delta.strings.len() - new_strings.len();
+        for string in &delta.strings[start..] {
+            self.push_new(string.as_str());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_delta_errors() {
+        let symbol_table = SymbolTable::new();
+        symbol_table.get("a");
+        symbol_table.get("b");
+
+        let mismatch = Delta::new(1, vec!["x".into()]);
+        assert_eq!(
+            symbol_table.apply_delta(&mismatch),
+            Err(DeltaError::Mismatch { index: 1 })
+        );
+        let duplicate = Delta::new(2, vec!["c".into(), "a".into()]);
+        assert_eq!(
+            symbol_table.apply_delta(&duplicate),
+            Err(DeltaError::Duplicate {
+                index: 3,
+                existing: 0
+            })
+        );
+        let repeated = Delta::new(2, vec!["c".into(), "c".into()]);
+        assert_eq!(
+            symbol_table.apply_delta(&repeated),
+            Err(DeltaError::Duplicate {
+                index: 3,
+                existing: 2
+            })
+        );
+        assert_eq!(
+            symbol_table.len(),
+            2,
+            "Failed deltas don't change the table."
+        );
+
+        assert!(symbol_table.delta_since(Mark::at(5)).is_empty());
+        assert_eq!(symbol_table.delta_since(Mark::at(5)).start(), 2);
+        assert_eq!(symbol_table.delta_since(Mark::default()).end(), 2);
+
+        // Strings in a redacted namespace are redacted, and applying them again is fine.
+        symbol_table.redact_namespace("user:", 1);
+        let redacted = Delta::new(2, vec!["user:secret".into()]);
+        symbol_table.apply_delta(&redacted).unwrap();
+        symbol_table.apply_delta(&redacted).unwrap();
+        assert!(!symbol_table.iter().any(|string| string.contains("secret")));
+        assert!(symbol_table.has("user:secret"));
+    }
+}
@@ -0,0 +1,223 @@
+//! Demangling of symbol names from compiled code, such as for symbolicating a profile,
+//! where the links between mangled and demangled names are cached in the table.
+
+use crate::{Symbol, SymbolTable};
+
+/// Demangles a name, see [`SymbolTable::set_demangler`].
+pub(crate) type Demangler = Box<dyn Fn(&str) -> Option<String>>;
+
+/// The escapes of the legacy Rust mangling scheme.
+const RUST_ESCAPES: &[(&str, &str)] = &[
+    ("$SP$", "@"),
+    ("$BP$", "*"),
+    ("$RF$", "&"),
+    ("$LT$", "<"),
+    ("$GT$", ">"),
+    ("$LP$", "("),
+    ("$RP$", ")"),
+    ("$C$", ","),
+];
+
+/// Demangles a name in the legacy Rust mangling scheme, like
+/// `_ZN4core3ptr13drop_in_place17h0123456789abcdefE`, which is the default of `rustc`.
+/// The trailing hash is removed. Returns `None` if the name isn't mangled this way.
+fn demangle_rust_legacy(name: &str) -> Option<String> {
+    let mut rest = name
+        .strip_prefix("_ZN")
+        .or_else(|| name.strip_prefix("__ZN"))?;
+    let mut components = Vec::new();
+    while !rest.starts_with('E') {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let len: usize = rest[..digits].parse().ok()?;
+        let component = rest[digits..].get(..len)?;
+        components.push(component);
+        rest = &rest[digits + len..];
+    }
+    // Allow for a suffix like `.llvm.1234` after the final `E`.
+    if rest.len() > 1 && !rest[1..].starts_with('.') {
+        return None;
+    }
+    let is_hash = |component: &str| {
+        component.len() == 17
+            && component.starts_with('h')
+            && component[1..].bytes().all(|byte| byte.is_ascii_hexdigit())
+    };
+    if components
+        .last()
+        .is_some_and(|component| is_hash(component))
+    {
+        components.pop();
+    }
+    if components.is_empty() {
+        return None;
+    }
+    let mut demangled = String::with_capacity(name.len());
+    for (i, component) in components.iter().enumerate() {
+        if i != 0 {
+            demangled.push_str("::");
+        }
+        // A leading `_` escapes a component that starts with `$`.
+        let mut component = match component.strip_prefix('_') {
+            Some(rest) if rest.starts_with('$') => rest,
+            _ => component,
+        };
+        while !component.is_empty() {
+            if let Some(rest) = component.strip_prefix("..") {
+                demangled.push_str("::");
+                component = rest;
+            } else if let Some((escape, replacement)) = RUST_ESCAPES
+                .iter()
+                .find(|(escape, _)| component.starts_with(escape))
+            {
+                demangled.push_str(replacement);
+                component = &component[escape.len()..];
+            } else if let Some(ch) = component
+                .strip_prefix("$u")
+                .and_then(|rest| rest.split_once('$'))
+                .and_then(|(hex, rest)| {
+                    let ch = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+                    Some((ch, rest))
+                })
+            {
+                demangled.push(ch.0);
+                component = ch.1;
+            } else {
+                let ch = component.chars().next().unwrap();
+                demangled.push(ch);
+                component = &component[ch.len_utf8()..];
+            }
+        }
+    }
+    Some(demangled)
+}
+
+impl<'strings> Symbol<'strings> {
+    /// Demangles the name, and interns the result. Names in the legacy Rust mangling
+    /// scheme are demangled by default, and other schemes, like C++ or Rust v0, can be
+    /// supported with [`SymbolTable::set_demangler`]. Names that aren't mangled are
+    /// returned as they are. The result is cached in the table, along with the link
+    /// back to the mangled name, see [`mangled`](struct.Symbol.html#method.mangled).
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let mangled = symbol_table.get("_ZN5alloc3vec16Vec$LT$T$C$A$GT$4push17h0123456789abcdefE");
+    /// let demangled = mangled.demangled();
+    /// assert_eq!(demangled, "alloc::vec::Vec<T,A>::push");
+    /// assert_eq!(demangled.mangled(), Some(mangled));
+    ///
+    /// assert_eq!(symbol_table.get("main").demangled(), "main");
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn demangled(&self) -> Symbol<'strings> {
+        let symbol_table = self.symbol_table;
+        let full_symbol = self.deslice();
+        if let Some(&index) = symbol_table.demangled.borrow().get(&full_symbol.index) {
+            return Symbol::new(symbol_table, index);
+        }
+        let name = full_symbol.str();
+        let demangled = symbol_table
+            .demangler
+            .borrow()
+            .as_ref()
+            .and_then(|demangler| demangler(name))
+            .or_else(|| demangle_rust_legacy(name));
+        let demangled = match demangled {
+            Some(demangled) if demangled != name => symbol_table.get(demangled),
+            _ => full_symbol,
+        };
+        symbol_table
+            .demangled
+            .borrow_mut()
+            .insert(full_symbol.index, demangled.index);
+        if demangled != full_symbol {
+            symbol_table
+                .mangled
+                .borrow_mut()
+                .entry(demangled.index)
+                .or_insert(full_symbol.index);
+        }
+        demangled
+    }
+
+    /// The mangled name that this name was demangled from with
+    /// [`demangled`](struct.Symbol.html#method.demangled), if any. When several
+    /// mangled names demangle to the same name, such as for different instantiations
+    /// of a generic function, this is the first one that was demangled.
+    pub fn mangled(&self) -> Option<Symbol<'strings>> {
+        let index = *self.symbol_table.mangled.borrow().get(&self.index)?;
+        Some(Symbol::new(self.symbol_table, index))
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Sets a function that demangles names for
+    /// [`Symbol::demangled`](struct.Symbol.html#method.demangled), such as one that
+    /// calls the `cpp_demangle` or `rustc-demangle` crates. The function returns `None`
+    /// for names that it doesn't recognize, which then fall back to the built-in
+    /// demangling of legacy Rust names. Names that were already demangled keep their
+    /// cached result.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.set_demangler(|name| {
+    ///     (name == "_Z3foov").then(|| "foo()".to_string())
+    /// });
+    /// assert_eq!(symbol_table.get("_Z3foov").demangled(), "foo()");
+    /// ```
+    pub fn set_demangler(&self, demangler: impl Fn(&str) -> Option<String> + 'static) {
+        *self.demangler.borrow_mut() = Some(Box::new(demangler));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_demangle_rust_legacy() {
+        for (mangled, demangled) in [
+            (
+                "_ZN4core3ptr13drop_in_place17h0123456789abcdefE",
+                Some("core::ptr::drop_in_place"),
+            ),
+            ("__ZN3foo3barE", Some("foo::bar")),
+            ("_ZN3foo3bar17h0123456789abcdefE.llvm.42", Some("foo::bar")),
+            ("_ZN27$u7b$$u7b$closure$u7d$$u7d$E", Some("{{closure}}")),
+            (
+                "_ZN45_$LT$T$u20$as$u20$alloc..string..ToString$GT$9to_stringE",
+                Some("<T as alloc::string::ToString>::to_string"),
+            ),
+            ("_ZN3foo", None),
+            ("_ZN99fooE", None),
+            ("_ZN18446744073709551615xE", None),
+            ("_ZNE", None),
+            ("_Z3foov", None),
+        ] {
+            assert_eq!(
+                demangle_rust_legacy(mangled).as_deref(),
+                demangled,
+                "{mangled}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_demangled_is_cached_and_remapped() {
+        let mut symbol_table = SymbolTable::new();
+        symbol_table.get("other");
+        let mangled = symbol_table.get("_ZN3foo3barE");
+        mangled.demangled();
+        mangled.demangled();
+        symbol_table.get("foo::bar");
+        let remapping = symbol_table.reindex_by_frequency();
+        let mangled = Symbol::new(&symbol_table, remapping.get(1).unwrap());
+        let demangled = Symbol::new(&symbol_table, remapping.get(2).unwrap());
+        assert_eq!(mangled.demangled(), demangled);
+        assert_eq!(demangled.mangled(), Some(mangled));
+        assert_eq!(symbol_table.demangled.borrow().len(), 1);
+    }
+}
@@ -0,0 +1,137 @@
+//! Interning of large structured documents, with sliced access by named anchors.
+
+use std::ops::Range;
+
+use fxhash::FxHashMap;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// Interns whole documents, such as templates, and provides sliced [`Symbol`]s for
+/// named regions of them. The regions, called anchors, are registered up front as
+/// byte ranges, and are then requested by name.
+///
+/// ```
+/// use gregtatum_symbol_table::{DocumentTable, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// let mut documents = DocumentTable::new(&symbol_table);
+///
+/// let template = documents
+///     .register(
+///         "<h1>Title</h1><p>Body</p>",
+///         [("heading", 4..9), ("body", 17..21)],
+///     )
+///     .unwrap();
+///
+/// assert_eq!(documents.anchor(template, "heading").unwrap(), "Title");
+/// assert_eq!(documents.anchor(template, "body").unwrap(), "Body");
+/// assert_eq!(documents.anchor(template, "footer"), None);
+/// ```
+pub struct DocumentTable<'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    anchors: FxHashMap<SymbolIndex, FxHashMap<String, Range<usize>>>,
+}
+
+impl<'strings> DocumentTable<'strings> {
+    /// Creates a document table that interns its documents into the `symbol_table`.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        DocumentTable {
+            symbol_table,
+            anchors: Default::default(),
+        }
+    }
+
+    /// Interns a document along with its named anchors, and returns the symbol for the
+    /// whole document. Registering the same document again adds to its anchors,
+    /// replacing any with the same name. Returns `None` without registering anything
+    /// if an anchor is out of bounds or isn't on char boundaries.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{DocumentTable, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let mut documents = DocumentTable::new(&symbol_table);
+    /// assert!(documents.register("short", [("too long", 0..100)]).is_none());
+    /// assert!(!symbol_table.has("short"));
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn register<'a, I>(&mut self, text: &str, anchors: I) -> Option<Symbol<'strings>>
+    where
+        I: IntoIterator<Item = (&'a str, Range<usize>)>,
+    {
+        let anchors = anchors
+            .into_iter()
+            .map(|(name, range)| text.get(range.clone()).map(|_| (name.to_string(), range)))
+            .collect::<Option<Vec<_>>>()?;
+        let document = self.symbol_table.get(text);
+        self.anchors
+            .entry(document.index())
+            .or_default()
+            .extend(anchors);
+        Some(document)
+    }
+
+    /// Gets the slice of a document for a named anchor.
+    pub fn anchor(&self, document: Symbol<'strings>, name: &str) -> Option<Symbol<'strings>> {
+        let range = self.anchors.get(&document.index())?.get(name)?;
+        document.parent().slice(range.clone())
+    }
+
+    /// Iterates over the names and slices of a document's anchors, in no particular
+    /// order.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{DocumentTable, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let mut documents = DocumentTable::new(&symbol_table);
+    /// let document = documents.register("a b", [("a", 0..1), ("b", 2..3)]).unwrap();
+    ///
+    /// let mut anchors: Vec<_> = documents.anchors(document).collect();
+    /// anchors.sort_by_key(|(name, _)| *name);
+    /// assert_eq!(anchors[0].0, "a");
+    /// assert_eq!(anchors[0].1, "a");
+    /// assert_eq!(anchors[1].0, "b");
+    /// ```
+    pub fn anchors(
+        &self,
+        document: Symbol<'strings>,
+    ) -> impl Iterator<Item = (&str, Symbol<'strings>)> + '_ {
+        let parent = document.parent();
+        self.anchors
+            .get(&document.index())
+            .into_iter()
+            .flatten()
+            .filter_map(move |(name, range)| Some((name.as_str(), parent.slice(range.clone())?)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reregistering_documents() {
+        let symbol_table = SymbolTable::new();
+        let mut documents = DocumentTable::new(&symbol_table);
+        let first = documents
+            .register("hello world", [("hello", 0..5), ("world", 6..11)])
+            .unwrap();
+        let second = documents
+            .register("hello world", [("hello", 0..4), ("ello", 1..5)])
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(documents.anchor(first, "hello").unwrap(), "hell");
+        assert_eq!(documents.anchor(first, "ello").unwrap(), "ello");
+        assert_eq!(documents.anchor(first, "world").unwrap(), "world");
+        assert_eq!(documents.anchors(first).count(), 3);
+
+        assert!(
+            documents.register("ü", [("half a char", 0..1)]).is_none(),
+            "Anchors must be on char boundaries."
+        );
+        let unregistered = symbol_table.get("unregistered");
+        assert_eq!(documents.anchor(unregistered, "hello"), None);
+        assert_eq!(documents.anchors(unregistered).count(), 0);
+    }
+}
@@ -0,0 +1,264 @@
+//! Reading the symbol tables of ELF object files, so that the function and object
+//! names of a binary are interned in one pass, such as for a symbolizer. This requires
+//! the `elf` feature.
+//!
+//! Only the `.symtab` and `.dynsym` sections of 64-bit files are read. DWARF debug
+//! information isn't read, so inlined functions and line numbers aren't available.
+
+use std::fmt;
+
+use crate::{Symbol, SymbolTable};
+
+const SHT_SYMTAB: u32 = 2;
+const SHT_DYNSYM: u32 = 11;
+const SECTION_HEADER_SIZE: usize = 64;
+const SYMBOL_SIZE: usize = 24;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+
+/// A defined function or object in an ELF file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ElfSymbol<'strings> {
+    /// The address of the symbol.
+    pub address: u64,
+    /// The size of the symbol in bytes, which is 0 if it's unknown.
+    pub size: u64,
+    /// The name of the symbol, which is mangled as it appears in the file. See
+    /// [`Symbol::demangled`](struct.Symbol.html#method.demangled).
+    pub name: Symbol<'strings>,
+}
+
+/// The error for bytes that aren't a supported ELF file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ElfError {
+    /// The bytes don't start with the ELF magic number.
+    NotElf,
+    /// The file isn't a 64-bit ELF file.
+    Unsupported,
+    /// A header or section is out of range of the bytes, or is malformed.
+    Malformed,
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfError::NotElf => write!(f, "The bytes aren't an ELF file."),
+            ElfError::Unsupported => write!(f, "Only 64-bit ELF files are supported."),
+            ElfError::Malformed => write!(f, "The ELF file is malformed."),
+        }
+    }
+}
+
+impl std::error::Error for ElfError {}
+
+/// Reads integers from the file in its byte order.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn slice(&self, offset: u64, len: u64) -> Result<&'a [u8], ElfError> {
+        let start = usize::try_from(offset).map_err(|_| ElfError::Malformed)?;
+        let len = usize::try_from(len).map_err(|_| ElfError::Malformed)?;
+        let end = start.checked_add(len).ok_or(ElfError::Malformed)?;
+        self.bytes.get(start..end).ok_or(ElfError::Malformed)
+    }
+
+    fn uint<const N: usize>(&self, bytes: &[u8], offset: usize) -> u64 {
+        let mut value = [0; 8];
+        let field = &bytes[offset..offset + N];
+        if self.little_endian {
+            value[..N].copy_from_slice(field);
+            u64::from_le_bytes(value)
+        } else {
+            value[8 - N..].copy_from_slice(field);
+            u64::from_be_bytes(value)
+        }
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Interns the names of every defined function and object in the symbol tables of a
+    /// 64-bit ELF file, in either byte order. The symbols are returned sorted by
+    /// address, so the symbol for an address can be found with a binary search.
+    ///
+    /// ```no_run
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let bytes = std::fs::read("target/debug/my-binary").unwrap();
+    /// let symbol_table = SymbolTable::new();
+    /// let symbols = symbol_table.read_elf_symbols(&bytes).unwrap();
+    ///
+    /// // Find the function that contains an address.
+    /// let address = 0x1234;
+    /// let position = symbols.partition_point(|symbol| symbol.address <= address);
+    /// if let Some(symbol) = position.checked_sub(1).map(|i| symbols[i]) {
+    ///     println!("{:#x} is in {}", address, symbol.name.demangled());
+    /// }
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn read_elf_symbols(
+        &'strings self,
+        bytes: &[u8],
+    ) -> Result<Vec<ElfSymbol<'strings>>, ElfError> {
+        if !bytes.starts_with(b"\x7fELF") {
+            return Err(ElfError::NotElf);
+        }
+        let reader = Reader {
+            bytes,
+            little_endian: match bytes.get(5) {
+                Some(1) => true,
+                Some(2) => false,
+                _ => return Err(ElfError::Malformed),
+            },
+        };
+        if bytes.get(4) != Some(&2) {
+            return Err(ElfError::Unsupported);
+        }
+        let header = reader.slice(0, 64)?;
+        let section_headers_offset = reader.uint::<8>(header, 0x28);
+        let section_count = reader.uint::<2>(header, 0x3C);
+        let section_headers = reader.slice(
+            section_headers_offset,
+            section_count * SECTION_HEADER_SIZE as u64,
+        )?;
+        let section = |index: u64| {
+            let start = usize::try_from(index).ok()? * SECTION_HEADER_SIZE;
+            section_headers.get(start..start + SECTION_HEADER_SIZE)
+        };
+
+        let mut symbols = Vec::new();
+        for index in 0..section_count {
+            let header = section(index).ok_or(ElfError::Malformed)?;
+            let section_type = reader.uint::<4>(header, 4) as u32;
+            if section_type != SHT_SYMTAB && section_type != SHT_DYNSYM {
+                continue;
+            }
+            let entries =
+                reader.slice(reader.uint::<8>(header, 24), reader.uint::<8>(header, 32))?;
+            let strings_header =
+                section(reader.uint::<4>(header, 40)).ok_or(ElfError::Malformed)?;
+            let strings = reader.slice(
+                reader.uint::<8>(strings_header, 24),
+                reader.uint::<8>(strings_header, 32),
+            )?;
+            for entry in entries.chunks_exact(SYMBOL_SIZE) {
+                let symbol_type = entry[4] & 0xf;
+                let section_index = reader.uint::<2>(entry, 6);
+                if !matches!(symbol_type, STT_FUNC | STT_OBJECT) || section_index == 0 {
+                    continue;
+                }
+                let name_offset = reader.uint::<4>(entry, 0) as usize;
+                let name = strings.get(name_offset..).ok_or(ElfError::Malformed)?;
+                let name = &name[..name
+                    .iter()
+                    .position(|&byte| byte == 0)
+                    .ok_or(ElfError::Malformed)?];
+                if name.is_empty() {
+                    continue;
+                }
+                symbols.push(ElfSymbol {
+                    address: reader.uint::<8>(entry, 8),
+                    size: reader.uint::<8>(entry, 16),
+                    name: self.get(String::from_utf8_lossy(name)),
+                });
+            }
+        }
+        symbols.sort_by_key(|symbol| (symbol.address, symbol.name.index()));
+        // A symbol can be in both the .symtab and .dynsym sections.
+        symbols.dedup();
+        Ok(symbols)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Builds an ELF file with a string table and a symbol table.
+    fn build_elf(little_endian: bool, symbols: &[(&str, u8, u16, u64)]) -> Vec<u8> {
+        let push = |bytes: &mut Vec<u8>, value: u64, len: usize| {
+            let value = if little_endian {
+                value.to_le_bytes()[..len].to_vec()
+            } else {
+                value.to_be_bytes()[8 - len..].to_vec()
+            };
+            bytes.extend_from_slice(&value);
+        };
+        let mut strings = vec![0];
+        let mut entries = vec![0; SYMBOL_SIZE];
+        for (name, symbol_type, section_index, address) in symbols {
+            push(&mut entries, strings.len() as u64, 4);
+            strings.extend_from_slice(name.as_bytes());
+            strings.push(0);
+            entries.extend_from_slice(&[*symbol_type, 0]);
+            push(&mut entries, *section_index as u64, 2);
+            push(&mut entries, *address, 8);
+            push(&mut entries, 16, 8);
+        }
+
+        let mut bytes = b"\x7fELF".to_vec();
+        bytes.extend_from_slice(&[2, if little_endian { 1 } else { 2 }, 1]);
+        bytes.resize(0x28, 0);
+        let section_headers_offset = 64 + strings.len() + entries.len();
+        push(&mut bytes, section_headers_offset as u64, 8);
+        bytes.resize(0x3C, 0);
+        push(&mut bytes, 3, 2);
+        bytes.resize(64, 0);
+        bytes.extend_from_slice(&strings);
+        bytes.extend_from_slice(&entries);
+
+        bytes.extend_from_slice(&[0; SECTION_HEADER_SIZE]);
+        for (section_type, offset, size, link) in [
+            (3, 64, strings.len(), 0),
+            (SHT_SYMTAB, 64 + strings.len(), entries.len(), 1),
+        ] {
+            let mut header = Vec::new();
+            push(&mut header, 0, 4);
+            push(&mut header, section_type as u64, 4);
+            push(&mut header, 0, 8);
+            push(&mut header, 0, 8);
+            push(&mut header, offset as u64, 8);
+            push(&mut header, size as u64, 8);
+            push(&mut header, link, 4);
+            header.resize(SECTION_HEADER_SIZE, 0);
+            bytes.extend_from_slice(&header);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_read_elf_symbols() {
+        for little_endian in [true, false] {
+            let bytes = build_elf(
+                little_endian,
+                &[
+                    ("main", STT_FUNC, 1, 0x2000),
+                    ("COUNTER", STT_OBJECT, 2, 0x1000),
+                    ("undefined", STT_FUNC, 0, 0),
+                    ("file.c", 4, 0xfff1, 0),
+                ],
+            );
+            let symbol_table = SymbolTable::new();
+            let symbols = symbol_table.read_elf_symbols(&bytes).unwrap();
+            let names: Vec<_> = symbols
+                .iter()
+                .map(|symbol| (symbol.address, symbol.name.str()))
+                .collect();
+            assert_eq!(names, [(0x1000, "COUNTER"), (0x2000, "main")]);
+            assert_eq!(symbols[0].size, 16);
+
+            assert_eq!(
+                symbol_table.read_elf_symbols(&bytes[..100]),
+                Err(ElfError::Malformed)
+            );
+        }
+        let symbol_table = SymbolTable::new();
+        assert_eq!(symbol_table.read_elf_symbols(b"MZ"), Err(ElfError::NotElf));
+        assert_eq!(
+            symbol_table.read_elf_symbols(b"\x7fELF\x01\x01"),
+            Err(ElfError::Unsupported)
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Enums whose variants are backed by interned names, such as the methods of a protocol
+//! or a set of keywords. See [`InternedEnum`] and [`interned_enum!`](crate::interned_enum).
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// An enum of unit variants that each have a unique name. A table that is created with
+/// [`SymbolTable::with_enum`] has the names at stable indexes, where the index of each
+/// name is the position of its variant. This is implemented by the
+/// [`interned_enum!`](crate::interned_enum) macro.
+pub trait InternedEnum: Copy + Sized + 'static {
+    /// The name of each variant, in the order of the variants.
+    const NAMES: &'static [&'static str];
+
+    /// The position of the variant, which is also the index of its name in a table
+    /// created with [`SymbolTable::with_enum`].
+    fn index(self) -> SymbolIndex;
+
+    /// The variant at a position.
+    fn from_index(index: SymbolIndex) -> Option<Self>;
+
+    /// The name of the variant.
+    fn to_str(self) -> &'static str {
+        Self::NAMES[self.index()]
+    }
+
+    /// The variant with a name.
+    fn from_str(name: &str) -> Option<Self> {
+        let index = Self::NAMES.iter().position(|other| *other == name)?;
+        Self::from_index(index)
+    }
+}
+
+/// Declares an enum of unit variants, each with a name, and implements [`InternedEnum`]
+/// for it. The enum derives `Copy` and `Clone`, and any other attributes are passed
+/// through. The names must be unique.
+///
+/// ```
+/// use gregtatum_symbol_table::{interned_enum, InternedEnum, SymbolTable};
+///
+/// interned_enum! {
+///     /// The methods of a JSON-RPC service.
+///     #[derive(Debug, PartialEq)]
+///     pub enum Method {
+///         Initialize = "initialize",
+///         Shutdown = "shutdown",
+///         DidOpen = "textDocument/didOpen",
+///     }
+/// }
+///
+/// assert_eq!(Method::DidOpen.to_str(), "textDocument/didOpen");
+/// assert_eq!(Method::from_str("shutdown"), Some(Method::Shutdown));
+/// assert_eq!(Method::from_str("exit"), None);
+///
+/// let symbol_table = SymbolTable::with_enum::<Method>();
+/// let method = symbol_table.get("textDocument/didOpen");
+/// assert_eq!(method.index(), Method::DidOpen.index());
+/// assert_eq!(method.to_enum(), Some(Method::DidOpen));
+/// ```
+#[macro_export]
+macro_rules! interned_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $string:literal),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant,)*
+        }
+
+        impl $crate::InternedEnum for $name {
+            const NAMES: &'static [&'static str] = &[$($string,)*];
+
+            fn index(self) -> $crate::SymbolIndex {
+                self as $crate::SymbolIndex
+            }
+
+            fn from_index(index: $crate::SymbolIndex) -> Option<Self> {
+                const VARIANTS: &[$name] = &[$($name::$variant,)*];
+                VARIANTS.get(index).copied()
+            }
+        }
+    };
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Creates a table that is preloaded with the names of an [`InternedEnum`], so that
+    /// the index of each name is the [`index`](InternedEnum::index) of its variant.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{interned_enum, InternedEnum, SymbolTable};
+    ///
+    /// interned_enum! {
+    ///     enum Level {
+    ///         Info = "info",
+    ///         Error = "error",
+    ///     }
+    /// }
+    ///
+    /// let symbol_table = SymbolTable::with_enum::<Level>();
+    /// assert_eq!(symbol_table.get("error").index(), Level::Error.index());
+    /// assert_eq!(symbol_table.get_enum(Level::Info), "info");
+    /// ```
+    pub fn with_enum<E: InternedEnum>() -> SymbolTable<'strings> {
+        let symbol_table = SymbolTable::new();
+        for (position, name) in E::NAMES.iter().enumerate() {
+            assert_eq!(
+                symbol_table.intern_index(*name),
+                position,
+                "The names of an InternedEnum must be unique."
+            );
+        }
+        symbol_table
+    }
+
+    /// Interns the name of an enum variant.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn get_enum<E: InternedEnum>(&'strings self, value: E) -> Symbol<'strings> {
+        self.get(value.to_str())
+    }
+}
+
+impl<'strings> Symbol<'strings> {
+    /// The enum variant with the symbol's name. When the table was created with
+    /// [`SymbolTable::with_enum`], this only compares the symbol with the variant name at
+    /// its index, rather than searching the names. The table doesn't record which enum it
+    /// was created with, so the string is still compared.
+    pub fn to_enum<E: InternedEnum>(&self) -> Option<E> {
+        let string = self.str();
+        if self.range.is_none() && E::NAMES.get(self.index) == Some(&string) {
+            return E::from_index(self.index);
+        }
+        E::from_str(string)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    crate::interned_enum! {
+        #[derive(Debug, PartialEq)]
+        enum Keyword {
+            Let = "let",
+            Fn = "fn",
+        }
+    }
+
+    #[test]
+    fn test_to_enum() {
+        // A table without the preloaded names still converts by name.
+        let symbol_table = SymbolTable::new();
+        let keyword = symbol_table.get("fn");
+        assert_eq!(keyword.index(), 0);
+        assert_eq!(keyword.to_enum(), Some(Keyword::Fn));
+        assert_eq!(symbol_table.get("let").to_enum(), Some(Keyword::Let));
+        assert_eq!(symbol_table.get("const").to_enum::<Keyword>(), None);
+
+        let slice = symbol_table.get("letter").slice(0..3).unwrap();
+        assert_eq!(slice.to_enum(), Some(Keyword::Let));
+    }
+}
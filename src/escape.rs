@@ -0,0 +1,250 @@
+//! Unescaping of string literals directly into interned [`Symbol`]s.
+
+use std::str::Chars;
+
+use crate::Symbol;
+
+impl<'strings> Symbol<'strings> {
+    /// Unescapes the contents of a JSON string literal, without the surrounding quotes,
+    /// and interns the result. The string is unescaped into a buffer that the table
+    /// reuses, so a [`String`] is only allocated when the unescaped string is new.
+    /// Returns `None` for invalid escapes.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let json = symbol_table.get(r#"{"tab\tkey": 1, "plain": 2}"#);
+    ///
+    /// let key = json.slice(2..10).unwrap();
+    /// assert_eq!(key.unescape_json().unwrap(), "tab\tkey");
+    ///
+    /// let key = json.slice(17..22).unwrap();
+    /// assert_eq!(key.unescape_json().unwrap(), "plain");
+    /// assert!(!key.unescape_json().unwrap().is_slice());
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn unescape_json(&self) -> Option<Symbol<'strings>> {
+        self.intern_unescaped(unescape_json)
+    }
+
+    /// Unescapes the contents of a Rust string literal, without the surrounding
+    /// quotes, and interns the result. Line continuations are supported. Like
+    /// [`unescape_json`](struct.Symbol.html#method.unescape_json), this only allocates
+    /// when the unescaped string is new. Returns `None` for invalid escapes.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let literal = symbol_table.get(r"caf\u{e9}\x21\n");
+    /// assert_eq!(literal.unescape_rust_literal().unwrap(), "café!\n");
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn unescape_rust_literal(&self) -> Option<Symbol<'strings>> {
+        self.intern_unescaped(unescape_rust_literal)
+    }
+
+    /// Unescapes the string into the table's buffer, and interns it if there were
+    /// escapes. The buffer is taken out of the table while it's in use, so that
+    /// interning can't observe it, and it's cleared afterwards, so that the table only
+    /// keeps the interned form of the string, which is redacted like any other.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    fn intern_unescaped(
+        &self,
+        unescape: fn(&str, &mut String) -> Option<bool>,
+    ) -> Option<Symbol<'strings>> {
+        let symbol_table = self.symbol_table;
+        let mut buffer = std::mem::take(&mut *symbol_table.unescape_buffer.borrow_mut());
+        let symbol = match unescape(self.str(), &mut buffer) {
+            Some(true) => Some(symbol_table.get(buffer.as_str())),
+            Some(false) => Some(self.deslice()),
+            None => None,
+        };
+        buffer.clear();
+        *symbol_table.unescape_buffer.borrow_mut() = buffer;
+        symbol
+    }
+}
+
+/// Unescapes a JSON string, see [`unescape`].
+pub(crate) fn unescape_json(string: &str, unescaped: &mut String) -> Option<bool> {
+    unescape(string, unescaped, |chars, unescaped| {
+        let ch = match chars.next()? {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let high = parse_hex(chars, 4)?;
+                if (0xD800..0xDC00).contains(&high) {
+                    // This is a surrogate pair.
+                    if chars.next()? != '\\' || chars.next()? != 'u' {
+                        return None;
+                    }
+                    let low = parse_hex(chars, 4)?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return None;
+                    }
+                    char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))?
+                } else {
+                    char::from_u32(high)?
+                }
+            }
+            _ => return None,
+        };
+        unescaped.push(ch);
+        Some(())
+    })
+}
+
+/// Unescapes a Rust string literal, see [`unescape`].
+pub(crate) fn unescape_rust_literal(string: &str, unescaped: &mut String) -> Option<bool> {
+    unescape(string, unescaped, |chars, unescaped| {
+        let ch = match chars.next()? {
+            '"' => '"',
+            '\'' => '\'',
+            '\\' => '\\',
+            '0' => '\0',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'x' => {
+                let value = parse_hex(chars, 2)?;
+                if value > 0x7F {
+                    return None;
+                }
+                char::from_u32(value)?
+            }
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+                let mut value: u32 = 0;
+                let mut digits = 0;
+                loop {
+                    match chars.next()? {
+                        '}' if digits > 0 => break,
+                        '_' => {}
+                        ch => {
+                            value = value.checked_mul(16)? + ch.to_digit(16)?;
+                            digits += 1;
+                            if digits > 6 {
+                                return None;
+                            }
+                        }
+                    }
+                }
+                char::from_u32(value)?
+            }
+            '\n' => {
+                // A line continuation skips the newline and any leading whitespace.
+                let rest = chars.as_str();
+                *chars = rest.trim_start().chars();
+                return Some(());
+            }
+            _ => return None,
+        };
+        unescaped.push(ch);
+        Some(())
+    })
+}
+
+/// Appends the unescaped string to a buffer, running the `escape` function after every
+/// backslash to push the unescaped chars. Returns `Some(false)` without appending
+/// anything if there are no escapes, as the string is then its own unescaped form.
+fn unescape<F>(string: &str, unescaped: &mut String, mut escape: F) -> Option<bool>
+where
+    F: FnMut(&mut Chars, &mut String) -> Option<()>,
+{
+    let Some(first_escape) = string.find('\\') else {
+        return Some(false);
+    };
+    unescaped.reserve(string.len());
+    unescaped.push_str(&string[..first_escape]);
+    let mut chars = string[first_escape..].chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            escape(&mut chars, unescaped)?;
+        } else {
+            unescaped.push(ch);
+        }
+    }
+    Some(true)
+}
+
+fn parse_hex(chars: &mut Chars, digits: usize) -> Option<u32> {
+    let mut value = 0;
+    for _ in 0..digits {
+        value = value * 16 + chars.next()?.to_digit(16)?;
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Unescapes a JSON string by interning it into a table, or returns `None` for
+    /// invalid escapes.
+    fn json(string: &str) -> Option<String> {
+        let symbol_table = crate::SymbolTable::new();
+        let unescaped = symbol_table.get(string).unescape_json()?;
+        Some(unescaped.into())
+    }
+
+    /// Unescapes a Rust string literal like [`json`].
+    fn rust_literal(string: &str) -> Option<String> {
+        let symbol_table = crate::SymbolTable::new();
+        let unescaped = symbol_table.get(string).unescape_rust_literal()?;
+        Some(unescaped.into())
+    }
+
+    #[test]
+    fn test_unescape_json() {
+        assert_eq!(json(r"plain").unwrap(), "plain");
+        assert_eq!(unescape_json(r"plain", &mut String::new()), Some(false));
+        assert_eq!(
+            json(r#"\"\\\/\b\f\n\r\t"#).unwrap(),
+            "\"\\/\u{8}\u{c}\n\r\t"
+        );
+        assert_eq!(json(r"é😀").unwrap(), "é😀");
+        assert_eq!(json(r"\ud83d"), None, "Unpaired surrogate");
+        assert_eq!(json(r"\x41"), None);
+        assert_eq!(json(r"trailing\"), None);
+
+        // Unescaping into a string that the table already has doesn't store a new one.
+        let symbol_table = crate::SymbolTable::new();
+        let key = symbol_table.get("tab\tkey");
+        let escaped = symbol_table.get(r"tab\tkey");
+        let len = symbol_table.len();
+        assert_eq!(escaped.unescape_json(), Some(key));
+        assert_eq!(escaped.unescape_rust_literal(), Some(key));
+        assert_eq!(symbol_table.len(), len);
+        assert_eq!(symbol_table.get(r"\q").unescape_json(), None);
+
+        // Unescaped strings are redacted, and aren't kept in the buffer.
+        symbol_table.redact_namespace("user:", 7);
+        let user = symbol_table
+            .get(r"user:\u0061lice")
+            .unescape_json()
+            .unwrap();
+        assert!(user.str().starts_with("user:…"));
+        assert!(symbol_table.unescape_buffer.borrow().is_empty());
+        assert!(!symbol_table.iter().any(|string| string.contains("alice")));
+    }
+
+    #[test]
+    fn test_unescape_rust_literal() {
+        assert_eq!(rust_literal(r"\'\0\x41\u{1F_600}").unwrap(), "'\0A😀");
+        assert_eq!(rust_literal("a\\\n    b").unwrap(), "ab");
+        assert_eq!(rust_literal(r"\x80"), None);
+        assert_eq!(rust_literal(r"\u{}"), None);
+        assert_eq!(rust_literal(r"\u{110000}"), None);
+        assert_eq!(rust_literal(r"\q"), None);
+    }
+}
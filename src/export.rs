@@ -0,0 +1,192 @@
+//! Exporting the strings of the table into plain buffers and collections, for libraries
+//! that need them, and building tables from them again.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{SymbolIndex, SymbolTable};
+
+/// The error for a list of strings that can't be made into a table with the same
+/// indexes, as a string is in it twice, see
+/// [`SymbolTable::from_vec`](struct.SymbolTable.html#method.from_vec).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateStringError {
+    /// The index of the second copy of the string.
+    pub index: SymbolIndex,
+    /// The index of the first copy of the string.
+    pub existing: SymbolIndex,
+}
+
+impl fmt::Display for DuplicateStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The string at index {} is a duplicate of the string at index {}.",
+            self.index, self.existing
+        )
+    }
+}
+
+impl std::error::Error for DuplicateStringError {}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Concatenates every string, in index order, into a single buffer, along with the
+    /// offsets of the strings, where the string at index `i` is the bytes from
+    /// `offsets[i]` to `offsets[i + 1]`. The buffer contains every distinct string once,
+    /// which makes it a good sample for training or seeding the dictionary of a
+    /// compression codec, like zstd or deflate. The offsets can be used to trim the
+    /// buffer at a string boundary, as deflate only uses the last 32 KiB of a dictionary.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// for string in ["GET", "POST", "/api/users"] {
+    ///     symbol_table.get(string);
+    /// }
+    ///
+    /// let (data, offsets) = symbol_table.as_dictionary_bytes();
+    /// assert_eq!(data, b"GETPOST/api/users");
+    /// assert_eq!(offsets, [0, 3, 7, 17]);
+    /// assert_eq!(&data[offsets[1]..offsets[2]], b"POST");
+    /// ```
+    pub fn as_dictionary_bytes(&self) -> (Vec<u8>, Vec<usize>) {
+        let mut data = Vec::new();
+        let mut offsets = Vec::with_capacity(self.len() + 1);
+        offsets.push(0);
+        for string in self.iter() {
+            data.extend_from_slice(string.as_bytes());
+            offsets.push(data.len());
+        }
+        (data, offsets)
+    }
+
+    /// Copies every string into a `Vec`, where the position of each string is its
+    /// index.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("a");
+    /// symbol_table.get("b");
+    /// assert_eq!(symbol_table.to_vec(), ["a", "b"]);
+    /// assert_eq!(symbol_table.to_str_vec(), ["a", "b"]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<String> {
+        self.iter().map(str::to_owned).collect()
+    }
+
+    /// Collects every string into a `Vec` that borrows from the table, where the
+    /// position of each string is its index.
+    pub fn to_str_vec(&self) -> Vec<&str> {
+        self.iter().collect()
+    }
+
+    /// Creates a table from a list of strings, where each string's index is its position
+    /// in the list, which is the inverse of
+    /// [`to_vec`](struct.SymbolTable.html#method.to_vec). The strings are moved into the
+    /// table rather than copied. This fails if a string is in the list twice, as the
+    /// indexes then can't be kept.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{DuplicateStringError, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("a");
+    /// symbol_table.get("b");
+    ///
+    /// let copy = SymbolTable::from_vec(symbol_table.to_vec()).unwrap();
+    /// assert_eq!(copy, symbol_table);
+    ///
+    /// let strings = vec!["a".to_string(), "b".to_string(), "a".to_string()];
+    /// assert_eq!(
+    ///     SymbolTable::from_vec(strings).err(),
+    ///     Some(DuplicateStringError { index: 2, existing: 0 })
+    /// );
+    /// ```
+    pub fn from_vec(strings: Vec<String>) -> Result<SymbolTable<'strings>, DuplicateStringError> {
+        let symbol_table = SymbolTable::new();
+        for (index, string) in strings.into_iter().enumerate() {
+            if let Some(existing) = symbol_table.lookup_index(&string) {
+                return Err(DuplicateStringError { index, existing });
+            }
+            symbol_table.push_string(string);
+        }
+        Ok(symbol_table)
+    }
+
+    /// Copies every string into a `HashMap` from the string to its index.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("a");
+    /// symbol_table.get("b");
+    /// assert_eq!(symbol_table.to_index_map()["b"], 1);
+    /// assert_eq!(symbol_table.to_str_index_map()["a"], 0);
+    /// ```
+    pub fn to_index_map(&self) -> HashMap<String, u32> {
+        self.iter()
+            .enumerate()
+            .map(|(index, string)| (string.to_owned(), index_to_u32(index)))
+            .collect()
+    }
+
+    /// Collects every string into a `HashMap` that borrows from the table, from the
+    /// string to its index.
+    pub fn to_str_index_map(&self) -> HashMap<&str, u32> {
+        self.iter()
+            .enumerate()
+            .map(|(index, string)| (string, index_to_u32(index)))
+            .collect()
+    }
+}
+
+/// Converts an index to a `u32`, which can't fail as a table holds at most
+/// `u32::MAX + 1` strings.
+fn index_to_u32(index: SymbolIndex) -> u32 {
+    u32::try_from(index).expect("A SymbolTable can hold at most u32::MAX + 1 strings.")
+}
+
+/// Interns the strings in order, so that collecting the strings of a table, such as the
+/// ones from [`to_vec`](struct.SymbolTable.html#method.to_vec), gives them the same
+/// indexes. Unlike [`SymbolTable::from_vec`](struct.SymbolTable.html#method.from_vec),
+/// duplicates are interned once.
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTable;
+///
+/// let mut symbol_table: SymbolTable = ["a", "b", "a"].into_iter().collect();
+/// symbol_table.extend(["c"]);
+/// assert_eq!(symbol_table.to_vec(), ["a", "b", "c"]);
+/// ```
+impl<'strings, T: Into<String> + AsRef<str>> FromIterator<T> for SymbolTable<'strings> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut symbol_table = SymbolTable::new();
+        symbol_table.extend(iter);
+        symbol_table
+    }
+}
+
+impl<'strings, T: Into<String> + AsRef<str>> Extend<T> for SymbolTable<'strings> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for string in iter {
+            self.intern_index(string);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_empty_dictionary_bytes() {
+        let symbol_table = SymbolTable::new();
+        assert_eq!(symbol_table.as_dictionary_bytes(), (vec![], vec![0]));
+        symbol_table.get("");
+        assert_eq!(symbol_table.as_dictionary_bytes(), (vec![], vec![0, 0]));
+    }
+}
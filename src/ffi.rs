@@ -0,0 +1,137 @@
+//! Interop with C strings and UTF-16, so that interned names can be passed to C APIs,
+//! Windows APIs, or JS engines without re-encoding them for every call.
+
+use std::ffi::{CStr, CString};
+
+use crate::{Symbol, SymbolTable};
+
+impl<'strings> SymbolTable<'strings> {
+    /// Interns a C string, and keeps a NUL-terminated copy of the interned string in the
+    /// table, so that [`Symbol::as_cstr`] is free for the returned symbol. The copy is
+    /// made from the stored string rather than the argument, so strings in a
+    /// [redacted namespace](struct.SymbolTable.html#method.redact_namespace) stay
+    /// redacted. Returns `None` if the string isn't valid UTF-8.
+    ///
+    /// ```
+    /// use std::ffi::CStr;
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let name = symbol_table.get_cstr(c"glDrawArrays").unwrap();
+    /// assert_eq!(name, "glDrawArrays");
+    /// assert_eq!(name.as_cstr(), Some(c"glDrawArrays"));
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn get_cstr(&'strings self, string: &CStr) -> Option<Symbol<'strings>> {
+        let symbol = self.get(string.to_str().ok()?);
+        symbol.as_cstr();
+        Some(symbol)
+    }
+}
+
+impl<'strings> Symbol<'strings> {
+    /// Returns the string as a NUL-terminated C string that lives as long as the table.
+    /// The first call for a string stores a NUL-terminated copy of it in the table, and
+    /// later calls reuse it. Slices can only be converted if they extend to the end of
+    /// the string. Returns `None` if the string contains a NUL byte, as it can't be
+    /// represented as a C string.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let path = symbol_table.get("std::ffi::CStr");
+    /// assert_eq!(path.as_cstr(), Some(c"std::ffi::CStr"));
+    ///
+    /// // Suffixes share the storage of the full string.
+    /// let name = path.slice(10..14).unwrap();
+    /// assert_eq!(name.as_cstr(), Some(c"CStr"));
+    ///
+    /// let prefix = path.slice(0..3).unwrap();
+    /// assert_eq!(prefix.as_cstr(), None);
+    ///
+    /// let nul = symbol_table.get("nul\0byte");
+    /// assert_eq!(nul.as_cstr(), None);
+    /// ```
+    pub fn as_cstr(&self) -> Option<&'strings CStr> {
+        let range = self.range();
+        let parent = self.symbol_table.str(self.index);
+        if range.end != parent.len() {
+            return None;
+        }
+        let cstring = match self.symbol_table.cstrings.get(&self.index) {
+            Some(cstring) => cstring,
+            None => self
+                .symbol_table
+                .cstrings
+                .insert(self.index, CString::new(parent).ok()?),
+        };
+        CStr::from_bytes_with_nul(&cstring.to_bytes_with_nul()[range.start..]).ok()
+    }
+}
+
+impl<'strings> Symbol<'strings> {
+    /// Returns the string encoded as UTF-16, which lives as long as the table. The
+    /// first call for a symbol encodes the string and caches it in the table, so that
+    /// later calls don't need to re-encode it. Slices are cached separately from their
+    /// parents.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let name = symbol_table.get("Ünïcödé");
+    /// let utf16 = name.to_utf16_cached();
+    /// assert_eq!(String::from_utf16(utf16).unwrap(), "Ünïcödé");
+    /// assert!(std::ptr::eq(utf16, name.to_utf16_cached()));
+    /// ```
+    pub fn to_utf16_cached(&self) -> &'strings [u16] {
+        let key = self.key();
+        match self.symbol_table.utf16.get(&key) {
+            Some(utf16) => utf16,
+            None => self
+                .symbol_table
+                .utf16
+                .insert(key, self.str().encode_utf16().collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cstr_storage_is_reused() {
+        let symbol_table = SymbolTable::new();
+        let hello = symbol_table.get("hello");
+        let a = hello.as_cstr().unwrap();
+        let b = hello.as_cstr().unwrap();
+        assert!(std::ptr::eq(a, b));
+
+        let c = symbol_table.get_cstr(c"hello").unwrap().as_cstr().unwrap();
+        assert!(std::ptr::eq(a, c));
+
+        let empty = symbol_table.get("");
+        assert_eq!(empty.as_cstr(), Some(c""));
+        assert_eq!(hello.slice(5..5).unwrap().as_cstr(), Some(c""));
+        assert_eq!(symbol_table.get_cstr(c"\xff"), None);
+
+        // The stored C string is redacted too.
+        symbol_table.redact_namespace("user:", 7);
+        let user = symbol_table.get_cstr(c"user:alice@example.com").unwrap();
+        let cstr = user.as_cstr().unwrap();
+        assert_eq!(cstr.to_str().unwrap(), user.str());
+        assert!(!cstr.to_str().unwrap().contains("alice"));
+    }
+
+    #[test]
+    fn test_utf16_slices() {
+        let symbol_table = SymbolTable::new();
+        let emoji = symbol_table.get("a😀b");
+        assert_eq!(emoji.to_utf16_cached().len(), 4);
+        let b = emoji.slice(5..6).unwrap();
+        assert_eq!(b.to_utf16_cached(), [b'b' as u16]);
+        assert_eq!(emoji.to_utf16_cached().len(), 4);
+    }
+}
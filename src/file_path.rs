@@ -0,0 +1,194 @@
+//! Interning of file paths, where different spellings of the same path are interned as
+//! a single symbol.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::{Symbol, SymbolTable};
+
+/// Canonicalizes a path after it has been normalized, see
+/// [`SymbolTable::set_path_canonicalizer`].
+pub(crate) type PathCanonicalizer = Box<dyn Fn(&Path) -> PathBuf>;
+
+/// How [`SymbolTable::get_file_path`] compares the case of paths, see
+/// [`set_path_case`](struct.SymbolTable.html#method.set_path_case).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PathCase {
+    /// Paths that differ in case are different paths, like on most Unix file systems.
+    #[default]
+    Sensitive,
+    /// Paths that only differ in case are the same path, which keeps the case of the
+    /// first spelling that was interned, like NTFS and APFS.
+    Insensitive,
+}
+
+/// Normalizes a path lexically, without touching the file system, by removing `.`
+/// components and resolving `..` components against the components before them. A `..`
+/// at the start of a relative path is kept, and a `..` after the root is dropped. This
+/// doesn't resolve symbolic links, so `a/link/..` becomes `a` even when `link` points
+/// elsewhere.
+///
+/// ```
+/// use std::path::Path;
+/// use gregtatum_symbol_table::normalize_path;
+///
+/// assert_eq!(normalize_path(Path::new("src/./lib/../main.rs")), Path::new("src/main.rs"));
+/// assert_eq!(normalize_path(Path::new("../a/b/..")), Path::new("../a"));
+/// assert_eq!(normalize_path(Path::new("/..")), Path::new("/"));
+/// assert_eq!(normalize_path(Path::new("a/..")), Path::new("."));
+/// ```
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir) => {}
+                _ => normalized.push(".."),
+            },
+            component => normalized.push(component),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        normalized.push(".");
+    }
+    normalized
+}
+
+/// Folds the case of a path like NTFS, which compares the uppercase of each character.
+/// Only characters with a single uppercase character are folded, so `ß` isn't `SS`.
+fn fold_case(path: &str) -> String {
+    path.chars()
+        .map(|char| {
+            let mut upper = char.to_uppercase();
+            match upper.len() {
+                1 => upper.next().unwrap_or(char),
+                _ => char,
+            }
+        })
+        .collect()
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Interns a file path after normalizing it with [`normalize_path`], and then with
+    /// the canonicalizer from
+    /// [`set_path_canonicalizer`](struct.SymbolTable.html#method.set_path_canonicalizer)
+    /// if there is one, so that different spellings of a path are the same symbol. Paths
+    /// that aren't valid UTF-8 are interned lossily.
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let a = symbol_table.get_file_path(Path::new("src/lib.rs"));
+    /// let b = symbol_table.get_file_path(Path::new("./src/../src/lib.rs"));
+    /// assert_eq!(a, b);
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn get_file_path(&'strings self, path: &Path) -> Symbol<'strings> {
+        let mut path = normalize_path(path);
+        if let Some(canonicalizer) = &*self.path_canonicalizer.borrow() {
+            path = canonicalizer(&path);
+        }
+        let string = match path.into_os_string().into_string() {
+            Ok(string) => string,
+            Err(os_string) => os_string.to_string_lossy().into_owned(),
+        };
+        if self.path_case.get() == PathCase::Sensitive {
+            return self.get(string);
+        }
+        if let Some(&index) = self.folded_paths.borrow().get(&fold_case(&string)) {
+            return Symbol::new(self, index);
+        }
+        let symbol = self.get(string);
+        // The stored string is folded, rather than the argument, so that redacted paths
+        // aren't kept.
+        self.folded_paths
+            .borrow_mut()
+            .insert(fold_case(symbol.str()), symbol.index());
+        symbol
+    }
+
+    /// Sets whether [`get_file_path`](struct.SymbolTable.html#method.get_file_path)
+    /// compares paths case insensitively. Insensitive paths keep the case of their
+    /// first spelling, so they display the way that they were first written. This
+    /// should be set before any paths are interned, as paths are only matched against
+    /// the spellings that were interned while paths were insensitive. Strings that are
+    /// interned directly, such as with [`get`](struct.SymbolTable.html#method.get), are
+    /// always case sensitive.
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use gregtatum_symbol_table::{PathCase, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.set_path_case(PathCase::Insensitive);
+    /// let a = symbol_table.get_file_path(Path::new("Program Files/App/README.md"));
+    /// let b = symbol_table.get_file_path(Path::new("program files/app/readme.md"));
+    /// assert_eq!(a, b);
+    /// assert_eq!(b, "Program Files/App/README.md");
+    /// ```
+    pub fn set_path_case(&self, path_case: PathCase) {
+        self.path_case.set(path_case);
+    }
+
+    /// Sets a function that canonicalizes paths for
+    /// [`get_file_path`](struct.SymbolTable.html#method.get_file_path), after they have
+    /// been normalized. This can make paths absolute, resolve symbolic links with
+    /// [`std::fs::canonicalize`], or normalize the case of paths on file systems that
+    /// are case insensitive.
+    ///
+    /// ```
+    /// use std::path::{Path, PathBuf};
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.set_path_canonicalizer(|path| Path::new("/project").join(path));
+    /// assert_eq!(symbol_table.get_file_path(Path::new("./Cargo.toml")), "/project/Cargo.toml");
+    /// ```
+    pub fn set_path_canonicalizer(&self, canonicalizer: impl Fn(&Path) -> PathBuf + 'static) {
+        *self.path_canonicalizer.borrow_mut() = Some(Box::new(canonicalizer));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_case_canonicalizer() {
+        let symbol_table = SymbolTable::new();
+        symbol_table.set_path_canonicalizer(|path| path.to_string_lossy().to_lowercase().into());
+        let a = symbol_table.get_file_path(Path::new("C/Users/../Windows"));
+        let b = symbol_table.get_file_path(Path::new("c/windows/."));
+        assert_eq!(a, b);
+        assert_eq!(a, "c/windows");
+    }
+
+    #[test]
+    fn test_insensitive_paths_are_remapped() {
+        let mut symbol_table = SymbolTable::new();
+        symbol_table.set_path_case(PathCase::Insensitive);
+        symbol_table.get("other");
+        symbol_table.get_file_path(Path::new("Straße"));
+        symbol_table.get_file_path(Path::new("STRAßE"));
+        symbol_table.get("Straße");
+        let remapping = symbol_table.reindex_by_frequency();
+        assert_eq!(remapping.get(1), Some(0));
+        assert_eq!(symbol_table.get_file_path(Path::new("straße")), "Straße");
+        // NTFS doesn't fold ß to SS.
+        assert_eq!(symbol_table.get_file_path(Path::new("STRASSE")), "STRASSE");
+
+        // Redacted paths are only kept in their redacted form.
+        symbol_table.redact_namespace("Users/", 1);
+        symbol_table.get_file_path(Path::new("Users/Alice"));
+        assert!(!symbol_table
+            .folded_paths
+            .borrow()
+            .keys()
+            .any(|path| path.contains("ALICE")));
+    }
+}
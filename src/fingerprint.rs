@@ -0,0 +1,122 @@
+//! Fingerprints of the contents of a table, for checking whether two tables are
+//! equivalent without comparing them string by string.
+
+use crate::SymbolTable;
+
+const FNV_OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+const FNV_PRIME: u128 = 0x0000000001000000000000000000013b;
+
+/// A 128-bit FNV-1a hash, which is stable across builds and platforms.
+struct Fnv128(u128);
+
+impl Fnv128 {
+    fn new() -> Fnv128 {
+        Fnv128(FNV_OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u128::from(byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Writes a string along with its length, so that the boundaries between strings
+    /// are part of the hash.
+    fn write_str(&mut self, string: &str) {
+        self.write(&(string.len() as u64).to_le_bytes());
+        self.write(string.as_bytes());
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// A 128-bit hash of every string and its index, which build systems can compare to
+    /// quickly check whether two tables are the same. Tables with the same strings at
+    /// the same indexes have the same fingerprint, on any platform and in any build.
+    /// This visits every string. See
+    /// [`unordered_fingerprint`](struct.SymbolTable.html#method.unordered_fingerprint)
+    /// for a fingerprint that ignores the indexes.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let a = SymbolTable::new();
+    /// let b = SymbolTable::new();
+    /// for string in ["main", "args"] {
+    ///     a.get(string);
+    ///     b.get(string);
+    /// }
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    ///
+    /// b.get("env");
+    /// assert_ne!(a.fingerprint(), b.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u128 {
+        let mut hash = Fnv128::new();
+        hash.write(&(self.len() as u64).to_le_bytes());
+        for string in self.iter() {
+            hash.write_str(string);
+        }
+        hash.0
+    }
+
+    /// A 128-bit hash of the set of strings, which doesn't depend on the order that they
+    /// were interned in, see [`fingerprint`](struct.SymbolTable.html#method.fingerprint).
+    /// Each string is hashed on its own, and the hashes are summed, so this doesn't need
+    /// to sort the strings.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let a = SymbolTable::new();
+    /// let b = SymbolTable::new();
+    /// for string in ["main", "args"] {
+    ///     a.get(string);
+    /// }
+    /// for string in ["args", "main"] {
+    ///     b.get(string);
+    /// }
+    /// assert_ne!(a.fingerprint(), b.fingerprint());
+    /// assert_eq!(a.unordered_fingerprint(), b.unordered_fingerprint());
+    /// ```
+    pub fn unordered_fingerprint(&self) -> u128 {
+        self.iter().fold(0, |sum: u128, string| {
+            let mut hash = Fnv128::new();
+            hash.write_str(string);
+            sum.wrapping_add(hash.0)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fingerprints_are_stable() {
+        let mut hash = Fnv128::new();
+        hash.write(b"a");
+        // The published FNV-1a 128 hash of "a".
+        assert_eq!(hash.0, 0xd228cb696f1a8caf78912b704e4a8964);
+
+        let symbol_table = SymbolTable::new();
+        let empty = symbol_table.fingerprint();
+        symbol_table.get("");
+        assert_ne!(symbol_table.fingerprint(), empty);
+        assert_ne!(symbol_table.unordered_fingerprint(), 0);
+
+        // The string boundaries are part of the fingerprint.
+        let [ab, a_b] = [&["ab"][..], &["a", "b"]].map(|strings| {
+            let symbol_table = SymbolTable::new();
+            for string in strings {
+                symbol_table.get(*string);
+            }
+            (
+                symbol_table.fingerprint(),
+                symbol_table.unordered_fingerprint(),
+            )
+        });
+        assert_ne!(ab.0, a_b.0);
+        assert_ne!(ab.1, a_b.1);
+    }
+}
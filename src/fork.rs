@@ -0,0 +1,226 @@
+//! Forks of a table, which share the strings of their parent rather than copying them.
+
+use std::cell::RefCell;
+
+use elsa::{FrozenMap, FrozenVec};
+use fxhash::{FxBuildHasher, FxHashMap};
+
+use crate::{Delta, Mark, SymbolIndex, SymbolTable};
+
+/// A copy-on-write fork of a [`SymbolTable`], created by
+/// [`SymbolTable::fork`](struct.SymbolTable.html#method.fork). The fork shares the
+/// strings that its parent had when it was forked, and only stores the strings that are
+/// interned into it afterwards, so forking costs the same no matter how large the
+/// parent is. This makes forks cheap enough for speculative work, such as compiling a
+/// branch that could be thrown away.
+///
+/// The new strings of a fork get the indexes after the parent's, so a fork can be
+/// committed back into its parent with
+/// [`SymbolTable::apply_delta`](struct.SymbolTable.html#method.apply_delta), as long as
+/// the parent hasn't interned anything since the fork. Strings that the parent interns
+/// after the fork aren't visible to the fork.
+///
+/// Cloning a fork is copy-on-write in the same way, as the clone shares the parent's
+/// strings and only copies the strings of the fork. Strings in the parent's
+/// [redacted namespaces](struct.SymbolTable.html#method.redact_namespace) are redacted
+/// by the fork too.
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTable;
+///
+/// let symbol_table = SymbolTable::new();
+/// let main = symbol_table.get("main");
+///
+/// let fork = symbol_table.fork();
+/// assert_eq!(fork.intern("main"), main.index());
+/// let branch = fork.intern("speculative");
+/// assert_eq!(fork.resolve(branch), Some("speculative"));
+/// assert_eq!(symbol_table.maybe_get("speculative"), None);
+///
+/// // Keep the branch by applying its strings to the parent.
+/// symbol_table.apply_delta(&fork.delta()).unwrap();
+/// assert_eq!(symbol_table.get("speculative").index(), branch);
+/// ```
+pub struct ForkedSymbolTable<'a> {
+    parent: &'a SymbolTable<'a>,
+    // The number of strings that the parent had when it was forked.
+    base_len: usize,
+    strings: FrozenVec<String>,
+    indexes: FrozenMap<String, Box<SymbolIndex>, FxBuildHasher>,
+    // The indexes of the redacted strings, by their salted hashes.
+    redacted: RefCell<FxHashMap<u128, SymbolIndex>>,
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Forks the table, to intern strings without adding them to it. See
+    /// [`ForkedSymbolTable`].
+    pub fn fork(&self) -> ForkedSymbolTable<'_> {
+        ForkedSymbolTable {
+            parent: self,
+            base_len: self.len(),
+            strings: FrozenVec::new(),
+            indexes: FrozenMap::default(),
+            redacted: RefCell::default(),
+        }
+    }
+}
+
+impl<'a> ForkedSymbolTable<'a> {
+    /// Interns a string, and returns its index. Strings that the parent had when it was
+    /// forked keep their indexes, and new strings are only added to the fork.
+    pub fn intern(&self, string: &str) -> SymbolIndex {
+        if let Some(index) = self.lookup(string) {
+            return index;
+        }
+        let index = self.len();
+        match self.parent.redact(string) {
+            Some((hash, prefix)) => {
+                let display = self
+                    .parent
+                    .redacted_display(hash, &prefix, |display| self.is_taken(display));
+                self.strings.push(display);
+                self.redacted.borrow_mut().insert(hash, index);
+            }
+            None => {
+                self.strings.push(string.to_owned());
+                self.indexes.insert(string.to_owned(), Box::new(index));
+            }
+        }
+        index
+    }
+
+    /// Looks up the index of a string without interning it.
+    pub fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        if let Some(index) = self
+            .parent
+            .lookup_index(string)
+            .filter(|&index| index < self.base_len)
+        {
+            return Some(index);
+        }
+        match self.parent.redact(string) {
+            Some((hash, _)) => self.redacted.borrow().get(&hash).copied(),
+            None => self.indexes.get(string).copied(),
+        }
+    }
+
+    /// Returns true if a redacted display form is already a string of the fork.
+    fn is_taken(&self, display: &str) -> bool {
+        self.indexes.get(display).is_some()
+            || self
+                .redacted
+                .borrow()
+                .values()
+                .any(|&index| self.resolve(index) == Some(display))
+    }
+
+    /// The string for an index, or `None` if it's out of range.
+    pub fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        match index.checked_sub(self.base_len) {
+            None => self.parent.get_by_index(index),
+            Some(index) => self.strings.get(index),
+        }
+    }
+
+    /// The number of strings, including the ones that are shared with the parent.
+    pub fn len(&self) -> usize {
+        self.base_len + self.strings.len()
+    }
+
+    /// Returns true if the fork has no strings, including the ones that are shared with
+    /// the parent.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The table that the fork was forked from.
+    pub fn parent(&self) -> &'a SymbolTable<'a> {
+        self.parent
+    }
+
+    /// The point in the parent's history where it was forked.
+    pub fn mark(&self) -> Mark {
+        Mark::at(self.base_len)
+    }
+
+    /// Iterates over the strings that were interned into the fork, in order.
+    pub fn additions(&self) -> impl Iterator<Item = &str> {
+        self.strings.iter()
+    }
+
+    /// The strings that were interned into the fork, as a delta from the point where
+    /// the parent was forked.
+    pub fn delta(&self) -> Delta {
+        Delta::new(self.base_len, self.additions().map(str::to_owned).collect())
+    }
+}
+
+impl Clone for ForkedSymbolTable<'_> {
+    fn clone(&self) -> Self {
+        let fork = self.parent.fork();
+        let fork = ForkedSymbolTable {
+            base_len: self.base_len,
+            redacted: self.redacted.clone(),
+            ..fork
+        };
+        for (offset, string) in self.strings.iter().enumerate() {
+            let index = self.base_len + offset;
+            fork.strings.push(string.to_owned());
+            if self.indexes.get(string) == Some(&index) {
+                fork.indexes.insert(string.to_owned(), Box::new(index));
+            }
+        }
+        fork
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_forks_are_independent() {
+        let symbol_table = SymbolTable::new();
+        symbol_table.get("shared");
+        let a = symbol_table.fork();
+        let b = symbol_table.fork();
+        assert_eq!(a.intern("a"), 1);
+        assert_eq!(b.intern("b"), 1);
+        assert_eq!(a.lookup("b"), None);
+        assert_eq!(b.resolve(1), Some("b"));
+
+        // The parent's strings after the fork aren't shared.
+        symbol_table.get("late");
+        assert_eq!(a.lookup("late"), None);
+        assert_eq!(a.intern("late"), 2);
+        assert_eq!(a.resolve(2), Some("late"));
+        assert_eq!(a.resolve(3), None);
+        assert_eq!(a.len(), 3);
+        assert_eq!(a.additions().collect::<Vec<_>>(), ["a", "late"]);
+        assert!(symbol_table.apply_delta(&a.delta()).is_err());
+
+        // Clones only copy the strings of the fork.
+        let c = a.clone();
+        assert_eq!(c.intern("c"), 3);
+        assert_eq!(c.lookup("a"), Some(1));
+        assert_eq!(a.lookup("c"), None);
+    }
+
+    #[test]
+    fn test_forks_are_redacted() {
+        let symbol_table = SymbolTable::new();
+        symbol_table.redact_namespace("user:", 7);
+        let fork = symbol_table.fork();
+        let alice = fork.intern("user:alice");
+        assert_eq!(fork.intern("user:alice"), alice);
+        assert_eq!(fork.clone().lookup("user:alice"), Some(alice));
+        assert_ne!(fork.intern("user:bob"), alice);
+        assert!(fork.resolve(alice).unwrap().starts_with("user:…"));
+        assert!(!fork.additions().any(|string| string.contains("alice")));
+        assert!(!fork
+            .delta()
+            .strings()
+            .iter()
+            .any(|string| string.contains("alice")));
+    }
+}
@@ -0,0 +1,222 @@
+//! Interning of stack frames, for profilers that record many stacks where the same
+//! functions and files repeat heavily.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use fxhash::FxHashMap;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// A frame of a stack: a function, the file that it is in, and a line in the file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StackFrame<'strings> {
+    /// The name of the function.
+    pub function: Symbol<'strings>,
+    /// The path of the file.
+    pub file: Symbol<'strings>,
+    /// The line in the file, starting at 1, or 0 if it's unknown.
+    pub line: u32,
+}
+
+/// The identifier of a frame in a [`FrameTable`]. The identifiers of a table form the
+/// dense range `0..len`, in the order that the frames were first interned.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FrameId(u32);
+
+impl FrameId {
+    /// The identifier as a `u32`.
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    /// The identifier as an index, for indexing into arrays.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for FrameId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+type FrameKey = (SymbolIndex, SymbolIndex, u32);
+
+/// Interns frames, which are stored as the indexes of their function and file, so that
+/// each distinct frame is stored once and can be referred to by a compact [`FrameId`].
+///
+/// ```
+/// use gregtatum_symbol_table::{FrameTable, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// let mut frames = FrameTable::new(&symbol_table);
+/// let main = frames.intern("main", "src/main.rs", 3);
+/// let parse = frames.intern("parse", "src/parse.rs", 10);
+/// assert_eq!(frames.intern("main", "src/main.rs", 3), main);
+///
+/// let frame = frames.get(parse).unwrap();
+/// assert_eq!(frame.function, "parse");
+/// assert_eq!(frame.line, 10);
+///
+/// // Stacks are lists of frame ids, from the root to the leaf.
+/// let stacks = [(vec![main, parse], 3), (vec![main], 1), (vec![main, parse], 2)];
+/// assert_eq!(frames.fold(&stacks), "main 1\nmain;parse 5\n");
+/// ```
+pub struct FrameTable<'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    frames: Vec<FrameKey>,
+    ids: FxHashMap<FrameKey, FrameId>,
+}
+
+impl<'strings> FrameTable<'strings> {
+    /// Creates an empty table that interns the strings of frames into the symbol table.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        FrameTable {
+            symbol_table,
+            frames: Vec::new(),
+            ids: FxHashMap::default(),
+        }
+    }
+
+    /// Interns a frame from its strings.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn intern(&mut self, function: &str, file: &str, line: u32) -> FrameId {
+        let function = self.symbol_table.get(function).index();
+        let file = self.symbol_table.get(file).index();
+        self.intern_key((function, file, line))
+    }
+
+    /// Interns a frame whose symbols are already interned. The symbols must be from the
+    /// table's symbol table.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn intern_frame(&mut self, frame: StackFrame<'strings>) -> FrameId {
+        let function = frame.function.deslice().index();
+        let file = frame.file.deslice().index();
+        self.intern_key((function, file, frame.line))
+    }
+
+    fn intern_key(&mut self, key: FrameKey) -> FrameId {
+        *self.ids.entry(key).or_insert_with(|| {
+            let id = u32::try_from(self.frames.len()).expect("Too many frames for a u32 id.");
+            self.frames.push(key);
+            FrameId(id)
+        })
+    }
+
+    /// The frame for an identifier, or `None` if it's out of range.
+    pub fn get(&self, id: FrameId) -> Option<StackFrame<'strings>> {
+        let &(function, file, line) = self.frames.get(id.index())?;
+        Some(StackFrame {
+            function: Symbol::new(self.symbol_table, function),
+            file: Symbol::new(self.symbol_table, file),
+            line,
+        })
+    }
+
+    /// The number of distinct frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns true if there are no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Iterates over every frame with its identifier, in identifier order.
+    pub fn iter(&self) -> impl Iterator<Item = (FrameId, StackFrame<'strings>)> + '_ {
+        (0..self.frames.len()).map(|index| {
+            let id = FrameId(index as u32);
+            (id, self.get(id).unwrap())
+        })
+    }
+
+    /// Interns every frame of another table, such as one from a different thread or
+    /// process, and returns the new identifier of each of its frames, in the order of
+    /// its identifiers. The other table can use a different symbol table.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{FrameTable, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let mut frames = FrameTable::new(&symbol_table);
+    /// let main = frames.intern("main", "main.rs", 1);
+    ///
+    /// let thread_symbols = SymbolTable::new();
+    /// let mut thread_frames = FrameTable::new(&thread_symbols);
+    /// let work = thread_frames.intern("work", "work.rs", 5);
+    /// let thread_main = thread_frames.intern("main", "main.rs", 1);
+    ///
+    /// let remapping = frames.merge(&thread_frames);
+    /// assert_eq!(remapping[thread_main.index()], main);
+    /// assert_eq!(frames.get(remapping[work.index()]).unwrap().function, "work");
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn merge(&mut self, other: &FrameTable) -> Vec<FrameId> {
+        let mut remapping = Vec::with_capacity(other.len());
+        for (_, frame) in other.iter() {
+            remapping.push(self.intern(frame.function.str(), frame.file.str(), frame.line));
+        }
+        remapping
+    }
+
+    /// Folds stacks of frames into the collapsed stack format of flame graph tools, where
+    /// each line is the function names of a stack, from the root to the leaf, joined by
+    /// `;`, followed by a space and the total count of the stack. Stacks with the same
+    /// function names are merged, and the lines are sorted. Identifiers that are out of
+    /// range are written as `?`.
+    pub fn fold<S: AsRef<[FrameId]>>(&self, stacks: &[(S, u64)]) -> String {
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        let mut line = String::new();
+        for (stack, count) in stacks {
+            line.clear();
+            for (i, id) in stack.as_ref().iter().enumerate() {
+                if i != 0 {
+                    line.push(';');
+                }
+                match self.get(*id) {
+                    Some(frame) => line.push_str(frame.function.str()),
+                    None => line.push('?'),
+                }
+            }
+            match counts.get_mut(&line) {
+                Some(total) => *total += count,
+                None => {
+                    counts.insert(line.clone(), *count);
+                }
+            }
+        }
+        let mut folded = String::new();
+        for (line, count) in counts {
+            folded.push_str(&format!("{line} {count}\n"));
+        }
+        folded
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_frames() {
+        let symbol_table = SymbolTable::new();
+        let mut frames = FrameTable::new(&symbol_table);
+        assert!(frames.is_empty());
+        let a = frames.intern("f", "a.rs", 1);
+        let b = frames.intern("f", "a.rs", 2);
+        assert_ne!(a, b);
+        let frame = frames.get(a).unwrap();
+        assert_eq!(frames.intern_frame(frame), a);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames.get(FrameId(2)), None);
+
+        // Frames that only differ in line fold together.
+        assert_eq!(
+            frames.fold(&[(vec![a], 1), (vec![b], 1), (vec![FrameId(9)], 1)]),
+            "? 1\nf 2\n"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+//! Reordering the table so that the most frequently used strings get the smallest
+//! indexes.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{IndexRemapping, SymbolIndex, SymbolTable};
+
+impl<'strings> SymbolTable<'strings> {
+    /// Counts a use of a string by `get`.
+    pub(crate) fn record_use(&self, index: SymbolIndex) {
+        let mut uses = self.uses.borrow_mut();
+        if uses.len() <= index {
+            uses.resize(index + 1, 0);
+        }
+        uses[index] += 1;
+    }
+
+    /// The number of times that a string was returned by
+    /// [`get`](struct.SymbolTable.html#method.get), including when it was first
+    /// interned.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let a = symbol_table.get("a");
+    /// symbol_table.get("a");
+    /// assert_eq!(symbol_table.use_count(a.index()), 2);
+    /// ```
+    pub fn use_count(&self, index: SymbolIndex) -> usize {
+        self.uses.borrow().get(index).copied().unwrap_or_default()
+    }
+
+    /// Reorders the strings so that the most frequently used ones get the smallest
+    /// indexes, which makes for smaller varint encodings, and better cache locality for
+    /// arrays indexed by symbol. Strings that are used equally often keep their relative
+    /// order. Uses are counted by [`use_count`](struct.SymbolTable.html#method.use_count).
+    ///
+    /// The returned remapping translates the old indexes, so that stored indexes can be
+    /// fixed. Everything else that the table keeps per string, such as tags and cached
+    /// encodings, is remapped along with the strings. This starts a new
+    /// [`generation`](struct.SymbolTable.html#method.generation) of the table.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let mut tokens: Vec<_> = ["rare", "common", "common", "middle", "common", "middle"]
+    ///     .iter()
+    ///     .map(|token| symbol_table.get(*token).index())
+    ///     .collect();
+    ///
+    /// let remapping = symbol_table.reindex_by_frequency();
+    /// remapping.remap(&mut tokens);
+    ///
+    /// assert_eq!(symbol_table.iter().collect::<Vec<_>>(), ["common", "middle", "rare"]);
+    /// assert_eq!(tokens, [2, 0, 0, 1, 0, 1]);
+    /// ```
+    pub fn reindex_by_frequency(&mut self) -> IndexRemapping {
+        let len = self.len();
+        let uses = self.uses.get_mut();
+        uses.resize(len, 0);
+        let mut order: Vec<SymbolIndex> = (0..len).collect();
+        order.sort_by_key(|&index| Reverse(uses[index]));
+        let mut new_indexes = vec![0; len];
+        for (new_index, &old_index) in order.iter().enumerate() {
+            new_indexes[old_index] = new_index;
+        }
+        let remap = |index: SymbolIndex| new_indexes[index];
+
+        // The sorted strings are no longer sorted by index, so they are hashed.
+        for index in 0..std::mem::take(&mut self.sorted_len) {
+            let string = self.symbols.as_mut()[index].clone();
+            self.indexes.as_mut().insert(string, Box::new(index));
+        }
+
+        *uses = order.iter().map(|&index| uses[index]).collect();
+        let widths = self.display_widths.get_mut();
+        *widths = order
+            .iter()
+            .map(|&index| widths.get(index).copied().flatten())
+            .collect();
+        let mut strings: Vec<Option<String>> = std::mem::take(self.symbols.as_mut())
+            .into_iter()
+            .map(Some)
+            .collect();
+        *self.symbols.as_mut() = order
+            .iter()
+            .map(|&index| strings[index].take().unwrap())
+            .collect();
+        #[cfg(debug_assertions)]
+        {
+            let addresses = self.interned_addresses.get_mut();
+            *addresses = order.iter().map(|&index| addresses[index]).collect();
+        }
+        self.addresses.get_mut().clear();
+        self.addressed_len.set(0);
+
+        for index in self.indexes.as_mut().values_mut() {
+            **index = remap(**index);
+        }
+        for indexes in self.tags.get_mut().values_mut() {
+            *indexes = indexes.iter().map(|&index| remap(index)).collect();
+        }
+        for index in self.static_indexes.get_mut().values_mut() {
+            *index = remap(*index);
+        }
+        remap_keys(self.formatted.get_mut(), |keys| {
+            keys.into_iter()
+                .map(|(index, range)| (remap(index), range))
+                .collect()
+        });
+        for index in self.formatted.get_mut().values_mut() {
+            *index = remap(*index);
+        }
+        remap_keys(self.slices.get_mut(), |((index, range), start, end)| {
+            ((remap(index), range), start, end)
+        });
+        remap_keys(self.line_starts.get_mut(), remap);
+        remap_keys(self.offset_checkpoints.get_mut(), remap);
+        remap_keys(self.cstrings.as_mut(), remap);
+        remap_keys(self.utf16.as_mut(), |(index, range)| (remap(index), range));
+        remap_keys(self.paths.as_mut(), |components| {
+            components.into_iter().map(remap).collect()
+        });
+        for components in self.paths.as_mut().values_mut() {
+            for index in components {
+                *index = remap(*index);
+            }
+        }
+        remap_keys(self.case_styles.get_mut(), |(index, style)| {
+            (remap(index), style)
+        });
+        for index in self.case_styles.get_mut().values_mut() {
+            *index = remap(*index);
+        }
+        for links in [self.demangled.get_mut(), self.mangled.get_mut()] {
+            remap_keys(links, remap);
+            for index in links.values_mut() {
+                *index = remap(*index);
+            }
+        }
+        for index in self.folded_paths.get_mut().values_mut() {
+            *index = remap(*index);
+        }
+        for indexes in self.large_strings.get_mut().values_mut() {
+            for index in indexes {
+                *index = remap(*index);
+            }
+        }
+        if let Some(suffixes) = self.suffixes.get_mut() {
+            for index in suffixes.values_mut() {
+                *index = remap(*index);
+            }
+        }
+        self.redaction.get_mut().remap(remap);
+        #[cfg(feature = "track-interning")]
+        remap_keys(self.call_sites.get_mut(), |(location, index)| {
+            (location, remap(index))
+        });
+
+        self.generation = self.generation.wrapping_add(1);
+        IndexRemapping::from_new_indexes(new_indexes)
+    }
+}
+
+fn remap_keys<K: Eq + Hash, V, S: BuildHasher + Default>(
+    map: &mut HashMap<K, V, S>,
+    mut remap: impl FnMut(K) -> K,
+) {
+    *map = std::mem::take(map)
+        .into_iter()
+        .map(|(key, value)| (remap(key), value))
+        .collect();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_side_tables_are_remapped() {
+        let mut symbol_table = SymbolTable::new();
+        let rare = symbol_table.get("rare\nline");
+        symbol_table.tag(rare, "tagged");
+        symbol_table.get_path(["a", "a", "a"]);
+        symbol_table.index_suffixes();
+        symbol_table.redact_namespace("user:", 1);
+        symbol_table.get("user:secret");
+        symbol_table.get("user:secret");
+        rare.as_cstr();
+        rare.to_utf16_cached();
+        symbol_table.line_col(rare.slice(5..9).unwrap());
+        assert!(rare.is("rare\nline"));
+
+        let remapping = symbol_table.reindex_by_frequency();
+        let rare = remapping.get(0).unwrap();
+        assert_eq!(
+            remapping.get(1),
+            Some(0),
+            "The path components are used most."
+        );
+        assert_eq!(
+            remapping.get(3),
+            Some(1),
+            "The redacted string is used twice."
+        );
+        assert_eq!(rare, 2, "Ties keep their order.");
+
+        let rare = symbol_table.get_by_index(rare).unwrap().to_owned();
+        let rare = symbol_table.get(rare);
+        assert_eq!(
+            symbol_table.iter_tagged("tagged").collect::<Vec<_>>(),
+            [rare]
+        );
+        assert_eq!(
+            symbol_table
+                .get_path(["a", "a", "a"])
+                .components()
+                .collect::<Vec<_>>(),
+            ["a", "a", "a"]
+        );
+        assert_eq!(
+            symbol_table.ends_with_lookup("line").collect::<Vec<_>>(),
+            [rare]
+        );
+        assert!(symbol_table.has("user:secret"));
+        assert_eq!(rare.as_cstr(), Some(c"rare\nline"));
+        assert_eq!(rare.to_utf16_cached().len(), 9);
+        assert_eq!(symbol_table.line_col(rare.slice(5..9).unwrap()), (2, 1));
+        assert!(rare.is("rare\nline"));
+        assert_eq!(symbol_table.symbol_of(rare.str()), Some(rare));
+    }
+}
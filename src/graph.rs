@@ -0,0 +1,253 @@
+//! A directed graph of relations between symbols, such as for dependency analysis.
+
+use std::collections::VecDeque;
+
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// Records directed edges between symbols, where each edge is labeled by a relation,
+/// like `"contains"` or `"alias of"`. The nodes and relations are symbols of the same
+/// table, so the graph only stores their indexes. Edges are iterated in the order that
+/// they were added.
+///
+/// ```
+/// use gregtatum_symbol_table::{SymbolGraph, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// let [app, net, http, json] = ["app", "net", "http", "json"].map(|name| symbol_table.get(name));
+/// let mut graph = SymbolGraph::new(&symbol_table);
+/// graph.add_edge(app, "imports", net);
+/// graph.add_edge(net, "imports", http);
+/// graph.add_edge(app, "imports", json);
+/// graph.add_edge(http, "imports", app);
+///
+/// let imports: Vec<_> = graph.targets(app, "imports").collect();
+/// assert_eq!(imports, ["net", "json"]);
+///
+/// let transitive: Vec<_> = graph.descendants(net, "imports").collect();
+/// // The starting node is included, since it can reach itself through a cycle.
+/// assert_eq!(transitive, ["http", "app", "net", "json"]);
+///
+/// let importers: Vec<_> = graph.sources(http, "imports").collect();
+/// assert_eq!(importers, ["net"]);
+/// ```
+pub struct SymbolGraph<'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    // The edges out of and into each node, as the relation and the other node.
+    outgoing: FxHashMap<SymbolIndex, Vec<(SymbolIndex, SymbolIndex)>>,
+    incoming: FxHashMap<SymbolIndex, Vec<(SymbolIndex, SymbolIndex)>>,
+    edges: FxHashSet<(SymbolIndex, SymbolIndex, SymbolIndex)>,
+}
+
+impl<'strings> SymbolGraph<'strings> {
+    /// Creates an empty graph of the symbols of a table.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        SymbolGraph {
+            symbol_table,
+            outgoing: FxHashMap::default(),
+            incoming: FxHashMap::default(),
+            edges: FxHashSet::default(),
+        }
+    }
+
+    /// Adds an edge, interning the relation. The symbols must be from the graph's table.
+    /// Returns false if the edge was already in the graph.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn add_edge(
+        &mut self,
+        from: Symbol<'strings>,
+        relation: &str,
+        to: Symbol<'strings>,
+    ) -> bool {
+        let from = from.deslice().index();
+        let relation = self.symbol_table.get(relation).index();
+        let to = to.deslice().index();
+        if !self.edges.insert((from, relation, to)) {
+            return false;
+        }
+        self.outgoing.entry(from).or_default().push((relation, to));
+        self.incoming.entry(to).or_default().push((relation, from));
+        true
+    }
+
+    /// Returns true if the graph has an edge.
+    pub fn contains_edge(&self, from: Symbol, relation: &str, to: Symbol) -> bool {
+        let Some(relation) = self.symbol_table.lookup_index(relation) else {
+            return false;
+        };
+        self.edges
+            .contains(&(from.deslice().index(), relation, to.deslice().index()))
+    }
+
+    /// The number of edges.
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns true if the graph has no edges.
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Iterates over every edge out of a node, as the relation and the target.
+    pub fn edges_from(
+        &self,
+        from: Symbol,
+    ) -> impl Iterator<Item = (Symbol<'strings>, Symbol<'strings>)> + '_ {
+        let symbol_table = self.symbol_table;
+        self.outgoing
+            .get(&from.deslice().index())
+            .into_iter()
+            .flatten()
+            .map(move |&(relation, to)| {
+                (
+                    Symbol::new(symbol_table, relation),
+                    Symbol::new(symbol_table, to),
+                )
+            })
+    }
+
+    /// Iterates over the targets of the edges with a relation out of a node.
+    pub fn targets(
+        &self,
+        from: Symbol,
+        relation: &str,
+    ) -> impl Iterator<Item = Symbol<'strings>> + '_ {
+        self.neighbors(&self.outgoing, from.deslice().index(), relation)
+    }
+
+    /// Iterates over the sources of the edges with a relation into a node.
+    pub fn sources(
+        &self,
+        to: Symbol,
+        relation: &str,
+    ) -> impl Iterator<Item = Symbol<'strings>> + '_ {
+        self.neighbors(&self.incoming, to.deslice().index(), relation)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        adjacency: &'a FxHashMap<SymbolIndex, Vec<(SymbolIndex, SymbolIndex)>>,
+        node: SymbolIndex,
+        relation: &str,
+    ) -> impl Iterator<Item = Symbol<'strings>> + 'a {
+        let relation = self.symbol_table.lookup_index(relation);
+        let symbol_table = self.symbol_table;
+        adjacency
+            .get(&node)
+            .filter(|_| relation.is_some())
+            .into_iter()
+            .flatten()
+            .filter(move |&&(edge_relation, _)| Some(edge_relation) == relation)
+            .map(move |&(_, other)| Symbol::new(symbol_table, other))
+    }
+
+    /// Iterates over every node that can be reached from a node by following edges with
+    /// a relation, in breadth-first order. Each node is visited once, so cycles are
+    /// fine, and the starting node is only included if it's part of a cycle.
+    pub fn descendants(&self, from: Symbol, relation: &str) -> Traversal<'_, 'strings> {
+        Traversal::new(self, &self.outgoing, from.deslice().index(), relation)
+    }
+
+    /// Iterates over every node that can reach a node by following edges with a
+    /// relation, in breadth-first order, like [`descendants`](Self::descendants) in
+    /// reverse.
+    pub fn ancestors(&self, to: Symbol, relation: &str) -> Traversal<'_, 'strings> {
+        Traversal::new(self, &self.incoming, to.deslice().index(), relation)
+    }
+}
+
+/// A breadth-first traversal of a [`SymbolGraph`], see
+/// [`SymbolGraph::descendants`] and [`SymbolGraph::ancestors`].
+pub struct Traversal<'a, 'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    adjacency: &'a FxHashMap<SymbolIndex, Vec<(SymbolIndex, SymbolIndex)>>,
+    relation: Option<SymbolIndex>,
+    queue: VecDeque<SymbolIndex>,
+    visited: FxHashSet<SymbolIndex>,
+}
+
+impl<'a, 'strings> Traversal<'a, 'strings> {
+    fn new(
+        graph: &SymbolGraph<'strings>,
+        adjacency: &'a FxHashMap<SymbolIndex, Vec<(SymbolIndex, SymbolIndex)>>,
+        start: SymbolIndex,
+        relation: &str,
+    ) -> Self {
+        let mut traversal = Traversal {
+            symbol_table: graph.symbol_table,
+            adjacency,
+            relation: graph.symbol_table.lookup_index(relation),
+            queue: VecDeque::new(),
+            visited: FxHashSet::default(),
+        };
+        traversal.enqueue_neighbors(start);
+        traversal
+    }
+
+    fn enqueue_neighbors(&mut self, node: SymbolIndex) {
+        let Some(edges) = self.adjacency.get(&node) else {
+            return;
+        };
+        for &(relation, other) in edges {
+            if Some(relation) == self.relation && self.visited.insert(other) {
+                self.queue.push_back(other);
+            }
+        }
+    }
+}
+
+impl<'a, 'strings> Iterator for Traversal<'a, 'strings> {
+    type Item = Symbol<'strings>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        self.enqueue_neighbors(node);
+        Some(Symbol::new(self.symbol_table, node))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_relations_are_separate() {
+        let symbol_table = SymbolTable::new();
+        let [a, b, c] = ["a", "b", "c"].map(|name| symbol_table.get(name));
+        let mut graph = SymbolGraph::new(&symbol_table);
+        assert!(graph.add_edge(a, "contains", b));
+        assert!(!graph.add_edge(a, "contains", b));
+        assert!(graph.add_edge(b, "alias of", c));
+        assert!(graph.add_edge(a, "alias of", a));
+        assert_eq!(graph.len(), 3);
+
+        assert!(graph.contains_edge(a, "contains", b));
+        assert!(!graph.contains_edge(a, "alias of", b));
+        assert!(!graph.contains_edge(a, "unknown", b));
+        assert_eq!(graph.descendants(a, "contains").collect::<Vec<_>>(), ["b"]);
+        assert_eq!(graph.descendants(a, "alias of").collect::<Vec<_>>(), ["a"]);
+        assert_eq!(graph.ancestors(c, "alias of").collect::<Vec<_>>(), ["b"]);
+        assert_eq!(graph.targets(a, "unknown").count(), 0);
+        let edges: Vec<_> = graph
+            .edges_from(a)
+            .map(|(relation, to)| (relation.str(), to.str()))
+            .collect();
+        assert_eq!(edges, [("contains", "b"), ("alias of", "a")]);
+
+        // Slices are the same nodes as the symbols of their strings.
+        let path = symbol_table.get("std::io");
+        let [std, io] = [path.slice(0..3).unwrap(), path.slice(5..7).unwrap()];
+        assert!(graph.add_edge(std, "contains", io));
+        assert!(graph.contains_edge(std, "contains", io));
+        assert_eq!(graph.targets(std, "contains").collect::<Vec<_>>(), ["io"]);
+        assert_eq!(graph.sources(io, "contains").collect::<Vec<_>>(), ["std"]);
+        assert_eq!(graph.edges_from(std).count(), 1);
+        assert_eq!(
+            graph.descendants(std, "contains").collect::<Vec<_>>(),
+            ["io"]
+        );
+        assert_eq!(graph.ancestors(io, "contains").collect::<Vec<_>>(), ["std"]);
+    }
+}
@@ -0,0 +1,116 @@
+//! Assertions for tests that a scope doesn't intern any new strings.
+
+use crate::SymbolTable;
+
+/// Snapshots the length of a [`SymbolTable`], so that any strings interned after the
+/// snapshot can be reported. Created with
+/// [`SymbolTable::len_guard`](struct.SymbolTable.html#method.len_guard).
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTable;
+///
+/// let symbol_table = SymbolTable::new();
+/// symbol_table.get("known");
+///
+/// let guard = symbol_table.len_guard();
+/// symbol_table.get("known");
+/// symbol_table.get("leaked");
+/// assert_eq!(guard.new_strings().collect::<Vec<_>>(), ["leaked"]);
+/// ```
+pub struct LenGuard<'table, 'strings> {
+    symbol_table: &'table SymbolTable<'strings>,
+    len: usize,
+}
+
+impl<'table> LenGuard<'table, '_> {
+    /// The length of the table when the guard was created.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the table was empty when the guard was created.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the strings interned since the guard was created, in order.
+    pub fn new_strings(&self) -> impl Iterator<Item = &'table str> {
+        let symbol_table = self.symbol_table;
+        (self.len..symbol_table.len()).map(move |index| symbol_table.str(index))
+    }
+
+    /// Panics if any strings were interned since the guard was created, listing the
+    /// new strings.
+    #[track_caller]
+    pub fn assert_stable(&self) {
+        let new_strings: Vec<_> = self.new_strings().collect();
+        assert!(
+            new_strings.is_empty(),
+            "Expected no strings to be interned, but {} were: {:?}",
+            new_strings.len(),
+            new_strings
+        );
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Snapshots the length of the table, to report any strings interned afterwards.
+    /// See [`assert_len_stable`](struct.SymbolTable.html#method.assert_len_stable) for
+    /// asserting on a closure.
+    pub fn len_guard(&self) -> LenGuard<'_, 'strings> {
+        LenGuard {
+            symbol_table: self,
+            len: self.len(),
+        }
+    }
+
+    /// Runs a closure, and panics if it interned any new strings, listing them. This is
+    /// useful for writing tests that assert a hot path only resolves strings.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let keyword = symbol_table.get("fn");
+    ///
+    /// let is_keyword = symbol_table.assert_len_stable(|| symbol_table.has("fn"));
+    /// assert!(is_keyword);
+    /// ```
+    ///
+    /// ```should_panic
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// // Panics with: Expected no strings to be interned, but 1 were: ["fn"]
+    /// symbol_table.assert_len_stable(|| symbol_table.get("fn"));
+    /// ```
+    #[track_caller]
+    pub fn assert_len_stable<R>(&self, f: impl FnOnce() -> R) -> R {
+        let guard = self.len_guard();
+        let result = f();
+        guard.assert_stable();
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_len_guard() {
+        let symbol_table = SymbolTable::new();
+        let guard = symbol_table.len_guard();
+        assert!(guard.is_empty());
+        guard.assert_stable();
+        symbol_table.get("a");
+        symbol_table.get("b");
+        assert_eq!(guard.len(), 0);
+        assert_eq!(guard.new_strings().collect::<Vec<_>>(), ["a", "b"]);
+
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| guard.assert_stable()));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.ends_with("but 2 were: [\"a\", \"b\"]"), "{message}");
+    }
+}
@@ -0,0 +1,180 @@
+//! A preset for using the [`SymbolTable`] as an atom table for HTTP header names. See
+//! [`SymbolTable::with_http_headers`].
+
+use std::borrow::Cow;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+macro_rules! headers {
+    ($($index:literal => $name:ident: $string:literal,)*) => {
+        $(
+            #[doc = concat!("The stable index of the `", $string, "` header.")]
+            pub const $name: SymbolIndex = $index;
+        )*
+
+        /// The standard header names, in lowercase. When preloaded with
+        /// [`SymbolTable::with_http_headers`], the index of each name in this list is
+        /// its [`SymbolIndex`], which is also available as a constant in this module.
+        pub const HEADER_NAMES: &[&str] = &[$($string,)*];
+    };
+}
+
+headers! {
+    0 => ACCEPT: "accept",
+    1 => ACCEPT_CHARSET: "accept-charset",
+    2 => ACCEPT_ENCODING: "accept-encoding",
+    3 => ACCEPT_LANGUAGE: "accept-language",
+    4 => ACCEPT_RANGES: "accept-ranges",
+    5 => ACCESS_CONTROL_ALLOW_CREDENTIALS: "access-control-allow-credentials",
+    6 => ACCESS_CONTROL_ALLOW_HEADERS: "access-control-allow-headers",
+    7 => ACCESS_CONTROL_ALLOW_METHODS: "access-control-allow-methods",
+    8 => ACCESS_CONTROL_ALLOW_ORIGIN: "access-control-allow-origin",
+    9 => ACCESS_CONTROL_EXPOSE_HEADERS: "access-control-expose-headers",
+    10 => ACCESS_CONTROL_MAX_AGE: "access-control-max-age",
+    11 => ACCESS_CONTROL_REQUEST_HEADERS: "access-control-request-headers",
+    12 => ACCESS_CONTROL_REQUEST_METHOD: "access-control-request-method",
+    13 => AGE: "age",
+    14 => ALLOW: "allow",
+    15 => AUTHORIZATION: "authorization",
+    16 => CACHE_CONTROL: "cache-control",
+    17 => CONNECTION: "connection",
+    18 => CONTENT_DISPOSITION: "content-disposition",
+    19 => CONTENT_ENCODING: "content-encoding",
+    20 => CONTENT_LANGUAGE: "content-language",
+    21 => CONTENT_LENGTH: "content-length",
+    22 => CONTENT_LOCATION: "content-location",
+    23 => CONTENT_RANGE: "content-range",
+    24 => CONTENT_SECURITY_POLICY: "content-security-policy",
+    25 => CONTENT_TYPE: "content-type",
+    26 => COOKIE: "cookie",
+    27 => DATE: "date",
+    28 => ETAG: "etag",
+    29 => EXPECT: "expect",
+    30 => EXPIRES: "expires",
+    31 => FORWARDED: "forwarded",
+    32 => FROM: "from",
+    33 => HOST: "host",
+    34 => IF_MATCH: "if-match",
+    35 => IF_MODIFIED_SINCE: "if-modified-since",
+    36 => IF_NONE_MATCH: "if-none-match",
+    37 => IF_RANGE: "if-range",
+    38 => IF_UNMODIFIED_SINCE: "if-unmodified-since",
+    39 => LAST_MODIFIED: "last-modified",
+    40 => LINK: "link",
+    41 => LOCATION: "location",
+    42 => MAX_FORWARDS: "max-forwards",
+    43 => ORIGIN: "origin",
+    44 => PRAGMA: "pragma",
+    45 => PROXY_AUTHENTICATE: "proxy-authenticate",
+    46 => PROXY_AUTHORIZATION: "proxy-authorization",
+    47 => RANGE: "range",
+    48 => REFERER: "referer",
+    49 => REFERRER_POLICY: "referrer-policy",
+    50 => RETRY_AFTER: "retry-after",
+    51 => SERVER: "server",
+    52 => SET_COOKIE: "set-cookie",
+    53 => STRICT_TRANSPORT_SECURITY: "strict-transport-security",
+    54 => TE: "te",
+    55 => TRAILER: "trailer",
+    56 => TRANSFER_ENCODING: "transfer-encoding",
+    57 => UPGRADE: "upgrade",
+    58 => USER_AGENT: "user-agent",
+    59 => VARY: "vary",
+    60 => VIA: "via",
+    61 => WWW_AUTHENTICATE: "www-authenticate",
+    62 => X_CONTENT_TYPE_OPTIONS: "x-content-type-options",
+    63 => X_FORWARDED_FOR: "x-forwarded-for",
+    64 => X_FORWARDED_HOST: "x-forwarded-host",
+    65 => X_FORWARDED_PROTO: "x-forwarded-proto",
+    66 => X_FRAME_OPTIONS: "x-frame-options",
+    67 => X_REQUEST_ID: "x-request-id",
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Creates a new [`SymbolTable`] that is preloaded with the standard HTTP
+    /// [`HEADER_NAMES`], so that they have stable indexes matching the constants in the
+    /// [`headers`](crate::headers) module.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{headers, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::with_http_headers();
+    /// let content_type = symbol_table.get_header("Content-Type");
+    /// assert_eq!(content_type, "content-type");
+    /// assert_eq!(content_type.index(), headers::CONTENT_TYPE);
+    /// ```
+    pub fn with_http_headers() -> SymbolTable<'strings> {
+        SymbolTable::preloaded(HEADER_NAMES)
+    }
+
+    /// Interns a header name case insensitively, by lowercasing it. This only
+    /// allocates when the name isn't already lowercase, or when it's a new name.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{headers, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::with_http_headers();
+    /// assert_eq!(symbol_table.get_header("HOST").index(), headers::HOST);
+    /// assert_eq!(symbol_table.get_header("host").index(), headers::HOST);
+    ///
+    /// // Custom headers are interned too.
+    /// assert_eq!(symbol_table.get_header("X-Custom"), "x-custom");
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn get_header(&'strings self, name: &str) -> Symbol<'strings> {
+        if name.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            self.get(name.to_ascii_lowercase())
+        } else {
+            self.get(name)
+        }
+    }
+
+    /// Looks up a header name case insensitively, without interning it.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{headers, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::with_http_headers();
+    /// let etag = symbol_table.maybe_get_header("ETag").unwrap();
+    /// assert_eq!(etag.index(), headers::ETAG);
+    /// assert_eq!(symbol_table.maybe_get_header("X-Custom"), None);
+    /// ```
+    pub fn maybe_get_header(&'strings self, name: &str) -> Option<Symbol<'strings>> {
+        let name = if name.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            Cow::Owned(name.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(name)
+        };
+        self.maybe_get(name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_header_constants() {
+        let symbol_table = SymbolTable::with_http_headers();
+        assert_eq!(
+            symbol_table.len(),
+            HEADER_NAMES.len(),
+            "There are no duplicates."
+        );
+        for (index, name) in HEADER_NAMES.iter().enumerate() {
+            assert_eq!(symbol_table.get_header(name).index(), index);
+        }
+        assert_eq!(
+            symbol_table.get_header("content-type").index(),
+            CONTENT_TYPE
+        );
+        assert_eq!(
+            symbol_table.get_header("X-Request-ID").index(),
+            X_REQUEST_ID
+        );
+        assert_eq!(
+            symbol_table.get_header("WWW-Authenticate").index(),
+            WWW_AUTHENTICATE
+        );
+    }
+}
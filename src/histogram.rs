@@ -0,0 +1,207 @@
+//! Statistics about the strings in a table, for tracking the health of long-lived
+//! tables, such as on a dashboard.
+
+use fxhash::FxHashMap;
+
+use crate::SymbolTable;
+
+/// The strings whose lengths are in a range of bytes, which is a size class of the
+/// table. Comparing the size classes shows how a workload mixes short, frequently used
+/// strings like tokens with long, rarely used ones like documents.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LengthBucket {
+    /// The shortest length in the bucket.
+    pub min: usize,
+    /// The longest length in the bucket.
+    pub max: usize,
+    /// The number of strings in the bucket.
+    pub count: usize,
+    /// The total length in bytes of the strings in the bucket.
+    pub bytes: usize,
+    /// The number of times that the strings in the bucket were returned by
+    /// [`SymbolTable::get`](struct.SymbolTable.html#method.get), see
+    /// [`SymbolTable::use_count`](struct.SymbolTable.html#method.use_count).
+    pub uses: usize,
+}
+
+/// The distribution of the strings in a table, created by
+/// [`SymbolTable::histogram`](struct.SymbolTable.html#method.histogram).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableHistogram {
+    /// The number of strings.
+    pub strings: usize,
+    /// The total length in bytes of the strings.
+    pub bytes: usize,
+    /// The bytes that the table has allocated for the strings, see
+    /// [`SymbolTable::memory_usage`](struct.SymbolTable.html#method.memory_usage).
+    pub allocated_bytes: usize,
+    /// The number of strings by length, in buckets that double in size: `0`, `1`,
+    /// `2..=3`, `4..=7`, and so on. Only buckets up to the longest string are included.
+    pub lengths: Vec<LengthBucket>,
+    /// The number of strings in each namespace, which is the part of a string up to and
+    /// including its first `:`, like `"user:"` or `"std:"`. These are sorted by count,
+    /// largest first. Strings without a `:` aren't in a namespace.
+    pub namespaces: Vec<(String, usize)>,
+}
+
+impl TableHistogram {
+    /// The fraction of the allocated bytes that don't hold string contents, such as the
+    /// second copy of each string that is kept for lookups. Each string has its own
+    /// allocation, rather than sharing an arena, so this is the overhead of the table
+    /// rather than fragmentation of an arena. This is `0.0` for an empty table.
+    pub fn overhead(&self) -> f64 {
+        if self.allocated_bytes == 0 {
+            return 0.0;
+        }
+        self.allocated_bytes.saturating_sub(self.bytes) as f64 / self.allocated_bytes as f64
+    }
+
+    /// Serializes the histogram as a JSON object, for exporting to a dashboard.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("user:1");
+    /// symbol_table.get("a");
+    ///
+    /// assert_eq!(
+    ///     symbol_table.histogram().to_json(),
+    ///     concat!(
+    ///         r#"{"strings":2,"bytes":7,"allocated_bytes":14,"overhead":0.5,"#,
+    ///         r#""lengths":[{"min":0,"max":0,"count":0,"bytes":0,"uses":0},"#,
+    ///         r#"{"min":1,"max":1,"count":1,"bytes":1,"uses":1},"#,
+    ///         r#"{"min":2,"max":3,"count":0,"bytes":0,"uses":0},"#,
+    ///         r#"{"min":4,"max":7,"count":1,"bytes":6,"uses":1}],"#,
+    ///         r#""namespaces":{"user:":1}}"#
+    ///     )
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            r#"{{"strings":{},"bytes":{},"allocated_bytes":{},"overhead":{},"lengths":["#,
+            self.strings,
+            self.bytes,
+            self.allocated_bytes,
+            self.overhead()
+        );
+        for (i, bucket) in self.lengths.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+            json.push_str(&format!(
+                r#"{{"min":{},"max":{},"count":{},"bytes":{},"uses":{}}}"#,
+                bucket.min, bucket.max, bucket.count, bucket.bytes, bucket.uses
+            ));
+        }
+        json.push_str(r#"],"namespaces":{"#);
+        for (i, (namespace, count)) in self.namespaces.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+            crate::json::push_string(&mut json, namespace);
+            json.push_str(&format!(":{count}"));
+        }
+        json.push_str("}}");
+        json
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Computes the distribution of string lengths and namespaces in the table, along
+    /// with its memory usage. The lengths are grouped into size classes, with the
+    /// bytes and uses of each class. This visits every string.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// for string in ["user:alice", "user:bob", "group:admins", "plain"] {
+    ///     symbol_table.get(string);
+    /// }
+    ///
+    /// let histogram = symbol_table.histogram();
+    /// assert_eq!(histogram.strings, 4);
+    /// assert_eq!(histogram.namespaces[0], ("user:".to_string(), 2));
+    /// assert_eq!(histogram.lengths.last().unwrap().max, 15);
+    ///
+    /// // The short strings are used far more often than the long ones.
+    /// for _ in 0..10 {
+    ///     symbol_table.get("plain");
+    /// }
+    /// let size_classes = symbol_table.histogram().lengths;
+    /// assert_eq!((size_classes[3].count, size_classes[3].bytes, size_classes[3].uses), (1, 5, 11));
+    /// assert_eq!((size_classes[4].count, size_classes[4].bytes, size_classes[4].uses), (3, 30, 3));
+    /// ```
+    pub fn histogram(&self) -> TableHistogram {
+        let mut histogram = TableHistogram {
+            strings: self.len(),
+            allocated_bytes: self.memory_usage(),
+            ..Default::default()
+        };
+        let mut namespaces: FxHashMap<&str, usize> = FxHashMap::default();
+        for (index, string) in self.iter().enumerate() {
+            histogram.bytes += string.len();
+            let bucket = (usize::BITS - string.len().leading_zeros()) as usize;
+            while histogram.lengths.len() <= bucket {
+                let i = histogram.lengths.len();
+                histogram.lengths.push(LengthBucket {
+                    min: if i == 0 { 0 } else { 1 << (i - 1) },
+                    max: if i == 0 { 0 } else { (1 << i) - 1 },
+                    count: 0,
+                    bytes: 0,
+                    uses: 0,
+                });
+            }
+            let bucket = &mut histogram.lengths[bucket];
+            bucket.count += 1;
+            bucket.bytes += string.len();
+            bucket.uses += self.use_count(index);
+            if let Some(end) = string.find(':') {
+                *namespaces.entry(&string[..=end]).or_default() += 1;
+            }
+        }
+        histogram.namespaces = namespaces
+            .into_iter()
+            .map(|(namespace, count)| (namespace.to_owned(), count))
+            .collect();
+        histogram
+            .namespaces
+            .sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then(a_name.cmp(b_name)));
+        histogram
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_empty_histogram() {
+        let histogram = SymbolTable::new().histogram();
+        assert_eq!(histogram, TableHistogram::default());
+        assert_eq!(histogram.overhead(), 0.0);
+        assert_eq!(
+            histogram.to_json(),
+            r#"{"strings":0,"bytes":0,"allocated_bytes":0,"overhead":0,"lengths":[],"namespaces":{}}"#
+        );
+    }
+
+    #[test]
+    fn test_buckets() {
+        let symbol_table = SymbolTable::new();
+        for len in [0, 4, 7, 8, 8] {
+            symbol_table.get(format!("{len}").repeat(len));
+        }
+        let counts: Vec<_> = symbol_table
+            .histogram()
+            .lengths
+            .iter()
+            .map(|bucket| (bucket.min, bucket.max, bucket.count))
+            .collect();
+        assert_eq!(
+            counts,
+            [(0, 0, 1), (1, 1, 0), (2, 3, 0), (4, 7, 2), (8, 15, 1)]
+        );
+    }
+}
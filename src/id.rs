@@ -0,0 +1,270 @@
+//! Dense `u32` identifiers for symbols, for indexing into arrays such as embedding
+//! matrices.
+
+use std::fmt;
+use std::ops::Index;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// The identifier of a string in a [`SymbolTable`], as a `u32`. The identifiers of a
+/// table always form the dense range `0..len`, without any gaps, since strings are never
+/// removed from a table. Tables that are compacted or reordered, such as with
+/// [`SymbolTable::map`](struct.SymbolTable.html#method.map), are rebuilt with a new
+/// dense range in a new generation, and the [`IndexRemapping`](crate::IndexRemapping)
+/// translates the old identifiers. This makes identifiers safe for directly indexing into arrays with one
+/// row per string.
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTable;
+///
+/// let symbol_table = SymbolTable::new();
+/// let tokens = ["the", "cat", "sat", "the"].map(|token| symbol_table.get(token).id());
+///
+/// // One embedding row per string in the table.
+/// let embeddings = vec![[0.0f32; 4]; symbol_table.len()];
+/// for id in tokens {
+///     let _row = &embeddings[id.index()];
+/// }
+/// assert_eq!(symbol_table.ids().last().unwrap().as_u32(), 2);
+/// ```
+///
+/// Identifiers also carry the [`generation`](struct.SymbolTable.html#method.generation)
+/// of the table that they came from, which changes when the table is cleared or
+/// reindexed. Resolving an identifier from an older generation is an error rather than
+/// resolving to whatever string now has its index.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SymbolId {
+    generation: u32,
+    id: u32,
+}
+
+impl SymbolId {
+    /// Creates an identifier from a `u32`, such as one that was read from storage. The
+    /// identifier is for the first generation of a table.
+    pub fn new(id: u32) -> SymbolId {
+        SymbolId { generation: 0, id }
+    }
+
+    /// Creates an identifier from a `u32` for a generation of a table.
+    pub fn with_generation(id: u32, generation: u32) -> SymbolId {
+        SymbolId { generation, id }
+    }
+
+    /// The identifier as a `u32`.
+    pub fn as_u32(self) -> u32 {
+        self.id
+    }
+
+    /// The identifier as a [`SymbolIndex`], for indexing into arrays.
+    pub fn index(self) -> SymbolIndex {
+        self.id as SymbolIndex
+    }
+
+    /// The generation of the table that the identifier is for.
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+impl From<SymbolId> for u32 {
+    fn from(id: SymbolId) -> u32 {
+        id.id
+    }
+}
+
+impl fmt::Display for SymbolId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.id.fmt(f)
+    }
+}
+
+/// The error for an identifier that can't be resolved by a table.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SymbolIdError {
+    /// The identifier is from a different generation of the table, from before it was
+    /// cleared or reindexed.
+    Stale {
+        /// The generation of the identifier.
+        generation: u32,
+        /// The current generation of the table.
+        current: u32,
+    },
+    /// The identifier is out of range of the table.
+    OutOfRange {
+        /// The identifier.
+        id: u32,
+        /// The number of strings in the table.
+        len: usize,
+    },
+}
+
+impl fmt::Display for SymbolIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolIdError::Stale {
+                generation,
+                current,
+            } => write!(
+                f,
+                "The identifier is from generation {generation} of the table, but the table is at generation {current}."
+            ),
+            SymbolIdError::OutOfRange { id, len } => write!(
+                f,
+                "The identifier {id} is out of range of a table with {len} strings."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SymbolIdError {}
+
+impl<'strings> Symbol<'strings> {
+    /// The dense `u32` identifier of the string, see [`SymbolId`]. Slices share the
+    /// identifier of the string that they are a slice of.
+    pub fn id(&self) -> SymbolId {
+        SymbolId::with_generation(self.index as u32, self.symbol_table.generation)
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Iterates over the identifiers of every string in the table, which are always
+    /// `0..len`.
+    pub fn ids(&self) -> impl Iterator<Item = SymbolId> {
+        let generation = self.generation;
+        (0..self.len()).map(move |index| SymbolId::with_generation(index as u32, generation))
+    }
+
+    /// The generation of the table, which starts at `0` and increases every time that
+    /// the table is [cleared](struct.SymbolTable.html#method.clear) or
+    /// [reindexed](struct.SymbolTable.html#method.reindex_by_frequency), since the
+    /// identifiers from before then no longer refer to the same strings.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Resolves the symbol for an identifier, checking that the identifier is from the
+    /// current generation of the table.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SymbolIdError, SymbolTable};
+    ///
+    /// let mut symbol_table = SymbolTable::new();
+    /// let id = symbol_table.get("old").id();
+    /// assert_eq!(symbol_table.resolve_id(id).unwrap(), "old");
+    ///
+    /// symbol_table.clear();
+    /// symbol_table.get("new");
+    /// assert_eq!(
+    ///     symbol_table.resolve_id(id),
+    ///     Err(SymbolIdError::Stale { generation: 0, current: 1 })
+    /// );
+    /// ```
+    pub fn resolve_id(&'strings self, id: SymbolId) -> Result<Symbol<'strings>, SymbolIdError> {
+        self.check_id(id)?;
+        Ok(Symbol::new(self, id.index()))
+    }
+
+    fn check_id(&self, id: SymbolId) -> Result<(), SymbolIdError> {
+        if id.generation != self.generation {
+            return Err(SymbolIdError::Stale {
+                generation: id.generation,
+                current: self.generation,
+            });
+        }
+        if id.index() >= self.len() {
+            return Err(SymbolIdError::OutOfRange {
+                id: id.id,
+                len: self.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Removes every string from the table, and starts a new
+    /// [`generation`](struct.SymbolTable.html#method.generation) so that the
+    /// identifiers from before are detected as stale. This returns the table to the
+    /// state of a new table, so any configuration, such as redacted namespaces, suffix
+    /// indexing, and memory thresholds, is also removed.
+    pub fn clear(&mut self) {
+        let generation = self.generation.wrapping_add(1);
+        *self = SymbolTable {
+            generation,
+            ..SymbolTable::new()
+        };
+    }
+}
+
+/// Resolves the string for an identifier. This panics if the identifier is stale or out
+/// of range.
+impl<'strings> Index<SymbolId> for SymbolTable<'strings> {
+    type Output = str;
+
+    fn index(&self, id: SymbolId) -> &str {
+        if let Err(error) = self.check_id(id) {
+            panic!("{error}");
+        }
+        &self[id.index()]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::panic::AssertUnwindSafe;
+
+    #[test]
+    fn test_ids_after_remapping() {
+        let symbol_table = SymbolTable::new();
+        for string in ["a", "B", "b", "c"] {
+            symbol_table.get(string);
+        }
+        let (lowercase, remapping) = symbol_table.map(|string| string.to_lowercase().into());
+        let ids: Vec<_> = lowercase.ids().map(SymbolId::as_u32).collect();
+        assert_eq!(ids, [0, 1, 2]);
+        for id in symbol_table.ids() {
+            let new_id = SymbolId::with_generation(remapping.get(id.index()).unwrap() as u32, 1);
+            assert_eq!(&lowercase[new_id], symbol_table[id].to_lowercase());
+        }
+
+        // The identifiers from before the remapping are stale.
+        assert_eq!(
+            lowercase.resolve_id(SymbolId::new(0)),
+            Err(SymbolIdError::Stale {
+                generation: 0,
+                current: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_stale_ids() {
+        let mut symbol_table = SymbolTable::new();
+        let ids: Vec<_> = ["a", "b", "b"]
+            .iter()
+            .map(|string| symbol_table.get(*string).id())
+            .collect();
+        assert_eq!(
+            symbol_table.resolve_id(SymbolId::new(2)),
+            Err(SymbolIdError::OutOfRange { id: 2, len: 2 })
+        );
+
+        symbol_table.reindex_by_frequency();
+        assert_eq!(symbol_table.generation(), 1);
+        assert_eq!(
+            symbol_table.resolve_id(ids[0]),
+            Err(SymbolIdError::Stale {
+                generation: 0,
+                current: 1
+            })
+        );
+        let b = symbol_table.ids().next().unwrap();
+        assert_eq!(&symbol_table[b], "b");
+        assert_eq!(b.generation(), 1);
+
+        symbol_table.clear();
+        assert!(symbol_table.is_empty());
+        assert_eq!(symbol_table.generation(), 2);
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| symbol_table[b].len()));
+        assert!(result.is_err());
+    }
+}
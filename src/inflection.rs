@@ -0,0 +1,218 @@
+//! English pluralization and singularization of identifiers, such as for deriving table
+//! names from type names in a code generator. This requires the `inflection` feature.
+
+use crate::Symbol;
+
+/// Words with irregular plurals, as `(singular, plural)`.
+const IRREGULAR: &[(&str, &str)] = &[
+    ("child", "children"),
+    ("foot", "feet"),
+    ("goose", "geese"),
+    ("half", "halves"),
+    ("knife", "knives"),
+    ("leaf", "leaves"),
+    ("life", "lives"),
+    ("man", "men"),
+    ("mouse", "mice"),
+    ("ox", "oxen"),
+    ("person", "people"),
+    ("shelf", "shelves"),
+    ("tooth", "teeth"),
+    ("wife", "wives"),
+    ("wolf", "wolves"),
+    ("woman", "women"),
+];
+
+/// Words that are the same in the singular and plural.
+const UNCOUNTABLE: &[&str] = &[
+    "data",
+    "equipment",
+    "fish",
+    "information",
+    "metadata",
+    "money",
+    "news",
+    "rice",
+    "series",
+    "sheep",
+    "species",
+];
+
+/// Plurals that end in `-uses` and are singularized by dropping the `-es`, unlike
+/// words like `houses`.
+const ES_PLURALS: &[&str] = &[
+    "aliases", "bonuses", "buses", "campuses", "statuses", "viruses",
+];
+
+/// Splits off the last word of an identifier, which starts at an uppercase letter in
+/// `PascalCase` or `camelCase`, or after a `_`, `-`, or space. Returns the prefix, and
+/// the last word.
+fn split_last_word(identifier: &str) -> (&str, &str) {
+    let mut start = 0;
+    let mut previous: Option<char> = None;
+    for (i, ch) in identifier.char_indices() {
+        match previous {
+            Some('_' | '-' | ' ') => start = i,
+            Some(previous) if ch.is_uppercase() && previous.is_lowercase() => start = i,
+            _ => {}
+        }
+        previous = Some(ch);
+    }
+    identifier.split_at(start)
+}
+
+/// Replaces a word with another of the same meaning, keeping its case, such as
+/// `Person` becoming `People`, and `PERSON` becoming `PEOPLE`.
+fn with_case_of(word: &str, replacement: &str) -> String {
+    let mut letters = word.chars().filter(|ch| ch.is_alphabetic());
+    let is_upper = word.chars().any(char::is_uppercase) && !word.chars().any(char::is_lowercase);
+    if is_upper && word.chars().count() > 1 {
+        return replacement.to_uppercase();
+    }
+    match letters.next() {
+        Some(first) if first.is_uppercase() => {
+            let mut chars = replacement.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        _ => replacement.to_owned(),
+    }
+}
+
+/// The suffix of a word, keeping the case of the last letter of the word.
+fn suffix(word: &str, suffix: &str) -> String {
+    let is_upper = word.chars().last().is_some_and(char::is_uppercase);
+    if is_upper {
+        suffix.to_uppercase()
+    } else {
+        suffix.to_owned()
+    }
+}
+
+fn is_vowel(ch: char) -> bool {
+    matches!(ch, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+fn pluralize_word(word: &str) -> String {
+    let lower = word.to_lowercase();
+    if lower.is_empty() || UNCOUNTABLE.contains(&lower.as_str()) {
+        return word.to_owned();
+    }
+    if let Some((_, plural)) = IRREGULAR.iter().find(|(singular, _)| *singular == lower) {
+        return with_case_of(word, plural);
+    }
+    if IRREGULAR.iter().any(|(_, plural)| *plural == lower) {
+        return word.to_owned();
+    }
+    let mut chars = lower.chars().rev();
+    let last = chars.next().unwrap();
+    let before_last = chars.next();
+    if lower.ends_with(['s', 'x', 'z']) || lower.ends_with("ch") || lower.ends_with("sh") {
+        format!("{word}{}", suffix(word, "es"))
+    } else if last == 'y' && before_last.is_some_and(|ch| !is_vowel(ch)) {
+        format!("{}{}", &word[..word.len() - 1], suffix(word, "ies"))
+    } else {
+        format!("{word}{}", suffix(word, "s"))
+    }
+}
+
+fn singularize_word(word: &str) -> String {
+    let lower = word.to_lowercase();
+    if lower.is_empty() || UNCOUNTABLE.contains(&lower.as_str()) {
+        return word.to_owned();
+    }
+    if let Some((singular, _)) = IRREGULAR.iter().find(|(_, plural)| *plural == lower) {
+        return with_case_of(word, singular);
+    }
+    if IRREGULAR.iter().any(|(singular, _)| *singular == lower) {
+        return word.to_owned();
+    }
+    let keep = |len: usize| word[..word.len() - len].to_owned();
+    if lower.ends_with("ies") && lower.len() > 3 {
+        format!("{}{}", keep(3), suffix(word, "y"))
+    } else if ["sses", "xes", "zzes", "ches", "shes"]
+        .iter()
+        .chain(ES_PLURALS)
+        .any(|ending| lower.ends_with(ending))
+    {
+        keep(2)
+    } else if lower.ends_with("ss") || lower.ends_with("us") || lower.ends_with("is") {
+        word.to_owned()
+    } else if lower.ends_with('s') {
+        keep(1)
+    } else {
+        word.to_owned()
+    }
+}
+
+impl<'strings> Symbol<'strings> {
+    /// Interns the English plural of the last word of an identifier, keeping its case.
+    /// This covers the regular rules, along with common irregular and uncountable words,
+    /// rather than the entire English language.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// assert_eq!(symbol_table.get("UserAccount").pluralize(), "UserAccounts");
+    /// assert_eq!(symbol_table.get("order_entry").pluralize(), "order_entries");
+    /// assert_eq!(symbol_table.get("SalesPerson").pluralize(), "SalesPeople");
+    /// assert_eq!(symbol_table.get("Address").pluralize(), "Addresses");
+    /// assert_eq!(symbol_table.get("Metadata").pluralize(), "Metadata");
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn pluralize(&self) -> Symbol<'strings> {
+        let (prefix, word) = split_last_word(self.str());
+        self.symbol_table
+            .get(format!("{prefix}{}", pluralize_word(word)))
+    }
+
+    /// Interns the English singular of the last word of an identifier, keeping its
+    /// case. This is the reverse of [`pluralize`](struct.Symbol.html#method.pluralize).
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// assert_eq!(symbol_table.get("user_accounts").singularize(), "user_account");
+    /// assert_eq!(symbol_table.get("Categories").singularize(), "Category");
+    /// assert_eq!(symbol_table.get("CHILDREN").singularize(), "CHILD");
+    /// assert_eq!(symbol_table.get("Statuses").singularize(), "Status");
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn singularize(&self) -> Symbol<'strings> {
+        let (prefix, word) = split_last_word(self.str());
+        self.symbol_table
+            .get(format!("{prefix}{}", singularize_word(word)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::SymbolTable;
+
+    #[test]
+    fn test_round_trips() {
+        let symbol_table = SymbolTable::new();
+        for (singular, plural) in [
+            ("box", "boxes"),
+            ("Church", "Churches"),
+            ("day", "days"),
+            ("Company", "Companies"),
+            ("ITEM", "ITEMS"),
+            ("Wolf", "Wolves"),
+            ("class", "classes"),
+            ("bus", "buses"),
+            ("house", "houses"),
+            ("", ""),
+        ] {
+            assert_eq!(symbol_table.get(singular).pluralize(), plural);
+            assert_eq!(symbol_table.get(plural).singularize(), singular);
+        }
+        // Words that are already plural or singular are unchanged.
+        assert_eq!(symbol_table.get("people").pluralize(), "people");
+        assert_eq!(symbol_table.get("analysis").singularize(), "analysis");
+    }
+}
@@ -0,0 +1,405 @@
+//! Reading CSV and JSON lines streams into dictionary-encoded [`SymbolColumn`]s, so
+//! that string columns are deduplicated through the table as they are read. This
+//! requires the `ingest` feature.
+
+use std::fmt;
+use std::io::{self, BufRead};
+
+use fxhash::FxHashMap;
+
+use crate::escape::unescape_json;
+use crate::{Symbol, SymbolColumn, SymbolTable};
+
+/// The error for a stream that couldn't be read into columns.
+#[derive(Debug)]
+pub enum IngestError {
+    /// Reading the stream failed.
+    Io(io::Error),
+    /// A CSV row has a different number of fields than the header.
+    FieldCount {
+        /// The line that the row starts on, starting at 1.
+        line: usize,
+        /// The number of fields in the header.
+        expected: usize,
+        /// The number of fields in the row.
+        found: usize,
+    },
+    /// A quoted CSV field is malformed, or isn't closed before the end of the stream.
+    InvalidQuote {
+        /// The line that the row starts on, starting at 1.
+        line: usize,
+    },
+    /// A JSON line isn't a flat object of strings, numbers, booleans, and nulls.
+    InvalidJson {
+        /// The line of the object, starting at 1.
+        line: usize,
+    },
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IngestError::Io(error) => error.fmt(f),
+            IngestError::FieldCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "The row on line {line} has {found} fields, but the header has {expected}."
+            ),
+            IngestError::InvalidQuote { line } => {
+                write!(f, "The row on line {line} has a malformed quoted field.")
+            }
+            IngestError::InvalidJson { line } => {
+                write!(f, "Line {line} isn't a flat JSON object.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IngestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IngestError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for IngestError {
+    fn from(error: io::Error) -> Self {
+        IngestError::Io(error)
+    }
+}
+
+/// The columns of a stream, each with its name, in the order that they first appear.
+pub type Columns<'strings> = Vec<(Symbol<'strings>, SymbolColumn<'strings>)>;
+
+impl<'strings> SymbolTable<'strings> {
+    /// Reads a CSV stream with a header row into a column per header, interning every
+    /// field. Fields can be quoted with `"`, where `""` is an escaped quote, and quoted
+    /// fields can span lines. Blank lines are skipped.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let csv = "level,service\nINFO,api\nERROR,\"db, primary\"\nINFO,api\n";
+    /// let symbol_table = SymbolTable::new();
+    /// let columns = symbol_table.read_csv(csv.as_bytes()).unwrap();
+    ///
+    /// let (name, levels) = &columns[0];
+    /// assert_eq!(*name, "level");
+    /// assert_eq!(levels.iter().collect::<Vec<_>>(), ["INFO", "ERROR", "INFO"]);
+    /// assert_eq!(columns[1].1.get(1).unwrap(), "db, primary");
+    /// assert_eq!(levels.indexes()[0], levels.indexes()[2]);
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn read_csv<R: BufRead>(
+        &'strings self,
+        mut reader: R,
+    ) -> Result<Columns<'strings>, IngestError> {
+        let mut line = 0;
+        let Some((_, header)) = next_csv_record(&mut reader, &mut line)? else {
+            return Ok(Vec::new());
+        };
+        let mut columns: Columns = Vec::with_capacity(header.len());
+        for name in &header {
+            columns.push((self.get(name.as_str()), SymbolColumn::new(self)));
+        }
+        while let Some((start_line, fields)) = next_csv_record(&mut reader, &mut line)? {
+            if fields.len() != columns.len() {
+                return Err(IngestError::FieldCount {
+                    line: start_line,
+                    expected: columns.len(),
+                    found: fields.len(),
+                });
+            }
+            for ((_, column), field) in columns.iter_mut().zip(&fields) {
+                column.push(field);
+            }
+        }
+        Ok(columns)
+    }
+
+    /// Reads a stream of JSON lines, where each line is a flat object, into a column per
+    /// key. String values are unescaped, and other values are interned as their JSON
+    /// text, like `"42"`, `"true"`, or `"null"`. Rows that are missing a key have an
+    /// empty string in that column. Blank lines are skipped.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let lines = r#"{"level": "INFO", "code": 200}
+    /// {"level": "WARN", "code": 429, "retry": true}
+    /// "#;
+    /// let symbol_table = SymbolTable::new();
+    /// let columns = symbol_table.read_json_lines(lines.as_bytes()).unwrap();
+    ///
+    /// let names: Vec<_> = columns.iter().map(|(name, _)| *name).collect();
+    /// assert_eq!(names, ["level", "code", "retry"]);
+    /// assert_eq!(columns[1].1.iter().collect::<Vec<_>>(), ["200", "429"]);
+    /// assert_eq!(columns[2].1.iter().collect::<Vec<_>>(), ["", "true"]);
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn read_json_lines<R: BufRead>(
+        &'strings self,
+        reader: R,
+    ) -> Result<Columns<'strings>, IngestError> {
+        let mut columns: Columns = Vec::new();
+        let mut positions = FxHashMap::default();
+        let mut rows = 0;
+        let empty = self.get("").index();
+        for (line, text) in reader.lines().enumerate() {
+            let text = text?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let fields =
+                parse_json_object(&text).ok_or(IngestError::InvalidJson { line: line + 1 })?;
+            let mut row = vec![None; columns.len()];
+            for (key, value) in fields {
+                let key = self.get(key);
+                let position = *positions.entry(key.index()).or_insert_with(|| {
+                    let indexes = vec![empty; rows];
+                    let column = SymbolColumn::from_indexes(self, indexes).unwrap();
+                    columns.push((key, column));
+                    row.push(None);
+                    columns.len() - 1
+                });
+                // When a key is repeated in the object, the last value wins.
+                row[position] = Some(value);
+            }
+            for ((_, column), value) in columns.iter_mut().zip(row) {
+                column.push(value.as_deref().unwrap_or_default());
+            }
+            rows += 1;
+        }
+        Ok(columns)
+    }
+}
+
+/// Reads the next non-blank record, and returns the line that it starts on along with
+/// its fields.
+fn next_csv_record<R: BufRead>(
+    reader: &mut R,
+    line: &mut usize,
+) -> Result<Option<(usize, Vec<String>)>, IngestError> {
+    let mut text = String::new();
+    let mut start_line = *line + 1;
+    loop {
+        if reader.read_line(&mut text)? == 0 {
+            if text.is_empty() {
+                return Ok(None);
+            }
+            return Err(IngestError::InvalidQuote { line: start_line });
+        }
+        *line += 1;
+        if text.trim_end_matches(['\r', '\n']).is_empty() {
+            text.clear();
+            start_line = *line + 1;
+            continue;
+        }
+        match parse_csv_record(&text) {
+            Ok(Some(fields)) => return Ok(Some((start_line, fields))),
+            // The record continues in a quoted field on the next line.
+            Ok(None) => {}
+            Err(()) => return Err(IngestError::InvalidQuote { line: start_line }),
+        }
+    }
+}
+
+/// Parses a record, returning `None` if it ends inside of a quoted field.
+fn parse_csv_record(text: &str) -> Result<Option<Vec<String>>, ()> {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    let mut quoted = false;
+    while let Some(ch) = chars.next() {
+        match (quoted, ch) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => {
+                quoted = false;
+                if !matches!(chars.peek(), None | Some(',') | Some('\r')) {
+                    return Err(());
+                }
+            }
+            (true, ch) => field.push(ch),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek().is_none() => {}
+            (false, ch) => field.push(ch),
+        }
+    }
+    if quoted {
+        return Ok(None);
+    }
+    fields.push(field);
+    Ok(Some(fields))
+}
+
+/// Parses a flat JSON object into its keys and values, in order.
+fn parse_json_object(text: &str) -> Option<Vec<(String, String)>> {
+    let mut rest = text.trim().strip_prefix('{')?.trim_start();
+    let mut fields = Vec::new();
+    if let Some(end) = rest.strip_prefix('}') {
+        return end.trim().is_empty().then_some(fields);
+    }
+    loop {
+        let (key, after_key) = parse_json_string(rest)?;
+        rest = after_key.trim_start().strip_prefix(':')?.trim_start();
+        let value;
+        if rest.starts_with('"') {
+            (value, rest) = parse_json_string(rest)?;
+        } else {
+            let end = rest.find([',', '}']).unwrap_or(rest.len());
+            let token = rest[..end].trim_end();
+            let is_scalar = matches!(token, "true" | "false" | "null") || is_json_number(token);
+            if !is_scalar {
+                return None;
+            }
+            value = token.to_owned();
+            rest = &rest[end..];
+        }
+        fields.push((key, value));
+        rest = rest.trim_start();
+        if let Some(after_comma) = rest.strip_prefix(',') {
+            rest = after_comma.trim_start();
+        } else {
+            return rest.strip_prefix('}')?.trim().is_empty().then_some(fields);
+        }
+    }
+}
+
+/// Whether a token is a JSON number, `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`,
+/// which is stricter than parsing an `f64`, as that also accepts `NaN`, `inf`, `+1`
+/// and `01`.
+fn is_json_number(token: &str) -> bool {
+    fn digits(text: &str) -> (&str, &str) {
+        let end = text
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(text.len());
+        text.split_at(end)
+    }
+    let rest = token.strip_prefix('-').unwrap_or(token);
+    let (integer, mut rest) = digits(rest);
+    if integer.is_empty() || (integer.len() > 1 && integer.starts_with('0')) {
+        return false;
+    }
+    if let Some(after_point) = rest.strip_prefix('.') {
+        let fraction;
+        (fraction, rest) = digits(after_point);
+        if fraction.is_empty() {
+            return false;
+        }
+    }
+    if let Some(after_e) = rest.strip_prefix(['e', 'E']) {
+        let after_sign = after_e.strip_prefix(['+', '-']).unwrap_or(after_e);
+        let exponent;
+        (exponent, rest) = digits(after_sign);
+        if exponent.is_empty() {
+            return false;
+        }
+    }
+    rest.is_empty()
+}
+
+/// Parses a quoted JSON string from the start of the text, and returns the unescaped
+/// string and the rest of the text.
+fn parse_json_string(text: &str) -> Option<(String, &str)> {
+    let contents = text.strip_prefix('"')?;
+    let mut escaped = false;
+    for (i, ch) in contents.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => {
+                let escaped = &contents[..i];
+                let mut string = String::new();
+                if !unescape_json(escaped, &mut string)? {
+                    string.push_str(escaped);
+                }
+                return Some((string, &contents[i + 1..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(column: &SymbolColumn) -> Vec<String> {
+        column.iter().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_csv() {
+        let symbol_table = SymbolTable::new();
+        let csv = "a,b\r\n\r\n\"multi\nline\",\"say \"\"hi\"\"\"\r\n,\n";
+        let columns = symbol_table.read_csv(csv.as_bytes()).unwrap();
+        assert_eq!(values(&columns[0].1), ["multi\nline", ""]);
+        assert_eq!(values(&columns[1].1), ["say \"hi\"", ""]);
+
+        assert!(symbol_table.read_csv("".as_bytes()).unwrap().is_empty());
+        assert!(matches!(
+            symbol_table.read_csv("a,b\n1\n".as_bytes()),
+            Err(IngestError::FieldCount {
+                line: 2,
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            symbol_table.read_csv("a\n\"open\n".as_bytes()),
+            Err(IngestError::InvalidQuote { line: 2 })
+        ));
+        assert!(matches!(
+            symbol_table.read_csv("a\n\"x\"y\n".as_bytes()),
+            Err(IngestError::InvalidQuote { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let symbol_table = SymbolTable::new();
+        let lines = "{}\n{\"k\": \"a\\\"b\", \"k\": null}\n\n{ \"n\" : -1.5e3 }\n";
+        let columns = symbol_table.read_json_lines(lines.as_bytes()).unwrap();
+        assert_eq!(values(&columns[0].1), ["", "null", ""]);
+        assert_eq!(values(&columns[1].1), ["", "", "-1.5e3"]);
+        for number in ["0", "-0", "10", "0.25", "1E+2", "2e-08"] {
+            assert!(is_json_number(number), "{number}");
+        }
+
+        for invalid in [
+            "[1]",
+            "{\"a\": {}}",
+            "{\"a\": 1,}",
+            "{\"a\": nope}",
+            "{\"a\" 1}",
+            "{\"a\": NaN}",
+            "{\"a\": inf}",
+            "{\"a\": infinity}",
+            "{\"a\": +1}",
+            "{\"a\": 01}",
+            "{\"a\": 1.}",
+            "{\"a\": .5}",
+            "{\"a\": 1e}",
+            "{\"a\": -}",
+        ] {
+            assert!(
+                matches!(
+                    symbol_table.read_json_lines(invalid.as_bytes()),
+                    Err(IngestError::InvalidJson { line: 1 })
+                ),
+                "{invalid}"
+            );
+        }
+    }
+}
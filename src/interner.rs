@@ -0,0 +1,315 @@
+//! An object-safe interface over all of the symbol table variants.
+
+use std::fmt;
+
+use crate::{
+    BorrowedSymbolTable, ForkedSymbolTable, LazySymbolTable, OverlaySymbolTable,
+    PersistentSymbolTable, SymbolIndex, SymbolResolver, SymbolTable, SymbolTableBuilder,
+    SyncSymbolTable,
+};
+
+/// An object-safe trait that is implemented by every table in this crate, so that a
+/// `&dyn DynInterner` can be passed across crate boundaries, such as to plugins,
+/// without making every function generic over the table type.
+///
+/// Some tables can't intern new strings through a shared reference. The
+/// [`SymbolResolver`] and the [`LazySymbolTable`] are read-only, the
+/// [`SymbolTableBuilder`] and the [`PersistentSymbolTable`] need a `&mut`, and the
+/// [`BorrowedSymbolTable`] needs a string that outlives its source buffer. For these,
+/// [`intern`](DynInterner::intern) only finds strings that have already been interned,
+/// and returns `None` otherwise. The [`LazySymbolTable`] also returns `None` when its
+/// file can't be read.
+///
+/// ```
+/// use gregtatum_symbol_table::{DynInterner, SymbolTable, SymbolTableBuilder};
+///
+/// fn plugin(interner: &dyn DynInterner) -> Option<&str> {
+///     let index = interner.intern("plugin")?;
+///     interner.resolve(index)
+/// }
+///
+/// let symbol_table = SymbolTable::new();
+/// assert_eq!(plugin(&symbol_table), Some("plugin"));
+/// assert_eq!(DynInterner::len(&symbol_table), 1);
+///
+/// let resolver = SymbolTableBuilder::new().build();
+/// assert_eq!(plugin(&resolver), None);
+/// ```
+pub trait DynInterner {
+    /// Interns a string and returns its index, or `None` if the string is new and the
+    /// table can't intern it.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    fn intern(&self, string: &str) -> Option<SymbolIndex>;
+
+    /// Interns a string and returns its index, or an error that explains why it
+    /// couldn't be interned. Tables that only fail because they can't intern new
+    /// strings return [`InternError::ReadOnly`], which is the default.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{DynInterner, InternError, SymbolTableBuilder};
+    ///
+    /// let resolver = SymbolTableBuilder::new().build();
+    /// assert_eq!(resolver.try_intern("new"), Err(InternError::ReadOnly));
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    fn try_intern(&self, string: &str) -> Result<SymbolIndex, InternError> {
+        self.intern(string).ok_or(InternError::ReadOnly)
+    }
+
+    /// Looks up the index of a string without interning it.
+    fn lookup(&self, string: &str) -> Option<SymbolIndex>;
+
+    /// Resolves an index to its string.
+    fn resolve(&self, index: SymbolIndex) -> Option<&str>;
+
+    /// The number of interned strings.
+    fn len(&self) -> usize;
+
+    /// Returns true if no strings have been interned.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The error for a string that a [`DynInterner`] couldn't intern, see
+/// [`try_intern`](DynInterner::try_intern).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InternError {
+    /// The string is new, and the table can't intern new strings.
+    ReadOnly,
+    /// The table is full.
+    Full {
+        /// The number of strings that the table can hold.
+        capacity: usize,
+    },
+    /// The string was rejected, such as by validation.
+    Invalid {
+        /// The reason that the string was rejected.
+        reason: String,
+    },
+}
+
+impl fmt::Display for InternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InternError::ReadOnly => write!(f, "The table can't intern new strings."),
+            InternError::Full { capacity } => {
+                write!(f, "The table is full, with {capacity} strings.")
+            }
+            InternError::Invalid { reason } => write!(f, "The string is invalid: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for InternError {}
+
+impl DynInterner for SymbolTable<'_> {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        let index = self.intern_index(string);
+        #[cfg(feature = "track-interning")]
+        self.track_call_site(index);
+        Some(index)
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        self.lookup_index(string)
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        self.get_by_index(index)
+    }
+
+    fn len(&self) -> usize {
+        SymbolTable::len(self)
+    }
+}
+
+impl DynInterner for BorrowedSymbolTable<'_> {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        DynInterner::lookup(self, string)
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        Some(self.maybe_get(string)?.index())
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        BorrowedSymbolTable::resolve(self, index)
+    }
+
+    fn len(&self) -> usize {
+        BorrowedSymbolTable::len(self)
+    }
+}
+
+impl DynInterner for SymbolTableBuilder {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        DynInterner::lookup(self, string)
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        SymbolTableBuilder::lookup(self, string)
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        SymbolTableBuilder::resolve(self, index)
+    }
+
+    fn len(&self) -> usize {
+        SymbolTableBuilder::len(self)
+    }
+}
+
+impl DynInterner for SymbolResolver {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        DynInterner::lookup(self, string)
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        SymbolResolver::lookup(self, string)
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        SymbolResolver::resolve(self, index)
+    }
+
+    fn len(&self) -> usize {
+        SymbolResolver::len(self)
+    }
+}
+
+impl DynInterner for ForkedSymbolTable<'_> {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        Some(ForkedSymbolTable::intern(self, string))
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        ForkedSymbolTable::lookup(self, string)
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        ForkedSymbolTable::resolve(self, index)
+    }
+
+    fn len(&self) -> usize {
+        ForkedSymbolTable::len(self)
+    }
+}
+
+impl DynInterner for OverlaySymbolTable {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        Some(OverlaySymbolTable::intern(self, string))
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        OverlaySymbolTable::lookup(self, string)
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        OverlaySymbolTable::resolve(self, index)
+    }
+
+    fn len(&self) -> usize {
+        OverlaySymbolTable::len(self)
+    }
+}
+
+impl DynInterner for PersistentSymbolTable {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        DynInterner::lookup(self, string)
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        PersistentSymbolTable::lookup(self, string)
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        PersistentSymbolTable::resolve(self, index)
+    }
+
+    fn len(&self) -> usize {
+        PersistentSymbolTable::len(self)
+    }
+}
+
+impl DynInterner for SyncSymbolTable {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        Some(SyncSymbolTable::intern(self, string))
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        SyncSymbolTable::lookup(self, string)
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        SyncSymbolTable::resolve(self, index)
+    }
+
+    fn len(&self) -> usize {
+        SyncSymbolTable::len(self)
+    }
+}
+
+impl DynInterner for LazySymbolTable {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        DynInterner::lookup(self, string)
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        LazySymbolTable::lookup(self, string).ok().flatten()
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        LazySymbolTable::resolve(self, index).ok().flatten()
+    }
+
+    fn len(&self) -> usize {
+        LazySymbolTable::len(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn intern_all(interner: &dyn DynInterner) -> Vec<Option<SymbolIndex>> {
+        ["a", "b", "a"]
+            .iter()
+            .map(|string| interner.intern(string))
+            .collect()
+    }
+
+    #[test]
+    fn test_all_variants() {
+        let symbol_table = SymbolTable::new();
+        assert_eq!(intern_all(&symbol_table), [Some(0), Some(1), Some(0)]);
+        assert_eq!(symbol_table.get("b").index(), 1);
+
+        let borrowed = BorrowedSymbolTable::new();
+        borrowed.get("b");
+        assert_eq!(intern_all(&borrowed), [None, Some(0), None]);
+
+        let mut builder = SymbolTableBuilder::new();
+        builder.insert("a");
+        assert_eq!(intern_all(&builder), [Some(0), None, Some(0)]);
+
+        let sync = SyncSymbolTable::new();
+        assert_eq!(intern_all(&*sync), [Some(0), Some(1), Some(0)]);
+
+        let path = std::env::temp_dir().join(format!("interner-{}.bin", std::process::id()));
+        symbol_table.save(&path).unwrap();
+        let lazy = LazySymbolTable::open(&path).unwrap();
+        assert_eq!(intern_all(&lazy), [Some(0), Some(1), Some(0)]);
+        assert_eq!(lazy.intern("c"), None);
+
+        let resolver = builder.build();
+        let interners: [&dyn DynInterner; 5] = [&symbol_table, &borrowed, &resolver, &*sync, &lazy];
+        for interner in interners {
+            assert!(!interner.is_empty());
+            let index = interner.lookup("a").or(interner.lookup("b")).unwrap();
+            assert!(interner.resolve(index).is_some());
+            assert_eq!(interner.resolve(interner.len()), None);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,30 @@
+//! A minimal JSON writer for the exporters in this crate.
+
+/// Appends a string as a quoted and escaped JSON string.
+pub(crate) fn push_string(out: &mut String, string: &str) {
+    out.push('"');
+    for ch in string.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch < ' ' => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escaping() {
+        let mut out = String::new();
+        push_string(&mut out, "a\"b\\c\nd\u{1}é");
+        assert_eq!(out, r#""a\"b\\c\nd\u0001é""#);
+    }
+}
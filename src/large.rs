@@ -0,0 +1,127 @@
+//! Large strings, like whole documents, which are kept out of the lookup map and the
+//! suffix index, so that interning one doesn't store extra copies of it.
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// The length in bytes above which strings are large, unless it's changed with
+/// [`SymbolTable::set_large_string_threshold`].
+pub const DEFAULT_LARGE_STRING_THRESHOLD: usize = 64 * 1024;
+
+impl<'strings> SymbolTable<'strings> {
+    /// Sets the length in bytes above which interned strings are large. A large string
+    /// is stored once, on its own, rather than also being copied into the lookup map,
+    /// and it is left out of the reversed copies of
+    /// [`index_suffixes`](struct.SymbolTable.html#method.index_suffixes). Large strings
+    /// are looked up by comparing them with the large strings of the same length. This
+    /// only applies to strings that are interned afterwards. The default is
+    /// [`DEFAULT_LARGE_STRING_THRESHOLD`].
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.set_large_string_threshold(10);
+    /// let document = symbol_table.get("A document that is longer than 10 bytes.");
+    /// let word = symbol_table.get("word");
+    /// assert!(document.is_large());
+    /// assert!(!word.is_large());
+    ///
+    /// // Large strings are still deduplicated.
+    /// assert_eq!(symbol_table.get("A document that is longer than 10 bytes."), document);
+    /// ```
+    pub fn set_large_string_threshold(&self, bytes: usize) {
+        self.large_string_threshold.set(Some(bytes));
+    }
+
+    /// The length in bytes above which interned strings are large, see
+    /// [`set_large_string_threshold`](struct.SymbolTable.html#method.set_large_string_threshold).
+    pub fn large_string_threshold(&self) -> usize {
+        self.large_string_threshold
+            .get()
+            .unwrap_or(DEFAULT_LARGE_STRING_THRESHOLD)
+    }
+
+    /// Returns true if a string of this length is stored as a large string.
+    pub(crate) fn is_large_len(&self, len: usize) -> bool {
+        len > self.large_string_threshold()
+    }
+
+    /// Adds a large string, which must not already be interned.
+    pub(crate) fn push_large(&self, string: String) -> SymbolIndex {
+        // The string is recorded as large before it's pushed, so that it's left out of
+        // the suffix index.
+        self.large_strings
+            .borrow_mut()
+            .entry(string.len())
+            .or_default()
+            .push(self.len());
+        self.push_unindexed(string)
+    }
+
+    /// Returns true if the string at an index was interned as a large string.
+    pub(crate) fn is_large_index(&self, index: SymbolIndex) -> bool {
+        let len = self.str(index).len();
+        self.large_strings
+            .borrow()
+            .get(&len)
+            .is_some_and(|indexes| indexes.contains(&index))
+    }
+
+    /// Looks up a large string by comparing it to the large strings of the same length.
+    pub(crate) fn lookup_large(&self, string: &str) -> Option<SymbolIndex> {
+        let large_strings = self.large_strings.borrow();
+        large_strings
+            .get(&string.len())?
+            .iter()
+            .copied()
+            .find(|&index| self.str(index) == string)
+    }
+
+    /// The indexes of every large string, in no particular order.
+    pub(crate) fn large_indexes(&self) -> Vec<SymbolIndex> {
+        self.large_strings
+            .borrow()
+            .values()
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
+impl<'strings> Symbol<'strings> {
+    /// Returns true if the symbol's string was interned as a large string, see
+    /// [`SymbolTable::set_large_string_threshold`](struct.SymbolTable.html#method.set_large_string_threshold).
+    /// For a slice, this is whether the string that it slices is large.
+    pub fn is_large(&self) -> bool {
+        self.symbol_table.is_large_index(self.index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_large_strings() {
+        let mut symbol_table = SymbolTable::new();
+        symbol_table.set_large_string_threshold(5);
+        symbol_table.index_suffixes();
+        let [a, b, c] =
+            ["aaaa.md", "bbbb.md", "c.md"].map(|string| symbol_table.get(string).index());
+        symbol_table.get("bbbb.md");
+        symbol_table.get("c.md");
+        assert_eq!(symbol_table.len(), 3);
+        assert_eq!(symbol_table.maybe_get("cccc.md"), None);
+
+        // Large strings aren't in the suffix index, but are still found.
+        let markdown: Vec<_> = symbol_table.ends_with_lookup(".md").collect();
+        assert_eq!(markdown, ["aaaa.md", "bbbb.md", "c.md"]);
+
+        let remapping = symbol_table.reindex_by_frequency();
+        let [a, b, c] =
+            [a, b, c].map(|index| Symbol::new(&symbol_table, remapping.get(index).unwrap()));
+        assert!(a.is_large() && b.is_large() && !c.is_large());
+        assert_eq!(symbol_table.maybe_get("bbbb.md"), Some(b));
+        assert!(a.slice(0..1).unwrap().is_large());
+    }
+}
@@ -0,0 +1,216 @@
+//! Lazy loading of saved tables, where the strings are only read from the file when
+//! they are resolved.
+
+use std::cell::{Cell, RefCell};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use elsa::FrozenMap;
+use fxhash::FxBuildHasher;
+
+use crate::persist::{invalid_data, MAGIC, VERSION};
+use crate::SymbolIndex;
+
+/// The number of strings in each block that is loaded.
+const BLOCK_LEN: usize = 1024;
+
+/// Reads a LEB128 varint, and returns it with its length in bytes.
+fn read_varint(reader: &mut impl BufRead) -> io::Result<(u64, u64)> {
+    let mut value: u64 = 0;
+    for i in 0..10 {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << (7 * i);
+        if byte[0] < 0x80 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(invalid_data(
+        "A varint in the saved symbol table is too large.",
+    ))
+}
+
+/// Reads a string as its varint length and UTF-8 bytes into the buffer, and returns the
+/// number of bytes that were read.
+fn read_string(reader: &mut impl BufRead, buffer: &mut Vec<u8>) -> io::Result<u64> {
+    let (len, varint_len) = read_varint(reader)?;
+    buffer.clear();
+    reader.take(len).read_to_end(buffer)?;
+    if buffer.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    if std::str::from_utf8(buffer).is_err() {
+        return Err(invalid_data(
+            "The saved symbol table contains invalid UTF-8.",
+        ));
+    }
+    Ok(varint_len + len)
+}
+
+/// A read-only view of a table that was saved with
+/// [`SymbolTable::save`](struct.SymbolTable.html#method.save), where the strings are
+/// loaded from the file in blocks the first time that one of them is resolved. Only the
+/// hashes of the strings are kept in memory up front, so opening a huge table takes
+/// little memory when most of its strings are never resolved. The file is read once
+/// when it's opened, to find the blocks and to hash the strings.
+///
+/// ```no_run
+/// use gregtatum_symbol_table::{LazySymbolTable, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// symbol_table.get("hello");
+/// symbol_table.get("world");
+/// symbol_table.save("symbols.bin").unwrap();
+///
+/// let lazy = LazySymbolTable::open("symbols.bin").unwrap();
+/// assert_eq!(lazy.len(), 2);
+/// assert_eq!(lazy.loaded_blocks(), 0);
+/// assert_eq!(lazy.lookup("world").unwrap(), Some(1));
+/// assert_eq!(lazy.resolve(0).unwrap(), Some("hello"));
+/// assert_eq!(lazy.loaded_blocks(), 1);
+/// ```
+pub struct LazySymbolTable {
+    reader: RefCell<BufReader<File>>,
+    len: usize,
+    // The file offset of the first string of each block.
+    block_offsets: Vec<u64>,
+    // The hash of each string with its index, sorted by hash.
+    hashes: Vec<(u64, u32)>,
+    blocks: FrozenMap<usize, Vec<String>, FxBuildHasher>,
+    loaded_blocks: Cell<usize>,
+}
+
+impl LazySymbolTable {
+    /// Opens a saved table. This returns an [`InvalidData`](io::ErrorKind::InvalidData)
+    /// error if the file is malformed.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<LazySymbolTable> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut header = [0; MAGIC.len() + 1];
+        reader.read_exact(&mut header)?;
+        if !header.starts_with(MAGIC) || header[MAGIC.len()] != VERSION {
+            return Err(invalid_data(
+                "The file isn't a saved symbol table with a supported version.",
+            ));
+        }
+        let (len, varint_len) = read_varint(&mut reader)?;
+        let len = usize::try_from(len).map_err(|_| invalid_data("The table is too large."))?;
+        let mut offset = header.len() as u64 + varint_len;
+
+        let mut block_offsets = Vec::new();
+        let mut hashes = Vec::new();
+        let mut buffer = Vec::new();
+        for index in 0..len {
+            if index % BLOCK_LEN == 0 {
+                block_offsets.push(offset);
+            }
+            offset += read_string(&mut reader, &mut buffer)?;
+            let string = std::str::from_utf8(&buffer).unwrap();
+            let index =
+                u32::try_from(index).map_err(|_| invalid_data("The table is too large."))?;
+            hashes.push((fxhash::hash64(string), index));
+        }
+        hashes.sort_unstable();
+
+        Ok(LazySymbolTable {
+            reader: RefCell::new(reader),
+            len,
+            block_offsets,
+            hashes,
+            blocks: FrozenMap::default(),
+            loaded_blocks: Cell::new(0),
+        })
+    }
+
+    /// The number of strings in the table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the table has no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of blocks of strings that have been loaded from the file.
+    pub fn loaded_blocks(&self) -> usize {
+        self.loaded_blocks.get()
+    }
+
+    /// The string for an index, loading its block from the file if it isn't loaded yet.
+    /// Returns `Ok(None)` if the index is out of range, and an error if the file can't
+    /// be read, such as when it changed after it was opened.
+    pub fn resolve(&self, index: SymbolIndex) -> io::Result<Option<&str>> {
+        if index >= self.len {
+            return Ok(None);
+        }
+        let block = self.load_block(index / BLOCK_LEN)?;
+        Ok(Some(&block[index % BLOCK_LEN]))
+    }
+
+    /// Looks up the index of a string. This only loads the blocks of the strings whose
+    /// hash matches, which is usually one block for a string that is in the table, and
+    /// none for one that isn't.
+    pub fn lookup(&self, string: &str) -> io::Result<Option<SymbolIndex>> {
+        let hash = fxhash::hash64(string);
+        let start = self.hashes.partition_point(|&(other, _)| other < hash);
+        for &(other, index) in &self.hashes[start..] {
+            if other != hash {
+                break;
+            }
+            if self.resolve(index as SymbolIndex)? == Some(string) {
+                return Ok(Some(index as SymbolIndex));
+            }
+        }
+        Ok(None)
+    }
+
+    fn load_block(&self, block: usize) -> io::Result<&[String]> {
+        if let Some(strings) = self.blocks.get(&block) {
+            return Ok(strings);
+        }
+        let start = block * BLOCK_LEN;
+        let block_len = BLOCK_LEN.min(self.len - start);
+        let mut reader = self.reader.borrow_mut();
+        reader.seek(SeekFrom::Start(self.block_offsets[block]))?;
+        let mut strings = Vec::with_capacity(block_len);
+        let mut buffer = Vec::new();
+        for _ in 0..block_len {
+            read_string(&mut *reader, &mut buffer)?;
+            strings.push(String::from_utf8(std::mem::take(&mut buffer)).unwrap());
+        }
+        self.loaded_blocks.set(self.loaded_blocks.get() + 1);
+        Ok(self.blocks.insert(block, strings))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SymbolTable;
+
+    #[test]
+    fn test_blocks_load_lazily() {
+        let path = std::env::temp_dir().join(format!("lazy-symbols-{}.bin", std::process::id()));
+        let symbol_table = SymbolTable::new();
+        for i in 0..BLOCK_LEN * 2 + 10 {
+            symbol_table.get(format!("string {i}"));
+        }
+        symbol_table.save(&path).unwrap();
+
+        let lazy = LazySymbolTable::open(&path).unwrap();
+        assert_eq!(lazy.len(), symbol_table.len());
+        assert_eq!(lazy.lookup("missing").unwrap(), None);
+        assert_eq!(lazy.loaded_blocks(), 0);
+        assert_eq!(
+            lazy.resolve(BLOCK_LEN * 2 + 9).unwrap(),
+            Some("string 2057")
+        );
+        assert_eq!(lazy.lookup("string 2050").unwrap(), Some(2050));
+        assert_eq!(lazy.loaded_blocks(), 1);
+        assert_eq!(lazy.resolve(5).unwrap(), Some("string 5"));
+        assert_eq!(lazy.resolve(BLOCK_LEN * 3).unwrap(), None);
+        assert_eq!(lazy.loaded_blocks(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
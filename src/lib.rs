@@ -5,12 +5,139 @@
 //! and easy storage of references to strings. The strings are accessed as [`Symbol`]s
 //! that have a [`fn str() -> &str`](struct.Symbol.html#method.str).
 
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeSet;
+use std::ffi::CString;
 use std::fmt;
 use std::marker::PhantomData;
-use std::ops::Range;
+use std::ops::{Index, Range};
 
 use elsa::{FrozenMap, FrozenVec};
-use fxhash::FxBuildHasher;
+use fxhash::{FxBuildHasher, FxHashMap};
+
+/// The hasher used for looking up strings in the [`SymbolTable`]. This is fxhash by
+/// default, which is fast for short identifiers, but isn't resistant to collision
+/// attacks. The `siphash` feature selects the standard library's randomly keyed
+/// SipHash instead, for tables that intern untrusted input.
+#[cfg(not(feature = "siphash"))]
+type StringHasher = FxBuildHasher;
+#[cfg(feature = "siphash")]
+type StringHasher = std::collections::hash_map::RandomState;
+
+#[cfg(feature = "arrow")]
+mod arrow;
+mod borrowed;
+mod case;
+mod case_style;
+mod column;
+mod delta;
+mod demangle;
+mod document;
+#[cfg(feature = "elf")]
+mod elf;
+mod enums;
+#[cfg(feature = "escape")]
+mod escape;
+mod export;
+mod ffi;
+mod file_path;
+mod fingerprint;
+mod fork;
+mod frame;
+mod frequency;
+mod graph;
+mod guard;
+pub mod headers;
+mod histogram;
+mod id;
+#[cfg(feature = "inflection")]
+mod inflection;
+#[cfg(feature = "ingest")]
+mod ingest;
+mod interner;
+mod json;
+mod large;
+mod lazy;
+pub mod mime;
+mod multimap;
+mod offsets;
+mod overlay;
+mod path;
+mod persist;
+mod persistent;
+mod pointer;
+mod profile;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+pub mod protocol;
+pub mod record;
+mod redact;
+mod remap;
+mod reserve;
+mod resolver;
+mod rope;
+mod scope;
+mod shared;
+mod sorted;
+mod suffix;
+mod sync_table;
+pub mod testing;
+mod token;
+#[cfg(feature = "track-interning")]
+mod tracking;
+mod trie;
+mod union_find;
+#[cfg(feature = "url")]
+mod url;
+pub mod varint;
+mod vocab;
+mod width;
+
+#[cfg(feature = "arrow")]
+pub use arrow::{ArrowDictionary, ArrowUtf8Array, ColumnarError};
+pub use borrowed::{BorrowedSymbol, BorrowedSymbolTable};
+pub use case_style::CaseStyle;
+pub use column::{Dictionary, SymbolColumn};
+pub use delta::{Delta, DeltaError, Mark};
+pub use document::DocumentTable;
+#[cfg(feature = "elf")]
+pub use elf::{ElfError, ElfSymbol};
+pub use enums::InternedEnum;
+pub use export::DuplicateStringError;
+pub use file_path::{normalize_path, PathCase};
+pub use fork::ForkedSymbolTable;
+pub use frame::{FrameId, FrameTable, StackFrame};
+pub use graph::{SymbolGraph, Traversal};
+pub use guard::LenGuard;
+pub use histogram::{LengthBucket, TableHistogram};
+pub use id::{SymbolId, SymbolIdError};
+#[cfg(feature = "ingest")]
+pub use ingest::{Columns, IngestError};
+pub use interner::{DynInterner, InternError};
+pub use large::DEFAULT_LARGE_STRING_THRESHOLD;
+pub use lazy::LazySymbolTable;
+pub use multimap::SymbolMultiMap;
+pub use overlay::OverlaySymbolTable;
+pub use path::PathSymbol;
+pub use persistent::PersistentSymbolTable;
+pub use profile::{ProfileReport, ProfileScope, ScopeProfile};
+pub use remap::IndexRemapping;
+pub use reserve::ReserveError;
+pub use resolver::{SymbolResolver, SymbolTableBuilder};
+pub use rope::{RopeId, RopeTable, VersionedSymbol};
+pub use scope::ScopedResolver;
+pub use shared::SharedSymbol;
+pub use sorted::UnsortedError;
+pub use sync_table::{SendSymbol, SyncSymbolTable};
+pub use token::{Token, TokenStream};
+#[cfg(feature = "track-interning")]
+pub use tracking::InterningHotSpot;
+pub use trie::SymbolTrie;
+pub use union_find::SymbolUnionFind;
+#[cfg(feature = "url")]
+pub use url::UrlSymbol;
+pub use vocab::VocabFormat;
 
 /// A cheap reference to a [`String`] in the [`SymbolTable`]. The only lifetime constraint
 /// is that it must outlive the StringTable. This makes it easy to operate on strings
@@ -78,6 +205,22 @@ impl<'strings> Symbol<'strings> {
         }
     }
 
+    /// The index of the string in the [`SymbolTable`]. Slices share the index of the
+    /// string that they are a slice of.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    /// let world = symbol_table.get("world");
+    /// assert_eq!(hello.index(), 0);
+    /// assert_eq!(world.index(), 1);
+    /// ```
+    pub fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
     /// Gets a slice of a string. This is a fast way to get substrings, but can
     /// incur penalties for string equality. A slice can be converted into a full
     /// symbol by running [`deslice`](struct.Symbol.html#method.deslice).
@@ -92,7 +235,7 @@ impl<'strings> Symbol<'strings> {
     /// let hello_slice = hello_world.slice(0..5).unwrap();
     /// assert_eq!(hello_slice, "hello");
     /// ```
-    pub fn slice(&self, range: Range<usize>) -> Option<Symbol> {
+    pub fn slice(&self, range: Range<usize>) -> Option<Symbol<'strings>> {
         let range = match self.range {
             Some(ref existing_range) => {
                 // Ensure the range is within the existing slice.
@@ -109,13 +252,195 @@ impl<'strings> Symbol<'strings> {
         // Get the original string.
         let string = self.symbol_table.str(self.index);
 
-        string.get(range.clone()).map(|_| Symbol {
+        string.get(range.clone())?;
+        self.symbol_table.record(|stats| stats.slices += 1);
+        Some(Symbol {
             index: self.index,
             range: Some((range.start as u32, range.end as u32)),
             symbol_table: self.symbol_table,
         })
     }
 
+    /// Gets a slice of a string like [`slice`](struct.Symbol.html#method.slice), but
+    /// rather than returning `None` for a range that is out of bounds or splits a char,
+    /// the range is clamped to the string and widened to the char boundaries around it,
+    /// so that every char that it touches is included. A reversed range is flipped.
+    /// This suits offsets that come from users, such as the selection in a text field.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let text = symbol_table.get("naïve café");
+    ///
+    /// // The ï is 2 bytes, and 3..4 is in the middle of it.
+    /// assert_eq!(text.slice(0..3), None);
+    /// assert_eq!(text.slice_clamped(0..3), "naï");
+    /// assert_eq!(text.slice_clamped(7..100), "café");
+    /// assert_eq!(text.slice_clamped(6..0), "naïve");
+    /// ```
+    pub fn slice_clamped(&self, range: Range<usize>) -> Symbol<'strings> {
+        let string = self.str();
+        let mut start = range.start.min(range.end).min(string.len());
+        while !string.is_char_boundary(start) {
+            start -= 1;
+        }
+        let mut end = range.start.max(range.end).min(string.len());
+        while !string.is_char_boundary(end) {
+            end += 1;
+        }
+        let offset = self.range().start;
+        self.symbol_table.record(|stats| stats.slices += 1);
+        Symbol {
+            index: self.index,
+            range: Some(((offset + start) as u32, (offset + end) as u32)),
+            symbol_table: self.symbol_table,
+        }
+    }
+
+    /// Returns true if the symbol is a slice of another string.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello_world = symbol_table.get("hello world");
+    /// assert!(!hello_world.is_slice());
+    /// assert!(hello_world.slice(0..5).unwrap().is_slice());
+    /// ```
+    pub fn is_slice(&self) -> bool {
+        self.range.is_some()
+    }
+
+    /// Returns the full symbol that this symbol is a slice of. Full symbols are their
+    /// own parent.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello_world = symbol_table.get("hello world");
+    /// let world = hello_world.slice(6..11).unwrap();
+    /// assert_eq!(world.parent(), hello_world);
+    /// assert_eq!(hello_world.parent(), hello_world);
+    /// ```
+    pub fn parent(&self) -> Symbol<'strings> {
+        Symbol::new(self.symbol_table, self.index)
+    }
+
+    /// Returns the byte range of this symbol within its
+    /// [`parent`](struct.Symbol.html#method.parent). For full symbols this is the
+    /// range of the entire string.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello_world = symbol_table.get("hello world");
+    /// let world = hello_world.slice(6..11).unwrap();
+    /// assert_eq!(world.range(), 6..11);
+    /// assert_eq!(hello_world.range(), 0..11);
+    /// ```
+    pub fn range(&self) -> Range<usize> {
+        match self.range {
+            Some((start, end)) => start as usize..end as usize,
+            None => 0..self.symbol_table.str(self.index).len(),
+        }
+    }
+
+    /// Joins two spans of the same parent into the smallest slice covering both, such
+    /// as when building the span of an expression from the spans of its tokens.
+    /// Returns `None` if the symbols have different parents.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let source = symbol_table.get("let x = a + b;");
+    /// let a = source.slice(8..9).unwrap();
+    /// let b = source.slice(12..13).unwrap();
+    /// assert_eq!(a.join_span(b).unwrap(), "a + b");
+    /// assert_eq!(b.join_span(a).unwrap(), "a + b");
+    ///
+    /// let other = symbol_table.get("other");
+    /// assert_eq!(a.join_span(other), None);
+    /// ```
+    pub fn join_span(&self, other: Symbol<'strings>) -> Option<Symbol<'strings>> {
+        let (a, b) = self.same_parent_ranges(other)?;
+        self.parent().slice(a.start.min(b.start)..a.end.max(b.end))
+    }
+
+    /// Returns the overlapping part of two spans of the same parent. Returns `None` if
+    /// the symbols have different parents, or if the spans don't overlap.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let source = symbol_table.get("hello world");
+    /// let hello_wo = source.slice(0..8).unwrap();
+    /// let lo_world = source.slice(3..11).unwrap();
+    /// assert_eq!(hello_wo.intersect_span(lo_world).unwrap(), "lo wo");
+    ///
+    /// let hello = source.slice(0..5).unwrap();
+    /// let world = source.slice(6..11).unwrap();
+    /// assert_eq!(hello.intersect_span(world), None);
+    /// ```
+    pub fn intersect_span(&self, other: Symbol<'strings>) -> Option<Symbol<'strings>> {
+        let (a, b) = self.same_parent_ranges(other)?;
+        let start = a.start.max(b.start);
+        let end = a.end.min(b.end);
+        if start >= end {
+            return None;
+        }
+        self.parent().slice(start..end)
+    }
+
+    fn same_parent_ranges(&self, other: Symbol<'strings>) -> Option<(Range<usize>, Range<usize>)> {
+        if self.index != other.index || !std::ptr::eq(self.symbol_table, other.symbol_table) {
+            return None;
+        }
+        Some((self.range(), other.range()))
+    }
+
+    /// The same as [`slice`](struct.Symbol.html#method.slice), but the result is
+    /// cached per parent symbol and range in the [`SymbolTable`]. Repeatedly slicing
+    /// the same span of a hot token is then a lookup that returns the canonical slice,
+    /// rather than re-verifying the bounds and char boundaries of the range.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello_world = symbol_table.get("hello world");
+    ///
+    /// let hello = hello_world.slice_cached(0..5).unwrap();
+    /// assert_eq!(hello, "hello");
+    /// assert_eq!(hello_world.slice_cached(0..5), Some(hello));
+    /// assert_eq!(hello_world.slice_cached(0..50), None);
+    /// ```
+    pub fn slice_cached(&self, range: Range<usize>) -> Option<Symbol<'strings>> {
+        let key = (self.key(), range.start, range.end);
+        let cached = self.symbol_table.slices.borrow().get(&key).copied();
+        let range = match cached {
+            Some(range) => {
+                self.symbol_table
+                    .record(|stats| stats.slice_cache_hits += 1);
+                range
+            }
+            None => {
+                let range = self.slice(range).and_then(|symbol| symbol.range);
+                self.symbol_table.slices.borrow_mut().insert(key, range);
+                range
+            }
+        };
+        range.map(|range| Symbol {
+            index: self.index,
+            range: Some(range),
+            symbol_table: self.symbol_table,
+        })
+    }
+
     /// Turns a string slice into a full symbol. This ensures equality checks are
     /// simple index equality checks rather than full string equality checks.
     ///
@@ -133,13 +458,73 @@ impl<'strings> Symbol<'strings> {
     /// // But slices can be turned back into full Symbols for cheap comparisons.
     /// assert_eq!(hello_slice.deslice(), hello);
     /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
     pub fn deslice(self) -> Symbol<'strings> {
         if self.range.is_some() {
+            self.symbol_table.record(|stats| stats.deslices += 1);
             self.symbol_table.get(self.str())
         } else {
             self
         }
     }
+
+    /// Compares the symbol to a string constant, such as a keyword. For full symbols the
+    /// constant's index is remembered by its pointer, so after the first comparison
+    /// against a given constant, no hashing or string comparison is needed. Slices are
+    /// compared directly against the string.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// const LET: &str = "let";
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let keyword = symbol_table.get("let");
+    /// let identifier = symbol_table.get("value");
+    ///
+    /// assert!(keyword.is(LET));
+    /// assert!(!identifier.is(LET));
+    ///
+    /// let statement = symbol_table.get("let value");
+    /// assert!(statement.slice(0..3).unwrap().is(LET));
+    /// ```
+    pub fn is(&self, string: &'static str) -> bool {
+        if self.range.is_some() {
+            return self.str() == string;
+        }
+        let key = (string.as_ptr() as usize, string.len());
+        if let Some(index) = self.symbol_table.static_indexes.borrow().get(&key) {
+            self.symbol_table
+                .record(|stats| stats.static_cache_hits += 1);
+            return self.index == *index;
+        }
+        match self.symbol_table.lookup_index(string) {
+            Some(index) => {
+                self.symbol_table
+                    .static_indexes
+                    .borrow_mut()
+                    .insert(key, index);
+                self.index == index
+            }
+            // Every full symbol is interned, so if the constant isn't interned, then
+            // they can't be equal. This isn't cached, as the constant could be interned
+            // later.
+            None => false,
+        }
+    }
+
+    fn key(&self) -> SymbolKey {
+        (self.index, self.range)
+    }
+
+    /// Like `deslice`, but doesn't intern the string if it's not already present.
+    fn full_symbol(self) -> Option<Symbol<'strings>> {
+        if self.range.is_some() {
+            self.symbol_table.maybe_get(self.str())
+        } else {
+            Some(self)
+        }
+    }
 }
 
 impl<'strings> PartialEq<String> for Symbol<'strings> {
@@ -154,6 +539,42 @@ impl<'strings> PartialEq<&str> for Symbol<'strings> {
     }
 }
 
+impl<'strings> PartialEq<str> for Symbol<'strings> {
+    fn eq(&self, other: &str) -> bool {
+        self.str() == other
+    }
+}
+
+impl<'strings> PartialEq<Cow<'_, str>> for Symbol<'strings> {
+    fn eq(&self, other: &Cow<'_, str>) -> bool {
+        self.str() == other
+    }
+}
+
+impl<'strings> PartialEq<Symbol<'strings>> for String {
+    fn eq(&self, other: &Symbol<'strings>) -> bool {
+        self == other.str()
+    }
+}
+
+impl<'strings> PartialEq<Symbol<'strings>> for &str {
+    fn eq(&self, other: &Symbol<'strings>) -> bool {
+        *self == other.str()
+    }
+}
+
+impl<'strings> PartialEq<Symbol<'strings>> for str {
+    fn eq(&self, other: &Symbol<'strings>) -> bool {
+        self == other.str()
+    }
+}
+
+impl<'strings> PartialEq<Symbol<'strings>> for Cow<'_, str> {
+    fn eq(&self, other: &Symbol<'strings>) -> bool {
+        self == other.str()
+    }
+}
+
 /// Cheap string equality checks. Slices may invoke full string checking.
 impl<'strings> PartialEq for Symbol<'strings> {
     fn eq(&self, other: &Self) -> bool {
@@ -163,14 +584,60 @@ impl<'strings> PartialEq for Symbol<'strings> {
             }
             // Even though the indexes match, the subranges could point to equivalent
             // strings. This requires a full string comparison.
-            return self.str() == other.str();
+            self.symbol_table
+                .record(|stats| stats.string_comparisons += 1);
+            return str_eq(self.str(), other.str());
         }
         if self.range.is_none() && other.range.is_none() {
             // The is no slice range, and the indexes differ, so they must be different.
             return false;
         }
         // Do a full string comparison.
-        self.str() == other.str()
+        self.symbol_table
+            .record(|stats| stats.string_comparisons += 1);
+        str_eq(self.str(), other.str())
+    }
+}
+
+/// The string comparison used when slices are compared. Strings of different lengths
+/// are rejected without looking at their bytes, and identical spans are accepted
+/// without comparing them. Short strings such as tokens are compared 8 or 16 bytes at a
+/// time, where the 16 byte lanes compile to vector comparisons on targets with SIMD.
+/// This avoids the call overhead of `memcmp`, which is still used for long strings.
+#[inline]
+fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    if std::ptr::eq(a.as_ptr(), b.as_ptr()) {
+        return true;
+    }
+    fn lane<const N: usize>(bytes: &[u8], start: usize) -> [u8; N] {
+        bytes[start..start + N]
+            .try_into()
+            .expect("The lane is in bounds.")
+    }
+    let len = a.len();
+    match len {
+        0..=7 => a == b,
+        // Compare two overlapping 8 byte lanes.
+        8..=16 => {
+            u64::from_ne_bytes(lane(a, 0)) == u64::from_ne_bytes(lane(b, 0))
+                && u64::from_ne_bytes(lane(a, len - 8)) == u64::from_ne_bytes(lane(b, len - 8))
+        }
+        // Compare 16 byte lanes, where the last lane overlaps the previous one.
+        17..=64 => {
+            let mut start = 0;
+            while start + 16 < len {
+                if u128::from_ne_bytes(lane(a, start)) != u128::from_ne_bytes(lane(b, start)) {
+                    return false;
+                }
+                start += 16;
+            }
+            u128::from_ne_bytes(lane(a, len - 16)) == u128::from_ne_bytes(lane(b, len - 16))
+        }
+        _ => a == b,
     }
 }
 
@@ -201,6 +668,61 @@ impl<'strings> From<Symbol<'strings>> for String {
 /// An index into the symbol vector.
 pub type SymbolIndex = usize;
 
+/// Uniquely identifies a symbol or slice of a symbol, without a reference to the table.
+type SymbolKey = (SymbolIndex, Option<(u32, u32)>);
+
+/// A symbol, and the range that it is being sliced by.
+type SliceKey = (SymbolKey, usize, usize);
+
+/// Counts of how symbols are being used, to help tune the performance of an application.
+/// For instance, many string comparisons could mean that it's worth eagerly deslicing
+/// symbols. See [`stats`](struct.SymbolTable.html#method.stats).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SymbolStats {
+    /// The number of slices created by [`Symbol::slice`].
+    pub slices: usize,
+    /// The number of slices turned into full symbols by [`Symbol::deslice`].
+    pub deslices: usize,
+    /// The number of symbol equality checks that needed to compare the strings,
+    /// because at least one of the symbols was a slice.
+    pub string_comparisons: usize,
+    /// The number of slices returned from the cache of [`Symbol::slice_cached`].
+    pub slice_cache_hits: usize,
+    /// The number of constants that were found by pointer in [`Symbol::is`].
+    pub static_cache_hits: usize,
+    /// The number of strings returned from the cache of
+    /// [`SymbolTable::cached_format`].
+    pub format_cache_hits: usize,
+}
+
+/// The error for resolving an index that is out of range of the [`SymbolTable`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidIndex {
+    /// The index that was resolved.
+    pub index: SymbolIndex,
+    /// The amount of strings in the table at the time.
+    pub len: usize,
+}
+
+impl fmt::Display for InvalidIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The symbol index {} is out of range of a table with {} strings.",
+            self.index, self.len
+        )
+    }
+}
+
+impl std::error::Error for InvalidIndex {}
+
+/// A callback registered with
+/// [`on_memory_threshold`](struct.SymbolTable.html#method.on_memory_threshold).
+struct MemoryThreshold {
+    bytes: usize,
+    callback: Box<dyn FnMut(usize)>,
+}
+
 /// Stores a unique list of strings, so that strings can be operated upon via stable
 /// indexes, which are stored in the [`Symbol`] type. This makes for cheap comparisons
 /// and easy storage of references to strings. The strings are accessed as [`Symbol`]s
@@ -229,10 +751,86 @@ pub type SymbolIndex = usize;
 /// // But slices can be turned back into full Symbols for cheap comparisons.
 /// assert_eq!(hello_slice.deslice(), hello);
 /// ```
+///
+/// ## Pointer stability
+///
+/// Every `&str` that the table hands out points into storage that is never moved or
+/// freed while the table is alive, even as more strings are interned. It's part of the
+/// API contract that a `&str` for a string keeps the same address for the lifetime of
+/// the table, so raw pointers to the resolved strings can be cached, for instance in an
+/// FFI layer. Debug builds assert this whenever a string is resolved.
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTable;
+///
+/// let symbol_table = SymbolTable::new();
+/// let hello = symbol_table.get("hello").str().as_ptr();
+/// for i in 0..1000 {
+///     symbol_table.get(format!("string {i}"));
+/// }
+/// assert_eq!(symbol_table.get("hello").str().as_ptr(), hello);
+/// ```
 #[derive(Default)]
 pub struct SymbolTable<'strings> {
+    // Any state that is keyed by index also needs to be remapped in
+    // reindex_by_frequency.
     symbols: FrozenVec<String>,
-    indexes: FrozenMap<String, Box<SymbolIndex>, FxBuildHasher>,
+    indexes: FrozenMap<String, Box<SymbolIndex>, StringHasher>,
+    tags: RefCell<FxHashMap<String, BTreeSet<SymbolIndex>>>,
+    // Maps the pointer and length of a &'static str to its index.
+    static_indexes: RefCell<FxHashMap<(usize, usize), SymbolIndex>>,
+    formatted: RefCell<FxHashMap<Vec<SymbolKey>, SymbolIndex>>,
+    slices: RefCell<FxHashMap<SliceKey, Option<(u32, u32)>>>,
+    line_starts: RefCell<FxHashMap<SymbolIndex, Vec<usize>>>,
+    // The char offsets at checkpoints of long strings.
+    offset_checkpoints: RefCell<FxHashMap<SymbolIndex, Vec<offsets::Checkpoint>>>,
+    stats: Cell<SymbolStats>,
+    cstrings: FrozenMap<SymbolIndex, CString, FxBuildHasher>,
+    utf16: FrozenMap<SymbolKey, Vec<u16>, FxBuildHasher>,
+    // The components of the paths interned with get_path, keyed by themselves so that
+    // each PathSymbol can borrow them.
+    paths: FrozenMap<Vec<SymbolIndex>, Vec<SymbolIndex>, FxBuildHasher>,
+    // The start address of each non-empty string, sorted lazily by symbol_of, along
+    // with how many strings have been added to it.
+    addresses: RefCell<Vec<(usize, SymbolIndex)>>,
+    addressed_len: Cell<usize>,
+    // The address of each string when it was interned, to assert pointer stability.
+    #[cfg(debug_assertions)]
+    interned_addresses: RefCell<Vec<usize>>,
+    profiler: RefCell<profile::Profiler>,
+    // The reversed strings, if enabled by index_suffixes.
+    suffixes: RefCell<Option<suffix::SuffixIndex>>,
+    redaction: RefCell<redact::Redaction>,
+    // Reused by the unescaping methods, so that only new strings are allocated.
+    #[cfg(feature = "escape")]
+    unescape_buffer: RefCell<String>,
+    // Counts the calls to get for each call site and string.
+    #[cfg(feature = "track-interning")]
+    call_sites: RefCell<FxHashMap<(&'static std::panic::Location<'static>, SymbolIndex), usize>>,
+    // The number of calls to get for each index.
+    uses: RefCell<Vec<usize>>,
+    // Caches the conversions of identifiers to naming conventions.
+    case_styles: RefCell<FxHashMap<(SymbolIndex, CaseStyle), SymbolIndex>>,
+    // The display width of each index, once it has been computed.
+    display_widths: RefCell<Vec<Option<u32>>>,
+    memory_usage: Cell<usize>,
+    memory_thresholds: RefCell<Vec<MemoryThreshold>>,
+    path_canonicalizer: RefCell<Option<file_path::PathCanonicalizer>>,
+    path_case: Cell<PathCase>,
+    demangler: RefCell<Option<demangle::Demangler>>,
+    // The links between mangled names and their demangled names.
+    demangled: RefCell<FxHashMap<SymbolIndex, SymbolIndex>>,
+    mangled: RefCell<FxHashMap<SymbolIndex, SymbolIndex>>,
+    // Maps the case folded spelling of a path to the first spelling that was interned.
+    folded_paths: RefCell<FxHashMap<String, SymbolIndex>>,
+    large_string_threshold: Cell<Option<usize>>,
+    // The indexes of the large strings, by their length in bytes.
+    large_strings: RefCell<FxHashMap<usize, Vec<SymbolIndex>>>,
+    // The number of strings loaded by from_sorted_unique, which are looked up by a
+    // binary search rather than through the indexes.
+    sorted_len: usize,
+    // Increases when the table is cleared or reindexed, to detect stale identifiers.
+    generation: u32,
     // Enforces the self lifetime.
     lifetime: PhantomData<&'strings ()>,
 }
@@ -252,6 +850,16 @@ impl<'strings> SymbolTable<'strings> {
         }
     }
 
+    /// Creates a table where each string's index is its position in the list. The
+    /// strings must be unique.
+    fn preloaded(strings: &[&str]) -> SymbolTable<'strings> {
+        let symbol_table = SymbolTable::new();
+        for string in strings {
+            symbol_table.push_string(string.to_string());
+        }
+        symbol_table
+    }
+
     /// Interns a string into the [`SymbolTable`] if it doesn't yet exists and returns a
     /// [`Symbol`]. If the [`String`] has already been interned, then its index is looked
     /// up via a HashMap and a [`Symbol`] is returned.
@@ -264,15 +872,89 @@ impl<'strings> SymbolTable<'strings> {
     /// assert_eq!(hello, "hello");
     /// assert_eq!(world, "world");
     /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
     pub fn get<T: Into<String> + AsRef<str>>(&'strings self, string: T) -> Symbol<'strings> {
-        if let Some(symbol) = self.maybe_get(string.as_ref()) {
-            return symbol;
+        let symbol = match self.maybe_get(string.as_ref()) {
+            Some(symbol) => symbol,
+            None => Symbol::new(self, self.push_new(string)),
+        };
+        self.record_use(symbol.index);
+        #[cfg(feature = "track-interning")]
+        self.track_call_site(symbol.index);
+        symbol
+    }
+
+    /// Adds a new string to the table, which must not already be interned. Strings in a
+    /// redacted namespace are only stored in their redacted form.
+    fn push_new<T: Into<String> + AsRef<str>>(&self, string: T) -> SymbolIndex {
+        match self.redact(string.as_ref()) {
+            Some((hash, prefix)) => self.push_redacted(hash, &prefix),
+            None => self.push_string(string.into()),
         }
-        let index = self.len();
-        let string: String = string.into();
-        self.symbols.push(string.clone());
+    }
+
+    /// Adds a new string to the table. The string must not already be interned.
+    fn push_string(&self, string: String) -> SymbolIndex {
+        if self.is_large_len(string.len()) {
+            return self.push_large(string);
+        }
+        let index = self.push_unindexed(string.clone());
         self.indexes.insert(string, Box::new(index));
-        Symbol::new(&self, index)
+        index
+    }
+
+    /// Adds a new string to the list of strings, without making it possible to look it
+    /// up by its contents.
+    fn push_unindexed(&self, string: String) -> SymbolIndex {
+        let index = self.len();
+        assert!(
+            index <= u32::MAX as usize,
+            "A SymbolTable can hold at most u32::MAX + 1 strings, so that ids fit in a u32."
+        );
+        let memory_usage = self.memory_usage.get() + 2 * string.len();
+        self.symbols.push(string);
+        self.record_address(index);
+        self.profile_intern(self.str(index).len());
+        self.index_suffix(index);
+        self.memory_usage.set(memory_usage);
+        self.check_memory_thresholds(memory_usage);
+        index
+    }
+
+    /// Looks up the index of a string, including strings in redacted namespaces.
+    fn lookup_index(&self, string: &str) -> Option<SymbolIndex> {
+        self.lookup_redacted(string)
+            .or_else(|| self.lookup_sorted(string))
+            .or_else(|| self.indexes.get(string).copied())
+            .or_else(|| self.lookup_large(string))
+    }
+
+    /// Interns the first `len` bytes of a fixed-size buffer, such as a token read from
+    /// a binary protocol. The bytes are validated as UTF-8 and looked up without
+    /// constructing a [`String`], which is only allocated if the string is new. Returns
+    /// `None` if `len` is larger than the buffer, or if the bytes aren't valid UTF-8.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    ///
+    /// let mut buffer = [0; 16];
+    /// buffer[..5].copy_from_slice(b"hello");
+    /// assert_eq!(symbol_table.get_in_place(&buffer, 5), Some(hello));
+    ///
+    /// assert_eq!(symbol_table.get_in_place(&buffer, 17), None);
+    /// assert_eq!(symbol_table.get_in_place(&[0xff; 4], 4), None);
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn get_in_place<const N: usize>(
+        &'strings self,
+        bytes: &[u8; N],
+        len: usize,
+    ) -> Option<Symbol<'strings>> {
+        let string = std::str::from_utf8(bytes.get(..len)?).ok()?;
+        Some(self.get(string))
     }
 
     /// Gets an [`Symbol`] for a string only if it already exists.
@@ -291,9 +973,8 @@ impl<'strings> SymbolTable<'strings> {
     /// assert_eq!(world, None);
     /// ```
     pub fn maybe_get<T: AsRef<str>>(&'strings self, string: T) -> Option<Symbol<'strings>> {
-        self.indexes
-            .get(string.as_ref())
-            .map(|index| Symbol::new(&self, *index))
+        self.lookup_index(string.as_ref())
+            .map(|index| Symbol::new(self, index))
     }
 
     /// Check if the `SymbolTable` has a string.
@@ -331,6 +1012,21 @@ impl<'strings> SymbolTable<'strings> {
         self.symbols.len()
     }
 
+    /// Returns true if no strings have been interned.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// assert!(symbol_table.is_empty());
+    ///
+    /// symbol_table.get("hello");
+    /// assert!(!symbol_table.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Iterate through all of the strings. This does not iterate through [`Symbol`]s as
     /// the iterator could outlive the [`SymbolTable`].
     ///
@@ -360,12 +1056,521 @@ impl<'strings> SymbolTable<'strings> {
         self.symbols.iter()
     }
 
+    /// Returns true if both tables have the same strings, even if the strings have
+    /// different indexes. Use `==` to also compare the indexes.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let expected = SymbolTable::new();
+    /// let actual = SymbolTable::new();
+    /// for string in ["a", "b"] {
+    ///     expected.get(string);
+    /// }
+    /// for string in ["b", "a"] {
+    ///     actual.get(string);
+    /// }
+    /// assert!(actual.set_eq(&expected));
+    /// assert_ne!(actual, expected);
+    /// ```
+    pub fn set_eq(&self, other: &SymbolTable) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|string| other.lookup_index(string).is_some())
+    }
+
+    /// Assigns a tag to a symbol, so that a subset of the table can be cheaply iterated
+    /// over with [`iter_tagged`](struct.SymbolTable.html#method.iter_tagged). A symbol
+    /// can have any number of tags. Tags apply to full strings, so slices are desliced
+    /// before being tagged. Returns false if the symbol already had the tag.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let no_unused = symbol_table.get("no-unused");
+    /// let max_len = symbol_table.get("max-len");
+    /// symbol_table.get("some other string");
+    ///
+    /// assert!(symbol_table.tag(no_unused, "rule"));
+    /// assert!(symbol_table.tag(max_len, "rule"));
+    /// assert!(!symbol_table.tag(max_len, "rule"), "The tag was already applied.");
+    ///
+    /// let rules: Vec<_> = symbol_table.iter_tagged("rule").collect();
+    /// assert_eq!(rules, ["no-unused", "max-len"]);
+    /// ```
+    pub fn tag(&'strings self, symbol: Symbol<'strings>, tag: &str) -> bool {
+        let index = symbol.deslice().index;
+        let mut tags = self.tags.borrow_mut();
+        match tags.get_mut(tag) {
+            Some(indexes) => indexes.insert(index),
+            None => {
+                tags.insert(tag.into(), BTreeSet::from([index]));
+                true
+            }
+        }
+    }
+
+    /// Removes a tag from a symbol. Returns false if the symbol didn't have the tag.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    /// symbol_table.tag(hello, "greeting");
+    ///
+    /// assert!(symbol_table.untag(hello, "greeting"));
+    /// assert!(!symbol_table.has_tag(hello, "greeting"));
+    /// assert!(!symbol_table.untag(hello, "greeting"));
+    /// ```
+    pub fn untag(&'strings self, symbol: Symbol<'strings>, tag: &str) -> bool {
+        let Some(full_symbol) = symbol.full_symbol() else {
+            return false;
+        };
+        match self.tags.borrow_mut().get_mut(tag) {
+            Some(indexes) => indexes.remove(&full_symbol.index),
+            None => false,
+        }
+    }
+
+    /// Checks if a symbol has a tag.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    /// let world = symbol_table.get("world");
+    /// symbol_table.tag(hello, "greeting");
+    ///
+    /// assert!(symbol_table.has_tag(hello, "greeting"));
+    /// assert!(!symbol_table.has_tag(world, "greeting"));
+    /// ```
+    pub fn has_tag(&'strings self, symbol: Symbol<'strings>, tag: &str) -> bool {
+        let Some(full_symbol) = symbol.full_symbol() else {
+            return false;
+        };
+        self.tags
+            .borrow()
+            .get(tag)
+            .is_some_and(|indexes| indexes.contains(&full_symbol.index))
+    }
+
+    /// Iterates over the symbols that have been given a tag, in index order. See
+    /// [`tag`](struct.SymbolTable.html#method.tag) for an example.
+    pub fn iter_tagged(&'strings self, tag: &str) -> impl Iterator<Item = Symbol<'strings>> {
+        let indexes: Vec<SymbolIndex> = match self.tags.borrow().get(tag) {
+            Some(indexes) => indexes.iter().copied().collect(),
+            None => Vec::new(),
+        };
+        indexes
+            .into_iter()
+            .map(move |index| Symbol::new(self, index))
+    }
+
+    /// Resolves the string for an index, or returns `None` if it's out of range. This
+    /// is the non-panicking version of indexing into the table.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    ///
+    /// assert_eq!(&symbol_table[hello.index()], "hello");
+    /// assert_eq!(symbol_table.get_by_index(hello.index()), Some("hello"));
+    /// assert_eq!(symbol_table.get_by_index(1), None);
+    /// ```
+    pub fn get_by_index(&self, index: SymbolIndex) -> Option<&str> {
+        let string = self.symbols.get(index)?;
+        self.assert_address(index, string);
+        Some(string)
+    }
+
+    /// Resolves a list of indexes to their strings, in order, such as when serializing
+    /// a record of symbols. This panics if an index is out of range, see
+    /// [`try_resolve_many`](struct.SymbolTable.html#method.try_resolve_many) for a
+    /// fallible version.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    /// let world = symbol_table.get("world");
+    ///
+    /// let indexes = [world.index(), hello.index(), world.index()];
+    /// assert_eq!(symbol_table.resolve_many(&indexes), ["world", "hello", "world"]);
+    /// ```
+    pub fn resolve_many(&self, indexes: &[SymbolIndex]) -> Vec<&str> {
+        let len = self.len();
+        indexes
+            .iter()
+            .map(|&index| {
+                assert!(index < len, "The index {index} is out of range.");
+                self.str(index)
+            })
+            .collect()
+    }
+
+    /// Resolves a list of indexes to their strings, in order. Returns an error for the
+    /// first index that is out of range.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{InvalidIndex, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    ///
+    /// assert_eq!(symbol_table.try_resolve_many(&[0, 0]), Ok(vec!["hello", "hello"]));
+    /// assert_eq!(
+    ///     symbol_table.try_resolve_many(&[0, 1]),
+    ///     Err(InvalidIndex { index: 1, len: 1 })
+    /// );
+    /// ```
+    pub fn try_resolve_many(&self, indexes: &[SymbolIndex]) -> Result<Vec<&str>, InvalidIndex> {
+        indexes
+            .iter()
+            .map(|&index| self.try_resolve(index))
+            .collect()
+    }
+
+    /// Resolves the string for an index, returning an [`InvalidIndex`] error if it's out
+    /// of range. This is useful for validating indexes that come from outside of the
+    /// table, such as persisted indexes that could be corrupted.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{InvalidIndex, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("hello");
+    ///
+    /// assert_eq!(symbol_table.try_resolve(0), Ok("hello"));
+    /// assert_eq!(symbol_table.try_resolve(5), Err(InvalidIndex { index: 5, len: 1 }));
+    /// ```
+    pub fn try_resolve(&self, index: SymbolIndex) -> Result<&str, InvalidIndex> {
+        self.get_by_index(index).ok_or(InvalidIndex {
+            index,
+            len: self.len(),
+        })
+    }
+
+    /// Finds groups of strings that are likely accidental duplicates of each other,
+    /// such as config keys that only differ by case, whitespace, or a small typo. The
+    /// strings are compared after lowercasing them and removing whitespace, and are
+    /// grouped together when the edit distance between them is at most
+    /// `max_edit_distance`. Only groups with more than one member are returned, and
+    /// each group is ordered by index.
+    ///
+    /// This compares every pair of strings, so it's meant as a diagnostic for auditing
+    /// a table, and not for hot paths.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("max_width");
+    /// symbol_table.get("Max_Width");
+    /// symbol_table.get("max_witdh");
+    /// symbol_table.get("min_height");
+    ///
+    /// let groups = symbol_table.find_near_duplicates(2);
+    /// assert_eq!(groups.len(), 1);
+    /// assert_eq!(groups[0], ["max_width", "Max_Width", "max_witdh"]);
+    ///
+    /// // With no edits allowed, only the case difference is found.
+    /// let groups = symbol_table.find_near_duplicates(0);
+    /// assert_eq!(groups[0], ["max_width", "Max_Width"]);
+    /// ```
+    pub fn find_near_duplicates(
+        &'strings self,
+        max_edit_distance: usize,
+    ) -> Vec<Vec<Symbol<'strings>>> {
+        let normalized: Vec<Vec<char>> = self
+            .iter()
+            .map(|string| {
+                string
+                    .chars()
+                    .filter(|ch| !ch.is_whitespace())
+                    .flat_map(char::to_lowercase)
+                    .collect()
+            })
+            .collect();
+
+        // Union together all of the strings that are within the edit distance, so
+        // that chains of near duplicates end up in the same group.
+        let mut roots: Vec<usize> = (0..normalized.len()).collect();
+        fn find_root(roots: &mut [usize], mut index: usize) -> usize {
+            while roots[index] != index {
+                roots[index] = roots[roots[index]];
+                index = roots[index];
+            }
+            index
+        }
+
+        for (a, a_chars) in normalized.iter().enumerate() {
+            for (b, b_chars) in normalized.iter().enumerate().skip(a + 1) {
+                if bounded_edit_distance(a_chars, b_chars, max_edit_distance).is_some() {
+                    let a_root = find_root(&mut roots, a);
+                    let b_root = find_root(&mut roots, b);
+                    roots[a_root.max(b_root)] = a_root.min(b_root);
+                }
+            }
+        }
+
+        let mut groups: Vec<Vec<Symbol<'strings>>> = Vec::new();
+        let mut group_by_root: Vec<Option<usize>> = vec![None; normalized.len()];
+        for index in 0..normalized.len() {
+            let root = find_root(&mut roots, index);
+            let group = *group_by_root[root].get_or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(Symbol::new(self, index));
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// Memoizes a formatted string that is built from other symbols. The first time a
+    /// combination of `key_symbols` is seen, `format` is run and its result is interned.
+    /// After that, the interned result is returned without running `format`. The keys
+    /// must fully determine the output, so it's a good idea to include a symbol for the
+    /// message template in the keys when multiple formats share the same inputs.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let template = symbol_table.get("unknown-variable");
+    /// let name = symbol_table.get("count");
+    ///
+    /// let message = symbol_table.cached_format(&[template, name], || {
+    ///     format!("Unknown variable `{}`", name)
+    /// });
+    /// assert_eq!(message, "Unknown variable `count`");
+    ///
+    /// // The second time around the message isn't formatted again.
+    /// let cached = symbol_table.cached_format(&[template, name], || unreachable!());
+    /// assert_eq!(cached, message);
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn cached_format<F: FnOnce() -> String>(
+        &'strings self,
+        key_symbols: &[Symbol<'strings>],
+        format: F,
+    ) -> Symbol<'strings> {
+        let key: Vec<SymbolKey> = key_symbols.iter().map(Symbol::key).collect();
+        if let Some(index) = self.formatted.borrow().get(&key) {
+            self.record(|stats| stats.format_cache_hits += 1);
+            return Symbol::new(self, *index);
+        }
+        // The borrow is released while formatting, so that the format function is free
+        // to use the cache as well.
+        let symbol = self.get(format());
+        self.formatted.borrow_mut().insert(key, symbol.index);
+        symbol
+    }
+
+    /// Returns counts of how the symbols of this table have been used, such as how
+    /// many slices were created, and how many equality checks fell back to comparing
+    /// strings.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    /// let hello_world = symbol_table.get("hello world");
+    /// let hello_slice = hello_world.slice(0..5).unwrap();
+    ///
+    /// assert_eq!(hello_slice, hello);
+    /// assert_eq!(hello_slice.deslice(), hello);
+    ///
+    /// let stats = symbol_table.stats();
+    /// assert_eq!(stats.slices, 1);
+    /// assert_eq!(stats.string_comparisons, 1);
+    /// assert_eq!(stats.deslices, 1);
+    /// ```
+    pub fn stats(&self) -> SymbolStats {
+        self.stats.get()
+    }
+
+    /// Resets all of the [`stats`](struct.SymbolTable.html#method.stats) to zero.
+    pub fn reset_stats(&self) {
+        self.stats.take();
+    }
+
+    fn record<F: FnOnce(&mut SymbolStats)>(&self, update: F) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+
+    /// An estimate of the bytes used to store the interned strings. Each string is
+    /// stored once in the list of strings, and once as a key for looking up its index.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("hello");
+    /// assert_eq!(symbol_table.memory_usage(), 10);
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage.get()
+    }
+
+    /// Registers a callback that is run once the [`memory_usage`] of the table grows to
+    /// `bytes` or more, so that an application can log, shed load, or otherwise react
+    /// to runaway growth. The callback is run at most once, and receives the current
+    /// memory usage. The callback is free to intern strings or register more
+    /// thresholds.
+    ///
+    /// [`memory_usage`]: struct.SymbolTable.html#method.memory_usage
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let crossed_at = Rc::new(Cell::new(None));
+    /// let crossed_at_clone = crossed_at.clone();
+    /// symbol_table.on_memory_threshold(20, move |bytes| crossed_at_clone.set(Some(bytes)));
+    ///
+    /// symbol_table.get("hello");
+    /// assert_eq!(crossed_at.get(), None);
+    ///
+    /// symbol_table.get("world");
+    /// assert_eq!(crossed_at.get(), Some(20));
+    /// ```
+    pub fn on_memory_threshold<F: FnMut(usize) + 'static>(&self, bytes: usize, callback: F) {
+        self.memory_thresholds.borrow_mut().push(MemoryThreshold {
+            bytes,
+            callback: Box::new(callback),
+        });
+    }
+
+    fn check_memory_thresholds(&self, memory_usage: usize) {
+        let has_crossed = |threshold: &MemoryThreshold| threshold.bytes <= memory_usage;
+        if !self.memory_thresholds.borrow().iter().any(has_crossed) {
+            return;
+        }
+        // Take the thresholds out of the table so that the callbacks can use it.
+        let (mut crossed, remaining): (Vec<_>, Vec<_>) = self
+            .memory_thresholds
+            .take()
+            .into_iter()
+            .partition(has_crossed);
+        self.memory_thresholds.borrow_mut().extend(remaining);
+        for threshold in &mut crossed {
+            (threshold.callback)(memory_usage);
+        }
+    }
+
+    /// Resolves the 1-based line and column of the start of a symbol within its
+    /// parent string, which is useful for error reporting on sliced tokens of
+    /// interned source text. Columns are counted in chars. The line starts of each
+    /// parent are computed on the first call, and then cached.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let source = symbol_table.get("fn main() {\n    lét x = 1;\n}");
+    /// let x = source.slice(21..22).unwrap();
+    /// assert_eq!(x, "x");
+    /// assert_eq!(symbol_table.line_col(x), (2, 9));
+    /// assert_eq!(symbol_table.line_col(source), (1, 1));
+    /// ```
+    pub fn line_col(&self, symbol: Symbol) -> (usize, usize) {
+        let string = self.str(symbol.index);
+        let offset = symbol.range().start;
+        let mut line_starts = self.line_starts.borrow_mut();
+        let line_starts = line_starts.entry(symbol.index).or_insert_with(|| {
+            std::iter::once(0)
+                .chain(string.match_indices('\n').map(|(index, _)| index + 1))
+                .collect()
+        });
+        let line = line_starts.partition_point(|&start| start <= offset);
+        let line_start = line_starts[line - 1];
+        (line, string[line_start..offset].chars().count() + 1)
+    }
+
     fn str(&self, index: SymbolIndex) -> &str {
-        match self.symbols.get(index) {
+        let string = self.symbols.get(index).unwrap_or_default();
+        self.assert_address(index, string);
+        string
+    }
+}
+
+/// Resolves the string for an index. This panics if the index is out of range, see
+/// [`get_by_index`](struct.SymbolTable.html#method.get_by_index) for a non-panicking
+/// version.
+impl<'strings> Index<SymbolIndex> for SymbolTable<'strings> {
+    type Output = str;
+
+    fn index(&self, index: SymbolIndex) -> &str {
+        match self.get_by_index(index) {
             Some(string) => string,
-            None => "",
+            None => panic!("The index {index} is out of range."),
+        }
+    }
+}
+
+/// Tables are equal when they have the same strings at the same indexes, see
+/// [`set_eq`](struct.SymbolTable.html#method.set_eq) to ignore the indexes.
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTable;
+///
+/// let expected = SymbolTable::new();
+/// expected.get("a");
+/// let actual = SymbolTable::new();
+/// actual.get("a");
+/// assert_eq!(actual, expected);
+/// ```
+impl<'strings, 'other> PartialEq<SymbolTable<'other>> for SymbolTable<'strings> {
+    fn eq(&self, other: &SymbolTable<'other>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<'strings> Eq for SymbolTable<'strings> {}
+
+/// Formats the table as the list of its strings, in index order.
+impl<'strings> fmt::Debug for SymbolTable<'strings> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Computes the Levenshtein distance between two strings, bailing out early with
+/// `None` once it's known to be larger than `max`.
+fn bounded_edit_distance(a: &[char], b: &[char], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        if row_min > max {
+            return None;
         }
+        std::mem::swap(&mut previous, &mut current);
     }
+    let distance = previous[b.len()];
+    (distance <= max).then_some(distance)
 }
 
 #[cfg(test)]
@@ -491,4 +1696,272 @@ mod test {
 
         let _hello_str: String = hello.into();
     }
+
+    #[test]
+    fn test_near_duplicates() {
+        let symbol_table = SymbolTable::new();
+        symbol_table.get("color");
+        symbol_table.get("colour");
+        symbol_table.get("background color");
+        symbol_table.get("BackgroundColor");
+        symbol_table.get("size");
+
+        let groups = symbol_table.find_near_duplicates(1);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0], ["color", "colour"]);
+        assert_eq!(groups[1], ["background color", "BackgroundColor"]);
+
+        assert_eq!(
+            bounded_edit_distance(&['a', 'b', 'c'], &['x', 'y', 'z'], 2),
+            None,
+            "The distance exceeds the maximum."
+        );
+        assert_eq!(
+            bounded_edit_distance(&['a', 'b'], &['a', 'c', 'b'], 2),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_tags() {
+        let symbol_table = SymbolTable::new();
+        let source = symbol_table.get("fn main");
+        let keyword = source.slice(0..2).unwrap();
+        let main = source.slice(3..7).unwrap();
+
+        assert!(
+            !symbol_table.has_tag(keyword, "keyword"),
+            "The slice isn't interned or tagged yet."
+        );
+        assert!(symbol_table.tag(keyword, "keyword"));
+        assert!(symbol_table.has_tag(symbol_table.get("fn"), "keyword"));
+        assert!(symbol_table.has_tag(keyword, "keyword"));
+        assert!(!symbol_table.has_tag(main, "keyword"));
+        assert!(!symbol_table.has_tag(main, "missing tag"));
+
+        assert_eq!(
+            symbol_table.iter_tagged("keyword").collect::<Vec<_>>(),
+            ["fn"]
+        );
+        assert_eq!(symbol_table.iter_tagged("missing tag").count(), 0);
+    }
+
+    #[test]
+    fn test_is() {
+        static FN: &str = "fn";
+        let symbol_table = SymbolTable::new();
+        let source = symbol_table.get("fn main");
+        let keyword = source.slice(0..2).unwrap();
+        assert!(keyword.is(FN));
+        assert!(!source.is(FN), "The full string isn't the constant.");
+
+        let keyword = symbol_table.get("fn");
+        assert!(keyword.is(FN));
+        assert_eq!(symbol_table.static_indexes.borrow().len(), 1);
+        assert!(keyword.is(FN), "The cached index is used.");
+        assert!(!source.is(FN), "The cached index is used.");
+    }
+
+    #[test]
+    fn test_symmetric_equality() {
+        let symbol_table = SymbolTable::new();
+        let hello = symbol_table.get("hello");
+        let hello_str: &str = "hello";
+        let hello_string = String::from("hello");
+        let hello_cow = Cow::Borrowed("hello");
+
+        assert_eq!(hello, hello_str);
+        assert_eq!(hello, *hello_str);
+        assert_eq!(hello, hello_string);
+        assert_eq!(hello, hello_cow);
+
+        assert_eq!(hello_str, hello);
+        assert_eq!(*hello_str, hello);
+        assert_eq!(hello_string, hello);
+        assert_eq!(hello_cow, hello);
+
+        assert_ne!("world", hello);
+        assert_ne!(String::from("world"), hello);
+        assert_ne!(Cow::<str>::Owned("world".into()), hello);
+    }
+
+    #[test]
+    fn test_cached_format() {
+        let symbol_table = SymbolTable::new();
+        let source = symbol_table.get("a + b");
+        let a = source.slice(0..1).unwrap();
+        let b = source.slice(4..5).unwrap();
+        let mut calls = 0;
+        let mut format = |keys: &[Symbol]| -> String {
+            symbol_table
+                .cached_format(keys, || {
+                    calls += 1;
+                    keys.iter().map(Symbol::str).collect::<Vec<_>>().join(", ")
+                })
+                .into()
+        };
+
+        assert_eq!(format(&[a, b]), "a, b");
+        assert_eq!(format(&[b, a]), "b, a");
+        assert_eq!(format(&[a, b]), "a, b");
+        assert_eq!(format(&[b, a]), "b, a");
+        assert_eq!(calls, 2, "Each combination of keys is only formatted once.");
+    }
+
+    #[test]
+    fn test_memory_thresholds() {
+        use std::rc::Rc;
+
+        let symbol_table = SymbolTable::new();
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        for bytes in [4, 8, 100] {
+            let calls = calls.clone();
+            symbol_table.on_memory_threshold(bytes, move |usage| {
+                calls.borrow_mut().push((bytes, usage));
+            });
+        }
+
+        symbol_table.get("a");
+        assert_eq!(*calls.borrow(), []);
+        symbol_table.get("bcd");
+        assert_eq!(*calls.borrow(), [(4, 8), (8, 8)]);
+        symbol_table.get("bcd");
+        symbol_table.get("efg");
+        assert_eq!(
+            *calls.borrow(),
+            [(4, 8), (8, 8)],
+            "The callbacks are only run once."
+        );
+        assert_eq!(symbol_table.memory_thresholds.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_memory_threshold_reentrancy() {
+        use std::rc::Rc;
+
+        let symbol_table = Rc::new(SymbolTable::new());
+        let weak_table = Rc::downgrade(&symbol_table);
+        symbol_table.on_memory_threshold(2, move |_| {
+            let symbol_table = weak_table.upgrade().unwrap();
+            symbol_table.get("interned from the callback");
+            symbol_table.on_memory_threshold(1000, |_| {});
+        });
+        symbol_table.get("hello");
+        assert!(symbol_table.has("interned from the callback"));
+        assert_eq!(symbol_table.memory_thresholds.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_span_arithmetic() {
+        let symbol_table = SymbolTable::new();
+        let source = symbol_table.get("(a + b)");
+        let open = source.slice(0..1).unwrap();
+        let close = source.slice(6..7).unwrap();
+
+        assert_eq!(open.join_span(close).unwrap(), "(a + b)");
+        assert_eq!(source.join_span(open).unwrap(), "(a + b)");
+        assert_eq!(source.intersect_span(close).unwrap(), ")");
+        assert_eq!(open.intersect_span(open).unwrap(), "(");
+        assert_eq!(open.intersect_span(close), None);
+
+        let other_table = SymbolTable::new();
+        let other_source = other_table.get("(a + b)");
+        assert_eq!(
+            open.join_span(other_source.slice(0..1).unwrap()),
+            None,
+            "Symbols from different tables don't share parents."
+        );
+    }
+
+    #[test]
+    fn test_slice_clamped() {
+        let symbol_table = SymbolTable::new();
+        let text = symbol_table.get("a→b→c");
+        // Slices are clamped to themselves, rather than their parent.
+        let middle = text.slice_clamped(1..8);
+        assert_eq!(middle, "→b→");
+        assert_eq!(middle.slice_clamped(2..4), "→b");
+        assert_eq!(middle.slice_clamped(9..20), "");
+        assert_eq!(middle.slice_clamped(9..20).range(), 8..8);
+        assert_eq!(symbol_table.get("").slice_clamped(1..2), "");
+    }
+
+    #[test]
+    fn test_line_col() {
+        let symbol_table = SymbolTable::new();
+        let source = symbol_table.get("a\n\nbc\nd");
+        let position = |range| symbol_table.line_col(source.slice(range).unwrap());
+        assert_eq!(position(0..1), (1, 1));
+        assert_eq!(
+            position(1..2),
+            (1, 2),
+            "The newline is at the end of the line."
+        );
+        assert_eq!(position(2..3), (2, 1));
+        assert_eq!(position(4..5), (3, 2));
+        assert_eq!(position(6..7), (4, 1));
+        assert_eq!(position(7..7), (4, 2));
+        assert_eq!(
+            symbol_table.line_starts.borrow()[&source.index],
+            [0, 2, 3, 6]
+        );
+    }
+
+    #[test]
+    fn test_str_eq() {
+        let text: String = (0..100u8).map(|i| (b'a' + i % 26) as char).collect();
+        for len in 0..100 {
+            let a = &text[..len];
+            let b = String::from(a);
+            assert!(str_eq(a, &b), "Equal strings of length {len}");
+            assert!(!str_eq(a, &text[..len + 1]), "Different lengths {len}");
+            for index in 0..len {
+                let mut c = b.clone().into_bytes();
+                c[index] = b'!';
+                let c = String::from_utf8(c).unwrap();
+                assert!(!str_eq(a, &c), "A difference at {index} of {len}");
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The index 1 is out of range.")]
+    fn test_index_out_of_range() {
+        let symbol_table = SymbolTable::new();
+        symbol_table.get("hello");
+        let _ = &symbol_table[1];
+    }
+
+    #[test]
+    fn test_stats() {
+        static KEYWORD: &str = "keyword";
+        let symbol_table = SymbolTable::new();
+        let source = symbol_table.get("keyword keyword");
+        let keyword = symbol_table.get("keyword");
+        let a = source.slice_cached(0..7).unwrap();
+        let b = source.slice_cached(0..7).unwrap();
+        let c = source.slice(8..15).unwrap();
+        assert_eq!(
+            a, b,
+            "The ranges are the same, so there's no string comparison."
+        );
+        assert_eq!(a, c);
+        assert_eq!(keyword, c);
+        assert!(keyword.is(KEYWORD));
+        assert!(keyword.is(KEYWORD));
+        assert_eq!(
+            symbol_table.stats(),
+            SymbolStats {
+                slices: 2,
+                deslices: 0,
+                string_comparisons: 2,
+                slice_cache_hits: 1,
+                static_cache_hits: 1,
+                format_cache_hits: 0,
+            }
+        );
+
+        symbol_table.reset_stats();
+        assert_eq!(symbol_table.stats(), SymbolStats::default());
+    }
 }
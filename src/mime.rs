@@ -0,0 +1,190 @@
+//! A preloaded dataset of common IANA media types (MIME types), for servers that
+//! intern content types. See [`SymbolTable::with_media_types`].
+
+use std::borrow::Cow;
+
+use crate::{Symbol, SymbolTable};
+
+/// Common IANA media types. When preloaded with [`SymbolTable::with_media_types`], the
+/// index of each media type in this list is its [`SymbolIndex`](crate::SymbolIndex) in
+/// the table, and this order will only ever be appended to.
+pub const MEDIA_TYPES: &[&str] = &[
+    "application/octet-stream",
+    "application/json",
+    "application/javascript",
+    "application/xml",
+    "application/x-www-form-urlencoded",
+    "application/pdf",
+    "application/zip",
+    "application/gzip",
+    "application/x-tar",
+    "application/x-7z-compressed",
+    "application/x-bzip2",
+    "application/zstd",
+    "application/wasm",
+    "application/ld+json",
+    "application/manifest+json",
+    "application/problem+json",
+    "application/geo+json",
+    "application/atom+xml",
+    "application/rss+xml",
+    "application/xhtml+xml",
+    "application/soap+xml",
+    "application/graphql",
+    "application/msgpack",
+    "application/cbor",
+    "application/x-protobuf",
+    "application/grpc",
+    "application/sql",
+    "application/rtf",
+    "application/msword",
+    "application/vnd.ms-excel",
+    "application/vnd.ms-powerpoint",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.oasis.opendocument.text",
+    "application/vnd.oasis.opendocument.spreadsheet",
+    "application/epub+zip",
+    "application/java-archive",
+    "application/x-sh",
+    "text/plain",
+    "text/html",
+    "text/css",
+    "text/csv",
+    "text/javascript",
+    "text/markdown",
+    "text/xml",
+    "text/calendar",
+    "text/event-stream",
+    "text/tab-separated-values",
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/avif",
+    "image/svg+xml",
+    "image/bmp",
+    "image/tiff",
+    "image/x-icon",
+    "image/vnd.microsoft.icon",
+    "image/heic",
+    "audio/mpeg",
+    "audio/ogg",
+    "audio/wav",
+    "audio/webm",
+    "audio/aac",
+    "audio/flac",
+    "audio/midi",
+    "audio/opus",
+    "video/mp4",
+    "video/mpeg",
+    "video/ogg",
+    "video/webm",
+    "video/quicktime",
+    "video/x-msvideo",
+    "video/mp2t",
+    "font/woff",
+    "font/woff2",
+    "font/ttf",
+    "font/otf",
+    "multipart/form-data",
+    "multipart/mixed",
+    "multipart/alternative",
+    "multipart/byteranges",
+    "message/rfc822",
+    "model/gltf+json",
+    "model/gltf-binary",
+];
+
+impl<'strings> SymbolTable<'strings> {
+    /// Creates a new [`SymbolTable`] that is preloaded with the [`MEDIA_TYPES`]. Each
+    /// media type's index in that list is its stable [`SymbolIndex`](crate::SymbolIndex).
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    /// use gregtatum_symbol_table::mime::MEDIA_TYPES;
+    ///
+    /// let symbol_table = SymbolTable::with_media_types();
+    /// assert_eq!(symbol_table.len(), MEDIA_TYPES.len());
+    /// assert!(symbol_table.has("application/json"));
+    /// ```
+    pub fn with_media_types() -> SymbolTable<'strings> {
+        SymbolTable::preloaded(MEDIA_TYPES)
+    }
+
+    /// Looks up the media type of a `Content-Type` style value. Parameters such as
+    /// `charset` are ignored, and the type is matched case insensitively. This only
+    /// allocates when the media type isn't already lowercase. Only media types that
+    /// are already in the table are returned, and they aren't interned.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::with_media_types();
+    /// let json = symbol_table.get("application/json");
+    ///
+    /// assert_eq!(symbol_table.parse_mime("application/json"), Some(json));
+    /// assert_eq!(
+    ///     symbol_table.parse_mime("Application/JSON; charset=utf-8"),
+    ///     Some(json)
+    /// );
+    /// assert_eq!(symbol_table.parse_mime("application/x-unknown"), None);
+    /// ```
+    pub fn parse_mime(&'strings self, content_type: &str) -> Option<Symbol<'strings>> {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        if !essence.contains('/') {
+            return None;
+        }
+        let essence = if essence.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            Cow::Owned(essence.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(essence)
+        };
+        self.maybe_get(essence)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stable_media_type_indexes() {
+        let symbol_table = SymbolTable::with_media_types();
+        for (index, media_type) in MEDIA_TYPES.iter().enumerate() {
+            assert_eq!(symbol_table.iter().nth(index), Some(*media_type));
+            assert_eq!(
+                symbol_table
+                    .parse_mime(media_type)
+                    .map(|symbol| symbol.index),
+                Some(index)
+            );
+        }
+        assert_eq!(
+            symbol_table.len(),
+            MEDIA_TYPES.len(),
+            "There are no duplicates."
+        );
+    }
+
+    #[test]
+    fn test_parse_mime() {
+        let symbol_table = SymbolTable::with_media_types();
+        assert_eq!(
+            symbol_table
+                .parse_mime(" text/html ;charset=utf-8")
+                .unwrap(),
+            "text/html"
+        );
+        assert_eq!(symbol_table.parse_mime("TEXT/HTML").unwrap(), "text/html");
+        assert_eq!(symbol_table.parse_mime(""), None);
+
+        symbol_table.get("not a media type");
+        assert_eq!(
+            symbol_table.parse_mime("not a media type"),
+            None,
+            "Only strings that look like media types are matched."
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! A map from symbols to multiple values, such as the overloads of a name.
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// Maps symbols to lists of values, such as a compiler's map from a name to its
+/// overloads. Since symbol indexes are dense, the lists are stored in a vector indexed
+/// by symbol rather than in a hash map. The keys are iterated in the order that they
+/// were first inserted, and the values of a key in the order that they were inserted.
+///
+/// ```
+/// use gregtatum_symbol_table::{SymbolMultiMap, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// let mut overloads = SymbolMultiMap::new(&symbol_table);
+/// let [print, max] = ["print", "max"].map(|name| symbol_table.get(name));
+/// overloads.insert(print, "fn(&str)");
+/// overloads.insert(max, "fn(i32, i32) -> i32");
+/// overloads.insert(print, "fn(i32)");
+///
+/// assert_eq!(overloads.get(print), ["fn(&str)", "fn(i32)"]);
+/// assert_eq!(overloads.get(symbol_table.get("min")), [] as [&str; 0]);
+///
+/// let keys: Vec<_> = overloads.iter().map(|(name, values)| (name, values.len())).collect();
+/// assert_eq!(keys, [(print, 2), (max, 1)]);
+/// ```
+pub struct SymbolMultiMap<'strings, V> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    // The values of each symbol, indexed by symbol index.
+    buckets: Vec<Vec<V>>,
+    // The keys with values, in the order that they were first inserted.
+    keys: Vec<SymbolIndex>,
+    values_len: usize,
+}
+
+impl<'strings, V> SymbolMultiMap<'strings, V> {
+    /// Creates an empty map for the symbols of a table.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        SymbolMultiMap {
+            symbol_table,
+            buckets: Vec::new(),
+            keys: Vec::new(),
+            values_len: 0,
+        }
+    }
+
+    /// Adds a value to the end of the values of a symbol. The symbol must be from the
+    /// map's table.
+    pub fn insert(&mut self, key: Symbol<'strings>, value: V) {
+        let index = key.deslice().index();
+        if index >= self.buckets.len() {
+            self.buckets.resize_with(index + 1, Vec::new);
+        }
+        let bucket = &mut self.buckets[index];
+        if bucket.is_empty() {
+            self.keys.push(index);
+        }
+        bucket.push(value);
+        self.values_len += 1;
+    }
+
+    /// The values of a symbol, which is empty if it has none.
+    pub fn get(&self, key: Symbol) -> &[V] {
+        self.buckets
+            .get(key.deslice().index())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The values of a symbol, for changing them in place.
+    pub fn get_mut(&mut self, key: Symbol) -> &mut [V] {
+        self.buckets
+            .get_mut(key.deslice().index())
+            .map_or(&mut [], Vec::as_mut_slice)
+    }
+
+    /// Returns true if a symbol has any values.
+    pub fn contains_key(&self, key: Symbol) -> bool {
+        !self.get(key).is_empty()
+    }
+
+    /// Removes every value of a symbol, and returns them.
+    pub fn remove(&mut self, key: Symbol) -> Vec<V> {
+        let index = key.deslice().index();
+        let Some(bucket) = self.buckets.get_mut(index) else {
+            return Vec::new();
+        };
+        let values = std::mem::take(bucket);
+        if !values.is_empty() {
+            self.keys.retain(|&key| key != index);
+            self.values_len -= values.len();
+        }
+        values
+    }
+
+    /// The number of symbols with values.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns true if no symbol has any values.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The total number of values of every symbol.
+    pub fn values_len(&self) -> usize {
+        self.values_len
+    }
+
+    /// Iterates over the symbols with values and their values, in the order that the
+    /// symbols were first inserted.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol<'strings>, &[V])> + '_ {
+        self.keys.iter().map(|&index| {
+            (
+                Symbol::new(self.symbol_table, index),
+                self.buckets[index].as_slice(),
+            )
+        })
+    }
+
+    /// Iterates over every pair of a symbol and one of its values, in the order of
+    /// [`iter`](Self::iter).
+    pub fn iter_flat(&self) -> impl Iterator<Item = (Symbol<'strings>, &V)> + '_ {
+        self.iter()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_remove_and_reinsert() {
+        let symbol_table = SymbolTable::new();
+        let [a, b] = ["a", "b"].map(|name| symbol_table.get(name));
+        let mut map = SymbolMultiMap::new(&symbol_table);
+        map.insert(a, 1);
+        map.insert(b, 2);
+        map.insert(a, 3);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.values_len(), 3);
+        map.get_mut(a)[0] = 10;
+
+        assert_eq!(map.remove(a), [10, 3]);
+        assert!(map.remove(a).is_empty());
+        assert!(!map.contains_key(a));
+        assert_eq!(map.values_len(), 1);
+
+        // A key that is inserted again goes to the end of the order.
+        map.insert(a, 4);
+        let pairs: Vec<_> = map
+            .iter_flat()
+            .map(|(key, value)| (key.str(), *value))
+            .collect();
+        assert_eq!(pairs, [("b", 2), ("a", 4)]);
+
+        // Slices are keyed by their full symbol.
+        let slice = symbol_table.get("ab").slice(1..2).unwrap();
+        assert_eq!(map.get(slice), [2]);
+    }
+}
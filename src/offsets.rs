@@ -0,0 +1,241 @@
+//! Conversions between the byte offsets of strings, which the table uses for slices,
+//! and the char offsets and UTF-16 offsets that editors and the Language Server
+//! Protocol use.
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// Strings up to this length in bytes are counted from their start, rather than from a
+/// cached checkpoint.
+const CHECKPOINT_THRESHOLD: usize = 256;
+/// The number of bytes between the checkpoints of a long string.
+const CHECKPOINT_INTERVAL: usize = 64;
+
+/// The offsets at a char boundary of a string.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct Checkpoint {
+    byte: usize,
+    chars: usize,
+    utf16: usize,
+}
+
+/// Computes the checkpoints of a string, at the first char boundary of every interval.
+fn checkpoints(string: &str) -> Vec<Checkpoint> {
+    let mut checkpoints = vec![Checkpoint::default()];
+    let mut next = CHECKPOINT_INTERVAL;
+    let mut utf16 = 0;
+    for (chars, (byte, char)) in string.char_indices().enumerate() {
+        if byte >= next {
+            checkpoints.push(Checkpoint { byte, chars, utf16 });
+            next = byte + CHECKPOINT_INTERVAL;
+        }
+        utf16 += char.len_utf16();
+    }
+    checkpoints
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// The last checkpoint of a string that matches a predicate, which must match a
+    /// prefix of the checkpoints. The checkpoints of long strings are cached.
+    fn checkpoint_before(
+        &self,
+        index: SymbolIndex,
+        predicate: impl FnMut(&Checkpoint) -> bool,
+    ) -> Checkpoint {
+        let string = self.str(index);
+        if string.len() <= CHECKPOINT_THRESHOLD {
+            return Checkpoint::default();
+        }
+        let mut all_checkpoints = self.offset_checkpoints.borrow_mut();
+        let checkpoints = all_checkpoints
+            .entry(index)
+            .or_insert_with(|| checkpoints(string));
+        checkpoints[checkpoints.partition_point(predicate).max(1) - 1]
+    }
+
+    /// The offsets at a byte offset of a string, which must be a char boundary.
+    fn offsets_at_byte(&self, index: SymbolIndex, byte: usize) -> Checkpoint {
+        let start = self.checkpoint_before(index, |checkpoint| checkpoint.byte <= byte);
+        let mut offsets = Checkpoint { byte, ..start };
+        for char in self.str(index)[start.byte..byte].chars() {
+            offsets.chars += 1;
+            offsets.utf16 += char.len_utf16();
+        }
+        offsets
+    }
+
+    /// The byte offset of a char offset of a string, or `None` if it's past the end.
+    fn byte_at_chars(&self, index: SymbolIndex, chars: usize) -> Option<usize> {
+        let start = self.checkpoint_before(index, |checkpoint| checkpoint.chars <= chars);
+        let string = self.str(index);
+        string[start.byte..]
+            .char_indices()
+            .map(|(byte, _)| start.byte + byte)
+            .chain(std::iter::once(string.len()))
+            .nth(chars - start.chars)
+    }
+
+    /// The byte offset of a UTF-16 offset of a string, or `None` if it's past the end,
+    /// or in the middle of a surrogate pair.
+    fn byte_at_utf16(&self, index: SymbolIndex, utf16: usize) -> Option<usize> {
+        let start = self.checkpoint_before(index, |checkpoint| checkpoint.utf16 <= utf16);
+        let string = self.str(index);
+        let mut units = start.utf16;
+        for (byte, char) in string[start.byte..].char_indices() {
+            if units >= utf16 {
+                return (units == utf16).then_some(start.byte + byte);
+            }
+            units += char.len_utf16();
+        }
+        (units == utf16).then_some(string.len())
+    }
+}
+
+impl<'strings> Symbol<'strings> {
+    /// Converts a byte offset in the symbol's string to the number of chars before it,
+    /// such as for reporting a span to an editor that counts chars. Returns `None` if
+    /// the offset is past the end of the string, or isn't on a char boundary. Strings
+    /// longer than a few hundred bytes cache checkpoints of their offsets on the first
+    /// call, so that later conversions only count the chars since a checkpoint.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let source = symbol_table.get("let café = 1;");
+    /// assert_eq!(source.byte_to_char_index(11), Some(10));
+    /// assert_eq!(source.char_to_byte_index(10), Some(11));
+    ///
+    /// // Byte 8 is in the middle of the é.
+    /// assert_eq!(source.byte_to_char_index(8), None);
+    ///
+    /// // Offsets are relative to slices.
+    /// let cafe = source.slice(4..9).unwrap();
+    /// assert_eq!(cafe.byte_to_char_index(5), Some(4));
+    /// assert_eq!(cafe.char_to_byte_index(5), None);
+    /// ```
+    pub fn byte_to_char_index(&self, byte: usize) -> Option<usize> {
+        if !self.str().is_char_boundary(byte) {
+            return None;
+        }
+        let start = self.range().start;
+        let symbol_table = self.symbol_table;
+        Some(
+            symbol_table.offsets_at_byte(self.index, start + byte).chars
+                - symbol_table.offsets_at_byte(self.index, start).chars,
+        )
+    }
+
+    /// Converts a number of chars from the start of the symbol's string to the byte
+    /// offset after them, which is the inverse of
+    /// [`byte_to_char_index`](struct.Symbol.html#method.byte_to_char_index). Returns
+    /// `None` if the string has fewer chars.
+    pub fn char_to_byte_index(&self, chars: usize) -> Option<usize> {
+        let range = self.range();
+        let symbol_table = self.symbol_table;
+        let start = symbol_table.offsets_at_byte(self.index, range.start);
+        let byte = symbol_table.byte_at_chars(self.index, start.chars + chars)?;
+        (byte <= range.end).then(|| byte - range.start)
+    }
+
+    /// Converts a byte offset in the symbol's string to the number of UTF-16 code units
+    /// before it, which is how the Language Server Protocol counts the characters of
+    /// positions by default. Returns `None` if the offset is past the end of the
+    /// string, or isn't on a char boundary. This caches checkpoints of long strings like
+    /// [`byte_to_char_index`](struct.Symbol.html#method.byte_to_char_index).
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// // The emoji is 4 bytes in UTF-8, and a surrogate pair of 2 code units in UTF-16.
+    /// let line = symbol_table.get("let 🦀 = é;");
+    /// assert_eq!(line.byte_to_utf16_offset(9), Some(7));
+    /// assert_eq!(line.utf16_offset_to_byte(7), Some(9));
+    ///
+    /// // Offset 5 is between the halves of the surrogate pair.
+    /// assert_eq!(line.utf16_offset_to_byte(5), None);
+    /// ```
+    pub fn byte_to_utf16_offset(&self, byte: usize) -> Option<usize> {
+        if !self.str().is_char_boundary(byte) {
+            return None;
+        }
+        let start = self.range().start;
+        let symbol_table = self.symbol_table;
+        Some(
+            symbol_table.offsets_at_byte(self.index, start + byte).utf16
+                - symbol_table.offsets_at_byte(self.index, start).utf16,
+        )
+    }
+
+    /// Converts a number of UTF-16 code units from the start of the symbol's string to
+    /// the byte offset after them, which is the inverse of
+    /// [`byte_to_utf16_offset`](struct.Symbol.html#method.byte_to_utf16_offset).
+    /// Returns `None` if the string has fewer code units, or if the offset splits a
+    /// surrogate pair.
+    pub fn utf16_offset_to_byte(&self, utf16: usize) -> Option<usize> {
+        let range = self.range();
+        let symbol_table = self.symbol_table;
+        let start = symbol_table.offsets_at_byte(self.index, range.start);
+        let byte = symbol_table.byte_at_utf16(self.index, start.utf16 + utf16)?;
+        (byte <= range.end).then(|| byte - range.start)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checkpointed_offsets() {
+        let symbol_table = SymbolTable::new();
+        let text: String = (0..200)
+            .map(|i| if i % 3 == 0 { 'é' } else { 'a' })
+            .collect();
+        let symbol = symbol_table.get(&text);
+        for (chars, (byte, _)) in text.char_indices().enumerate() {
+            assert_eq!(symbol.byte_to_char_index(byte), Some(chars));
+            assert_eq!(symbol.char_to_byte_index(chars), Some(byte));
+        }
+        assert_eq!(symbol.byte_to_char_index(text.len()), Some(200));
+        assert_eq!(symbol.char_to_byte_index(200), Some(text.len()));
+        assert_eq!(symbol.char_to_byte_index(201), None);
+        assert_eq!(symbol.byte_to_char_index(text.len() + 1), None);
+        assert!(symbol_table
+            .offset_checkpoints
+            .borrow()
+            .contains_key(&symbol.index()));
+
+        assert_eq!(symbol.byte_to_utf16_offset(text.len()), Some(200));
+
+        let slice = symbol.slice(100..200).unwrap();
+        let slice_text = &text[100..200];
+        for (chars, (byte, _)) in slice_text.char_indices().enumerate() {
+            assert_eq!(slice.byte_to_char_index(byte), Some(chars));
+            assert_eq!(slice.char_to_byte_index(chars), Some(byte));
+        }
+    }
+
+    #[test]
+    fn test_utf16_offsets() {
+        let symbol_table = SymbolTable::new();
+        let text = "a🦀é".repeat(100);
+        let symbol = symbol_table.get(&text);
+        let mut utf16 = 0;
+        for (byte, char) in text.char_indices() {
+            assert_eq!(symbol.byte_to_utf16_offset(byte), Some(utf16));
+            assert_eq!(symbol.utf16_offset_to_byte(utf16), Some(byte));
+            if char == '🦀' {
+                assert_eq!(symbol.utf16_offset_to_byte(utf16 + 1), None);
+            }
+            utf16 += char.len_utf16();
+        }
+        assert_eq!(symbol.utf16_offset_to_byte(utf16), Some(text.len()));
+        assert_eq!(symbol.utf16_offset_to_byte(utf16 + 1), None);
+
+        let slice = symbol.slice(7..12).unwrap();
+        assert_eq!(slice, "a🦀");
+        assert_eq!(slice.byte_to_utf16_offset(5), Some(3));
+        assert_eq!(slice.utf16_offset_to_byte(3), Some(5));
+        assert_eq!(slice.utf16_offset_to_byte(4), None);
+    }
+}
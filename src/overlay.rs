@@ -0,0 +1,192 @@
+//! A small writable table on top of a large read-only one, which is shared rather than
+//! copied.
+
+use std::sync::Arc;
+
+use elsa::{FrozenMap, FrozenVec};
+use fxhash::FxBuildHasher;
+
+use crate::{SymbolIndex, SymbolResolver};
+
+/// A writable overlay on top of a read-only [`SymbolResolver`], such as a large
+/// dictionary that is shared by every request or thread of a service. Strings are
+/// looked up in the overlay and then the base, and strings that are in neither are
+/// interned into the overlay, so each overlay can be extended without copying the base.
+/// The base is held through an [`Arc`], as the resolver is `Send` and `Sync`.
+///
+/// The index space is partitioned between the base and the overlay. The strings of the
+/// base keep their indexes, which are below
+/// [`OVERLAY_START`](OverlaySymbolTable::OVERLAY_START), and the strings of the overlay
+/// get indexes from `OVERLAY_START` upwards. This way the base can be replaced with
+/// [`swap_base`](OverlaySymbolTable::swap_base), such as when an updated dictionary is
+/// reloaded, without changing the indexes of the overlay.
+///
+/// ```
+/// use std::sync::Arc;
+/// use gregtatum_symbol_table::{OverlaySymbolTable, SymbolTableBuilder};
+///
+/// let mut builder = SymbolTableBuilder::new();
+/// builder.extend(["apple", "banana"]);
+/// let dictionary = Arc::new(builder.build());
+///
+/// let overlay = OverlaySymbolTable::new(Arc::clone(&dictionary));
+/// assert_eq!(overlay.intern("banana"), 1);
+/// let word = overlay.intern("bananna");
+/// assert!(OverlaySymbolTable::is_overlay_index(word));
+/// assert_eq!(overlay.resolve(word), Some("bananna"));
+/// assert_eq!(overlay.overlay_len(), 1);
+///
+/// // The dictionary is unchanged, and shared with other overlays.
+/// assert_eq!(dictionary.lookup("bananna"), None);
+/// assert_eq!(OverlaySymbolTable::new(dictionary).lookup("bananna"), None);
+/// ```
+pub struct OverlaySymbolTable {
+    base: Arc<SymbolResolver>,
+    strings: FrozenVec<String>,
+    indexes: FrozenMap<String, Box<SymbolIndex>, FxBuildHasher>,
+}
+
+impl OverlaySymbolTable {
+    /// The index of the first string of the overlay. Indexes below it are in the base.
+    pub const OVERLAY_START: SymbolIndex = 1 << 31;
+
+    /// Creates an empty overlay on top of a base. This panics if the base has
+    /// [`OVERLAY_START`](OverlaySymbolTable::OVERLAY_START) strings or more.
+    pub fn new(base: Arc<SymbolResolver>) -> OverlaySymbolTable {
+        assert_base_fits(&base);
+        OverlaySymbolTable {
+            base,
+            strings: FrozenVec::new(),
+            indexes: FrozenMap::default(),
+        }
+    }
+
+    /// The read-only base of the overlay.
+    pub fn base(&self) -> &Arc<SymbolResolver> {
+        &self.base
+    }
+
+    /// Returns true if an index is in the index space of the overlay, rather than the
+    /// base.
+    pub fn is_overlay_index(index: SymbolIndex) -> bool {
+        index >= OverlaySymbolTable::OVERLAY_START
+    }
+
+    /// Replaces the base, such as with an updated dictionary that was reloaded while a
+    /// service is running, and returns the old base. The strings of the overlay keep
+    /// their indexes, even the ones that are now in the new base too, as the overlay is
+    /// looked up first. The indexes of the old base refer to the new base afterwards,
+    /// so any that were stored need to be looked up again by their strings. This panics
+    /// if the new base has [`OVERLAY_START`](OverlaySymbolTable::OVERLAY_START) strings
+    /// or more.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use gregtatum_symbol_table::{OverlaySymbolTable, SymbolTableBuilder};
+    ///
+    /// let mut builder = SymbolTableBuilder::new();
+    /// builder.extend(["colour"]);
+    /// let mut overlay = OverlaySymbolTable::new(Arc::new(builder.build()));
+    /// let color = overlay.intern("color");
+    ///
+    /// let mut builder = SymbolTableBuilder::new();
+    /// builder.extend(["color", "colour"]);
+    /// overlay.swap_base(Arc::new(builder.build()));
+    /// assert_eq!(overlay.intern("color"), color);
+    /// assert_eq!(overlay.lookup("colour"), Some(1));
+    /// ```
+    pub fn swap_base(&mut self, base: Arc<SymbolResolver>) -> Arc<SymbolResolver> {
+        assert_base_fits(&base);
+        std::mem::replace(&mut self.base, base)
+    }
+
+    /// Interns a string, and returns its index. Strings that aren't in the base are
+    /// added to the overlay.
+    pub fn intern(&self, string: &str) -> SymbolIndex {
+        if let Some(index) = self.lookup(string) {
+            return index;
+        }
+        let index = OverlaySymbolTable::OVERLAY_START + self.strings.len();
+        self.strings.push(string.to_owned());
+        self.indexes.insert(string.to_owned(), Box::new(index));
+        index
+    }
+
+    /// Looks up the index of a string in the overlay and then the base, without
+    /// interning it.
+    pub fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        self.indexes
+            .get(string)
+            .copied()
+            .or_else(|| self.base.lookup(string))
+    }
+
+    /// The string for an index, or `None` if it's out of range.
+    pub fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        match index.checked_sub(OverlaySymbolTable::OVERLAY_START) {
+            None => self.base.resolve(index),
+            Some(index) => self.strings.get(index),
+        }
+    }
+
+    /// The number of strings, including the ones in the base.
+    pub fn len(&self) -> usize {
+        self.base.len() + self.strings.len()
+    }
+
+    /// Returns true if there are no strings, including the ones in the base.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of strings that were added to the overlay.
+    pub fn overlay_len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Iterates over the strings that were added to the overlay, in order.
+    pub fn additions(&self) -> impl Iterator<Item = &str> {
+        self.strings.iter()
+    }
+}
+
+fn assert_base_fits(base: &SymbolResolver) {
+    assert!(
+        base.len() < OverlaySymbolTable::OVERLAY_START,
+        "The base of an overlay must have fewer than {} strings.",
+        OverlaySymbolTable::OVERLAY_START
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_overlay_then_base() {
+        let base = Arc::new(SymbolResolver::default());
+        let overlay = OverlaySymbolTable::new(base);
+        assert!(overlay.is_empty());
+        let a = overlay.intern("a");
+        assert_eq!(a, OverlaySymbolTable::OVERLAY_START);
+        assert_eq!(overlay.intern("a"), a);
+
+        let mut builder = crate::SymbolTableBuilder::new();
+        builder.extend(["x", "y"]);
+        let mut overlay = OverlaySymbolTable::new(Arc::new(builder.build().with_perfect_hash()));
+        let z = overlay.intern("z");
+        assert_eq!(overlay.lookup("y"), Some(1));
+        assert_eq!(overlay.resolve(0), Some("x"));
+        assert_eq!(overlay.resolve(2), None);
+        assert_eq!(overlay.resolve(z + 1), None);
+        assert_eq!(overlay.additions().collect::<Vec<_>>(), ["z"]);
+        assert_eq!(overlay.len(), 3);
+
+        // Swapping the base keeps the overlay's indexes.
+        let old = overlay.swap_base(Arc::new(SymbolResolver::default()));
+        assert_eq!(old.len(), 2);
+        assert_eq!(overlay.resolve(z), Some("z"));
+        assert_eq!(overlay.lookup("x"), None);
+        assert_eq!(overlay.len(), 1);
+    }
+}
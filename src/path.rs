@@ -0,0 +1,122 @@
+//! Interning of identifier paths, like module paths in a compiler, where both the full
+//! path and each of its components are symbols.
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// A path of identifiers that has been interned with
+/// [`SymbolTable::get_path`](struct.SymbolTable.html#method.get_path). The full path is
+/// interned joined by `"::"`, and the list of component symbols is stored in the table,
+/// so that the components never need to be re-split from the string.
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTable;
+///
+/// let symbol_table = SymbolTable::new();
+/// let path = symbol_table.get_path(["std", "vec", "Vec"]);
+/// assert_eq!(path.symbol(), "std::vec::Vec");
+/// assert_eq!(path.components().collect::<Vec<_>>(), ["std", "vec", "Vec"]);
+/// assert_eq!(path.last().unwrap(), symbol_table.get("Vec"));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct PathSymbol<'strings> {
+    symbol: Symbol<'strings>,
+    components: &'strings [SymbolIndex],
+}
+
+impl<'strings> PathSymbol<'strings> {
+    /// The full path, joined by `"::"`.
+    pub fn symbol(&self) -> Symbol<'strings> {
+        self.symbol
+    }
+
+    /// Iterates over the component symbols of the path, in order.
+    pub fn components(&self) -> impl Iterator<Item = Symbol<'strings>> {
+        let symbol_table = self.symbol.symbol_table;
+        self.components
+            .iter()
+            .map(move |&index| Symbol::new(symbol_table, index))
+    }
+
+    /// The last component of the path, which is typically the name of the item.
+    pub fn last(&self) -> Option<Symbol<'strings>> {
+        self.components().last()
+    }
+
+    /// The number of components in the path.
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Returns true if the path has no components.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl PartialEq for PathSymbol<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol && self.components == other.components
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Interns each component of a path, as well as the full path joined by `"::"`. The
+    /// list of components is stored once for each distinct list, so `["a::b"]` and
+    /// `["a", "b"]` share the symbol of the full path, but are different paths that
+    /// keep their own components.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let a = symbol_table.get_path(["core", "mem", "swap"]);
+    /// let b = symbol_table.get_path(vec![String::from("core"), "mem".into(), "swap".into()]);
+    /// assert_eq!(a, b);
+    /// assert_eq!(symbol_table.get("core::mem::swap"), a.symbol());
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn get_path<I, S>(&'strings self, components: I) -> PathSymbol<'strings>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut joined = String::new();
+        let mut indexes = Vec::new();
+        for component in components {
+            let component = component.as_ref();
+            if !indexes.is_empty() {
+                joined.push_str("::");
+            }
+            joined.push_str(component);
+            indexes.push(self.get(component).index());
+        }
+        let symbol = self.get(joined);
+        let components = match self.paths.get(indexes.as_slice()) {
+            Some(components) => components,
+            None => self.paths.insert(indexes.clone(), indexes),
+        };
+        PathSymbol { symbol, components }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_path_components() {
+        let symbol_table = SymbolTable::new();
+        let nested = symbol_table.get_path(["a::b"]);
+        assert_eq!(nested.len(), 1);
+        let flat = symbol_table.get_path(["a", "b"]);
+        assert_eq!(flat.components().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(flat.symbol(), nested.symbol());
+        assert_ne!(flat, nested);
+        assert_eq!(nested.components().collect::<Vec<_>>(), ["a::b"]);
+
+        let empty = symbol_table.get_path(Vec::<&str>::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.symbol(), "");
+        assert_eq!(empty.last(), None);
+    }
+}
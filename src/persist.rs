@@ -0,0 +1,250 @@
+//! Saving a table to a file, and loading it again in a later run.
+//!
+//! The file starts with the magic bytes `SYMTAB`, followed by a version byte, the number
+//! of strings as a LEB128 varint, and then each string in index order as a varint byte
+//! length followed by its UTF-8 bytes.
+//!
+//! The canonical form of a table is the same format, with the strings sorted by their
+//! bytes rather than in index order. It only depends on the set of strings, and not on
+//! the order that they were interned in, or on the seed of the hasher, so two builds
+//! that intern the same strings write byte-identical canonical files, which can be
+//! cached by their hash. Reading a canonical file gives each string its sorted position
+//! as its index.
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use crate::{varint, SymbolTable};
+
+pub(crate) const MAGIC: &[u8] = b"SYMTAB";
+pub(crate) const VERSION: u8 = 1;
+
+pub(crate) fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Decodes the strings of a saved table, in index order.
+fn decode(mut bytes: &[u8]) -> io::Result<Vec<&str>> {
+    bytes = bytes
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| invalid_data("The file isn't a saved symbol table."))?;
+    match bytes.split_first() {
+        Some((&VERSION, rest)) => bytes = rest,
+        _ => {
+            return Err(invalid_data(
+                "The saved symbol table has an unsupported version.",
+            ))
+        }
+    }
+    let read_len = |bytes: &mut &[u8]| {
+        varint::read_u64(bytes)
+            .ok()
+            .and_then(|len| usize::try_from(len).ok())
+            .ok_or_else(|| invalid_data("The saved symbol table is truncated."))
+    };
+    let len = read_len(&mut bytes)?;
+    // The count comes from the file, so don't trust it for the capacity.
+    let mut strings = Vec::with_capacity(len.min(bytes.len()));
+    for _ in 0..len {
+        let string_len = read_len(&mut bytes)?;
+        if string_len > bytes.len() {
+            return Err(invalid_data("The saved symbol table is truncated."));
+        }
+        let (string, rest) = bytes.split_at(string_len);
+        let string = std::str::from_utf8(string)
+            .map_err(|_| invalid_data("The saved symbol table contains invalid UTF-8."))?;
+        strings.push(string);
+        bytes = rest;
+    }
+    if !bytes.is_empty() {
+        return Err(invalid_data("The saved symbol table has trailing bytes."));
+    }
+    Ok(strings)
+}
+
+fn write_strings<'a, W: Write>(
+    mut writer: W,
+    len: usize,
+    strings: impl Iterator<Item = &'a str>,
+) -> io::Result<()> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION);
+    varint::write_u64(&mut bytes, len as u64);
+    for string in strings {
+        varint::write_u64(&mut bytes, string.len() as u64);
+        bytes.extend_from_slice(string.as_bytes());
+    }
+    writer.write_all(&bytes)
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Writes every string of the table, in index order, in a compact binary format that
+    /// [`read_from`](struct.SymbolTable.html#method.read_from) reads back with the same
+    /// indexes.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("hello");
+    /// symbol_table.get("world");
+    ///
+    /// let mut bytes = Vec::new();
+    /// symbol_table.write_to(&mut bytes).unwrap();
+    /// let loaded = SymbolTable::read_from(bytes.as_slice()).unwrap();
+    /// assert_eq!(loaded.to_vec(), ["hello", "world"]);
+    /// ```
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_strings(writer, self.len(), self.iter())
+    }
+
+    /// Writes the table in its canonical form, which has the strings sorted by their
+    /// bytes, so that tables with the same strings write the same bytes, no matter the
+    /// order that the strings were interned in. This is the format to use for files
+    /// that are cached by their contents, such as in a build cache. The file is read
+    /// with [`read_from`](struct.SymbolTable.html#method.read_from), which gives the
+    /// strings their sorted indexes.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let a = SymbolTable::new();
+    /// let b = SymbolTable::new();
+    /// for string in ["main", "args", "env"] {
+    ///     a.get(string);
+    /// }
+    /// for string in ["env", "main", "args"] {
+    ///     b.get(string);
+    /// }
+    ///
+    /// let (mut a_bytes, mut b_bytes) = (Vec::new(), Vec::new());
+    /// a.write_canonical_to(&mut a_bytes).unwrap();
+    /// b.write_canonical_to(&mut b_bytes).unwrap();
+    /// assert_eq!(a_bytes, b_bytes);
+    ///
+    /// let loaded = SymbolTable::read_from(a_bytes.as_slice()).unwrap();
+    /// assert_eq!(loaded.to_vec(), ["args", "env", "main"]);
+    /// ```
+    pub fn write_canonical_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut strings: Vec<&str> = self.iter().collect();
+        strings.sort_unstable();
+        write_strings(writer, strings.len(), strings.into_iter())
+    }
+
+    /// Reads a table that was written by
+    /// [`write_to`](struct.SymbolTable.html#method.write_to), where every string has
+    /// the index that it had when it was written. This returns an
+    /// [`InvalidData`](io::ErrorKind::InvalidData) error if the bytes are malformed, or
+    /// contain the same string twice, as the indexes then can't be kept.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<SymbolTable<'strings>> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let symbol_table = SymbolTable::new();
+        for string in decode(&bytes)? {
+            if symbol_table.lookup_index(string).is_some() {
+                return Err(invalid_data(
+                    "The saved symbol table contains a duplicate string.",
+                ));
+            }
+            symbol_table.get(string);
+        }
+        Ok(symbol_table)
+    }
+
+    /// Saves the table to a file, see
+    /// [`write_to`](struct.SymbolTable.html#method.write_to).
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes)?;
+        fs::write(path, bytes)
+    }
+
+    /// Saves the table to a file in its canonical form, see
+    /// [`write_canonical_to`](struct.SymbolTable.html#method.write_canonical_to).
+    pub fn save_canonical(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        self.write_canonical_to(&mut bytes)?;
+        fs::write(path, bytes)
+    }
+
+    /// Loads a table from a file that was written by
+    /// [`save`](struct.SymbolTable.html#method.save), with the same indexes, see
+    /// [`read_from`](struct.SymbolTable.html#method.read_from).
+    ///
+    /// ```no_run
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("hello");
+    /// symbol_table.save("symbols.bin").unwrap();
+    ///
+    /// let loaded = SymbolTable::load("symbols.bin").unwrap();
+    /// assert_eq!(loaded.get("hello").index(), 0);
+    /// ```
+    pub fn load(path: impl AsRef<Path>) -> io::Result<SymbolTable<'strings>> {
+        SymbolTable::read_from(fs::read(path)?.as_slice())
+    }
+
+    /// Interns every string of a file that was written by
+    /// [`save`](struct.SymbolTable.html#method.save), such as one from a previous run,
+    /// to warm up the table before it is used. Unlike
+    /// [`load`](struct.SymbolTable.html#method.load), this works on a table that
+    /// already has strings, and the indexes aren't kept, so strings that are already
+    /// interned keep their index and the rest are appended. Returns the number of
+    /// strings that were newly interned.
+    ///
+    /// ```no_run
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// // A missing cache only means a colder start.
+    /// let warmed = symbol_table.warm_from("symbols.bin").unwrap_or(0);
+    /// println!("Pre-interned {warmed} strings");
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn warm_from(&self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let bytes = fs::read(path)?;
+        let len = self.len();
+        for string in decode(&bytes)? {
+            self.get(string);
+        }
+        Ok(self.len() - len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_save_load_and_warm() {
+        let path = std::env::temp_dir().join(format!("symbol-table-{}.bin", std::process::id()));
+        let symbol_table = SymbolTable::new();
+        for string in ["", "a", "ü", "a longer string"] {
+            symbol_table.get(string);
+        }
+        symbol_table.save(&path).unwrap();
+        let loaded = SymbolTable::load(&path).unwrap();
+        assert_eq!(loaded.to_vec(), symbol_table.to_vec());
+
+        let warm = SymbolTable::new();
+        warm.get("a longer string");
+        warm.get("b");
+        assert_eq!(warm.warm_from(&path).unwrap(), 3);
+        assert_eq!(warm.to_vec(), ["a longer string", "b", "", "a", "ü"]);
+        fs::remove_file(&path).unwrap();
+
+        let mut bytes = Vec::new();
+        symbol_table.write_to(&mut bytes).unwrap();
+        for malformed in [
+            &bytes[..bytes.len() - 1],
+            &bytes[1..],
+            b"SYMTAB\x01\x02\x01a\x01a",
+        ] {
+            let error = SymbolTable::read_from(malformed).err().unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
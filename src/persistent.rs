@@ -0,0 +1,250 @@
+//! An immutable table, whose versions share their structure.
+
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use fxhash::FxHasher;
+
+use crate::SymbolIndex;
+
+/// The number of bits of an index or hash that each level of a trie consumes.
+const BITS: u32 = 5;
+/// The number of children of a branch of a trie.
+const WIDTH: usize = 1 << BITS;
+const MASK: usize = WIDTH - 1;
+/// The number of hashes in a leaf of the lookup trie before it's split into a branch.
+const LEAF_LEN: usize = 8;
+
+/// A node of the 32-ary trie of strings, where the leaves hold up to 32 strings each.
+#[derive(Clone)]
+enum StringNode {
+    Branch(Vec<Rc<StringNode>>),
+    Leaf(Vec<Rc<str>>),
+}
+
+/// A node of the 32-ary trie that maps the hashes of strings to their indexes.
+#[derive(Clone)]
+enum HashNode {
+    Branch(Vec<Option<Rc<HashNode>>>),
+    Leaf(Vec<(u64, SymbolIndex)>),
+}
+
+/// An immutable table of interned strings, where each version is a snapshot that
+/// shares its structure with the others. Cloning the table is O(1), as it only clones
+/// the roots of its tries, and interning a string into a clone only copies the path of
+/// trie nodes that lead to the new string, which is O(log n). This suits editors that
+/// keep a version of the table for each edit to undo and redo them.
+///
+/// The strings are stored in a 32-ary trie by index, and looked up through a 32-ary
+/// trie of their hashes. The [`SymbolTable`](crate::SymbolTable) is faster when old
+/// versions don't need to be kept.
+///
+/// ```
+/// use gregtatum_symbol_table::PersistentSymbolTable;
+///
+/// let mut symbol_table = PersistentSymbolTable::new();
+/// symbol_table.intern("first");
+/// let undo = symbol_table.clone();
+///
+/// let second = symbol_table.intern("second");
+/// assert_eq!(symbol_table.resolve(second), Some("second"));
+/// assert_eq!(symbol_table.len(), 2);
+///
+/// // The snapshot is unchanged.
+/// assert_eq!(undo.lookup("second"), None);
+/// assert_eq!(undo.len(), 1);
+/// ```
+#[derive(Clone)]
+pub struct PersistentSymbolTable {
+    strings: Rc<StringNode>,
+    // The number of levels of branches above the leaves of the strings.
+    depth: u32,
+    hashes: Rc<HashNode>,
+    len: usize,
+}
+
+impl Default for PersistentSymbolTable {
+    fn default() -> Self {
+        PersistentSymbolTable {
+            strings: Rc::new(StringNode::Leaf(Vec::new())),
+            depth: 0,
+            hashes: Rc::new(HashNode::Leaf(Vec::new())),
+            len: 0,
+        }
+    }
+}
+
+fn hash(string: &str) -> u64 {
+    let mut hasher = FxHasher::default();
+    string.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl StringNode {
+    /// Creates an empty node at a level of the trie, where the leaves are at level 0.
+    fn empty(depth: u32) -> StringNode {
+        match depth {
+            0 => StringNode::Leaf(Vec::with_capacity(WIDTH)),
+            _ => StringNode::Branch(Vec::with_capacity(WIDTH)),
+        }
+    }
+
+    fn push(&mut self, depth: u32, index: SymbolIndex, string: Rc<str>) {
+        match self {
+            StringNode::Leaf(strings) => strings.push(string),
+            StringNode::Branch(children) => {
+                let slot = (index >> (depth * BITS)) & MASK;
+                if slot == children.len() {
+                    children.push(Rc::new(StringNode::empty(depth - 1)));
+                }
+                Rc::make_mut(&mut children[slot]).push(depth - 1, index, string);
+            }
+        }
+    }
+}
+
+impl HashNode {
+    fn insert(&mut self, level: u32, hash: u64, index: SymbolIndex) {
+        if let HashNode::Leaf(entries) = self {
+            // Past the last bits of the hash, the leaves hold all of the collisions.
+            if entries.len() < LEAF_LEN || level * BITS >= u64::BITS {
+                entries.push((hash, index));
+                return;
+            }
+            let entries = std::mem::take(entries);
+            *self = HashNode::Branch(vec![None; WIDTH]);
+            for (hash, index) in entries {
+                self.insert(level, hash, index);
+            }
+        }
+        if let HashNode::Branch(children) = self {
+            let slot = (hash >> (level * BITS)) as usize & MASK;
+            let child = children[slot].get_or_insert_with(|| Rc::new(HashNode::Leaf(Vec::new())));
+            Rc::make_mut(child).insert(level + 1, hash, index);
+        }
+    }
+
+    /// The indexes of the strings with a hash.
+    fn find(&self, hash: u64) -> impl Iterator<Item = SymbolIndex> + '_ {
+        let mut node = self;
+        let mut level = 0;
+        while let HashNode::Branch(children) = node {
+            match &children[(hash >> (level * BITS)) as usize & MASK] {
+                Some(child) => node = child,
+                None => break,
+            }
+            level += 1;
+        }
+        let entries = match node {
+            HashNode::Leaf(entries) => &entries[..],
+            HashNode::Branch(_) => &[],
+        };
+        entries
+            .iter()
+            .filter(move |(entry, _)| *entry == hash)
+            .map(|&(_, index)| index)
+    }
+}
+
+impl PersistentSymbolTable {
+    /// Creates an empty table.
+    pub fn new() -> PersistentSymbolTable {
+        PersistentSymbolTable::default()
+    }
+
+    /// Interns a string into this version of the table, and returns its index. Clones of
+    /// the table that were made before are unchanged.
+    pub fn intern(&mut self, string: &str) -> SymbolIndex {
+        let hash = hash(string);
+        if let Some(index) = self.lookup_hashed(string, hash) {
+            return index;
+        }
+        let index = self.len;
+        if index == WIDTH << (self.depth * BITS) {
+            // The trie is full, so it gets a new root with the old root as its first child.
+            let root = std::mem::replace(&mut self.strings, Rc::new(StringNode::empty(0)));
+            self.strings = Rc::new(StringNode::Branch(vec![root]));
+            self.depth += 1;
+        }
+        Rc::make_mut(&mut self.strings).push(self.depth, index, string.into());
+        Rc::make_mut(&mut self.hashes).insert(0, hash, index);
+        self.len += 1;
+        index
+    }
+
+    fn lookup_hashed(&self, string: &str, hash: u64) -> Option<SymbolIndex> {
+        self.hashes
+            .find(hash)
+            .find(|&index| self.resolve(index) == Some(string))
+    }
+
+    /// Looks up the index of a string without interning it.
+    pub fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        self.lookup_hashed(string, hash(string))
+    }
+
+    /// The string for an index, or `None` if it's out of range.
+    pub fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        if index >= self.len {
+            return None;
+        }
+        let mut node = &*self.strings;
+        let mut depth = self.depth;
+        loop {
+            match node {
+                StringNode::Branch(children) => {
+                    node = &children[(index >> (depth * BITS)) & MASK];
+                    depth -= 1;
+                }
+                StringNode::Leaf(strings) => return Some(&strings[index & MASK]),
+            }
+        }
+    }
+
+    /// The number of interned strings.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no strings are interned.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the strings in order of their indexes.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        (0..self.len).filter_map(|index| self.resolve(index))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_versions_share_structure() {
+        let mut symbol_table = PersistentSymbolTable::new();
+        let mut versions = Vec::new();
+        for i in 0..2000 {
+            if i % 500 == 0 {
+                versions.push(symbol_table.clone());
+            }
+            assert_eq!(symbol_table.intern(&i.to_string()), i);
+        }
+        assert_eq!(symbol_table.depth, 2);
+        assert_eq!(symbol_table.intern("1999"), 1999);
+        assert_eq!(symbol_table.resolve(1024), Some("1024"));
+        assert_eq!(symbol_table.resolve(2000), None);
+        assert!(symbol_table.iter().eq((0..2000).map(|i| i.to_string())));
+
+        for (version, len) in versions.iter().zip([0, 500, 1000, 1500]) {
+            assert_eq!(version.len(), len);
+            assert_eq!(version.lookup("499"), (len > 499).then_some(499));
+            assert_eq!(version.resolve(len), None);
+        }
+
+        // The first string is shared by every version that has it.
+        let first = |table: &PersistentSymbolTable| table.resolve(0).unwrap().as_ptr();
+        assert_eq!(first(&versions[1]), first(&symbol_table));
+    }
+}
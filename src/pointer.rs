@@ -0,0 +1,163 @@
+//! Recovering symbols from the `&str` pointers that the table hands out, and the debug
+//! assertions that back the pointer stability guarantee of the table.
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+impl<'strings> SymbolTable<'strings> {
+    /// Recognizes a `&str` that was handed out by the table, such as from
+    /// [`Symbol::str`](struct.Symbol.html#method.str), and returns its symbol without
+    /// hashing the string. This is useful for recovering cheap handles after a `&str`
+    /// has been passed through an external API. Sub-slices of an interned string are
+    /// returned as sliced symbols.
+    ///
+    /// Only the address is checked, so strings that weren't handed out by the table
+    /// return `None`, even if they are equal to an interned string. Empty strings have
+    /// no address in the table, so they also return `None`.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let greeting = symbol_table.get("hello world");
+    /// let string: &str = greeting.str();
+    ///
+    /// assert_eq!(symbol_table.symbol_of(string), Some(greeting));
+    ///
+    /// let world = symbol_table.symbol_of(&string[6..]).unwrap();
+    /// assert!(world.is_slice());
+    /// assert_eq!(world, "world");
+    ///
+    /// let copy = String::from("hello world");
+    /// assert_eq!(symbol_table.symbol_of(&copy), None);
+    /// ```
+    pub fn symbol_of(&'strings self, string: &str) -> Option<Symbol<'strings>> {
+        if string.is_empty() {
+            return None;
+        }
+        let address = string.as_ptr() as usize;
+        let index = self.index_at_address(address)?;
+        let parent = self.str(index);
+        let start = address - parent.as_ptr() as usize;
+        let end = start.checked_add(string.len())?;
+        if end > parent.len() {
+            return None;
+        }
+        let symbol = Symbol::new(self, index);
+        if end - start == parent.len() {
+            return Some(symbol);
+        }
+        symbol.slice(start..end)
+    }
+
+    /// Finds the index of the string whose storage starts at or before the address,
+    /// using a list of addresses that is lazily sorted as strings are added.
+    fn index_at_address(&self, address: usize) -> Option<SymbolIndex> {
+        let mut addresses = self.addresses.borrow_mut();
+        let indexed = self.addressed_len.get();
+        if indexed < self.len() {
+            for index in indexed..self.len() {
+                let string = self.str(index);
+                if !string.is_empty() {
+                    addresses.push((string.as_ptr() as usize, index));
+                }
+            }
+            addresses.sort_unstable();
+            self.addressed_len.set(self.len());
+        }
+        let position = addresses.partition_point(|&(start, _)| start <= address);
+        Some(addresses.get(position.checked_sub(1)?)?.1)
+    }
+
+    /// Records the address of a newly interned string in debug builds.
+    pub(crate) fn record_address(&self, index: SymbolIndex) {
+        #[cfg(debug_assertions)]
+        {
+            let mut addresses = self.interned_addresses.borrow_mut();
+            debug_assert_eq!(addresses.len(), index);
+            addresses.push(self.symbols[index].as_ptr() as usize);
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = index;
+    }
+
+    /// Asserts in debug builds that a string hasn't moved since it was interned.
+    pub(crate) fn assert_address(&self, index: SymbolIndex, string: &str) {
+        #[cfg(debug_assertions)]
+        if let Some(&address) = self.interned_addresses.borrow().get(index) {
+            debug_assert_eq!(
+                string.as_ptr() as usize,
+                address,
+                "The string for index {index} moved after it was interned."
+            );
+        }
+        #[cfg(not(debug_assertions))]
+        let _ = (index, string);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_symbol_of() {
+        let symbol_table = SymbolTable::new();
+        let empty = symbol_table.get("");
+        assert_eq!(symbol_table.symbol_of(empty.str()), None);
+
+        let strings: Vec<_> = (0..100)
+            .map(|i| symbol_table.get(format!("string {i}")))
+            .collect();
+        for symbol in &strings {
+            let found = symbol_table.symbol_of(symbol.str()).unwrap();
+            assert_eq!(found.index(), symbol.index());
+            assert!(!found.is_slice());
+        }
+
+        // Strings added after the addresses are sorted are found.
+        let late = symbol_table.get("late");
+        let a = symbol_table.symbol_of(&late.str()[1..3]).unwrap();
+        assert_eq!(a, "at");
+        assert_eq!(a.range(), 1..3);
+
+        let outside = [0u8; 4];
+        let outside = std::str::from_utf8(&outside).unwrap();
+        assert_eq!(symbol_table.symbol_of(outside), None);
+    }
+
+    #[test]
+    fn test_pointer_stability() {
+        let symbol_table = SymbolTable::new();
+        let first: Vec<*const u8> = (0..10)
+            .map(|i| symbol_table.get(format!("first {i}")).str().as_ptr())
+            .collect();
+        let slice = symbol_table
+            .get("first 0")
+            .slice(2..5)
+            .unwrap()
+            .str()
+            .as_ptr();
+
+        // Grow the table well past its initial capacity.
+        for i in 0..10_000 {
+            symbol_table.get(format!("grow {i}"));
+        }
+
+        for (i, &pointer) in first.iter().enumerate() {
+            assert_eq!(
+                symbol_table.get(format!("first {i}")).str().as_ptr(),
+                pointer
+            );
+            assert_eq!(symbol_table[i].as_ptr(), pointer);
+        }
+        assert_eq!(
+            symbol_table
+                .get("first 0")
+                .slice(2..5)
+                .unwrap()
+                .str()
+                .as_ptr(),
+            slice
+        );
+    }
+}
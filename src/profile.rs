@@ -0,0 +1,193 @@
+//! Attribution of table growth to named scopes, such as the phases of a compiler.
+
+use std::fmt;
+
+use fxhash::FxHashMap;
+
+use crate::SymbolTable;
+
+/// The strings interned while a profile scope was the innermost active scope.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScopeProfile {
+    /// The number of new strings that were interned.
+    pub interned: usize,
+    /// The total length in bytes of the new strings.
+    pub bytes: usize,
+}
+
+/// The profiles of every scope, sorted by the bytes interned, largest first. This is
+/// created by [`SymbolTable::profile_report`](struct.SymbolTable.html#method.profile_report),
+/// and is displayed as a table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProfileReport {
+    /// The name and profile of each scope.
+    pub scopes: Vec<(&'static str, ScopeProfile)>,
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .scopes
+            .iter()
+            .map(|(name, _)| name.len())
+            .chain(Some("scope".len()))
+            .max()
+            .unwrap_or_default();
+        writeln!(f, "{:width$} {:>10} {:>10}", "scope", "interned", "bytes")?;
+        for (name, profile) in &self.scopes {
+            writeln!(
+                f,
+                "{name:width$} {:>10} {:>10}",
+                profile.interned, profile.bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A guard that attributes newly interned strings to a named scope until it is dropped.
+/// Created with [`SymbolTable::profile_scope`](struct.SymbolTable.html#method.profile_scope).
+pub struct ProfileScope<'table, 'strings> {
+    symbol_table: &'table SymbolTable<'strings>,
+    name: &'static str,
+}
+
+impl Drop for ProfileScope<'_, '_> {
+    fn drop(&mut self) {
+        let active = &mut self.symbol_table.profiler.borrow_mut().active;
+        // Scopes are normally dropped in reverse order, but remove the latest scope with
+        // this name in case guards were dropped out of order.
+        if let Some(position) = active.iter().rposition(|name| *name == self.name) {
+            active.remove(position);
+        }
+    }
+}
+
+/// The active scopes, innermost last, and the profile of every scope.
+#[derive(Default)]
+pub(crate) struct Profiler {
+    active: Vec<&'static str>,
+    profiles: FxHashMap<&'static str, ScopeProfile>,
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Attributes the strings interned until the returned guard is dropped to a named
+    /// scope. When scopes are nested, strings are attributed to the innermost one. The
+    /// profiles are accumulated across every use of a scope name, and reported by
+    /// [`profile_report`](struct.SymbolTable.html#method.profile_report).
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// {
+    ///     let _scope = symbol_table.profile_scope("parse");
+    ///     symbol_table.get("fn");
+    ///     symbol_table.get("main");
+    ///     {
+    ///         let _scope = symbol_table.profile_scope("parse_expr");
+    ///         symbol_table.get("println");
+    ///         symbol_table.get("main");
+    ///     }
+    /// }
+    /// symbol_table.get("unattributed");
+    ///
+    /// let report = symbol_table.profile_report();
+    /// assert_eq!(report.scopes[0].0, "parse_expr");
+    /// assert_eq!(report.scopes[0].1.interned, 1);
+    /// assert_eq!(report.scopes[0].1.bytes, 7);
+    /// assert_eq!(report.scopes[1].0, "parse");
+    /// assert_eq!(report.scopes[1].1.interned, 2);
+    /// assert_eq!(report.scopes[1].1.bytes, 6);
+    ///
+    /// println!("{report}");
+    /// ```
+    pub fn profile_scope(&self, name: &'static str) -> ProfileScope<'_, 'strings> {
+        let mut profiler = self.profiler.borrow_mut();
+        profiler.active.push(name);
+        profiler.profiles.entry(name).or_default();
+        ProfileScope {
+            symbol_table: self,
+            name,
+        }
+    }
+
+    /// Reports the strings interned within each profile scope, sorted by the bytes
+    /// interned, largest first.
+    pub fn profile_report(&self) -> ProfileReport {
+        let mut scopes: Vec<_> = self
+            .profiler
+            .borrow()
+            .profiles
+            .iter()
+            .map(|(name, profile)| (*name, *profile))
+            .collect();
+        scopes.sort_by(|(a_name, a), (b_name, b)| {
+            b.bytes
+                .cmp(&a.bytes)
+                .then(b.interned.cmp(&a.interned))
+                .then(a_name.cmp(b_name))
+        });
+        ProfileReport { scopes }
+    }
+
+    /// Attributes a newly interned string to the innermost active scope.
+    pub(crate) fn profile_intern(&self, bytes: usize) {
+        let mut profiler = self.profiler.borrow_mut();
+        let Profiler { active, profiles } = &mut *profiler;
+        if let Some(name) = active.last() {
+            let profile = profiles.entry(name).or_default();
+            profile.interned += 1;
+            profile.bytes += bytes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scopes_accumulate() {
+        let symbol_table = SymbolTable::new();
+        for i in 0..3 {
+            let _scope = symbol_table.profile_scope("loop");
+            symbol_table.get(format!("{i}"));
+        }
+        let outer = symbol_table.profile_scope("outer");
+        let inner = symbol_table.profile_scope("inner");
+        drop(outer);
+        symbol_table.get("still inner");
+        drop(inner);
+        symbol_table.get("none");
+
+        let report = symbol_table.profile_report();
+        assert_eq!(
+            report.scopes,
+            [
+                (
+                    "inner",
+                    ScopeProfile {
+                        interned: 1,
+                        bytes: 11
+                    }
+                ),
+                (
+                    "loop",
+                    ScopeProfile {
+                        interned: 3,
+                        bytes: 3
+                    }
+                ),
+                ("outer", ScopeProfile::default()),
+            ]
+        );
+        assert_eq!(
+            report.to_string(),
+            "scope   interned      bytes\n\
+             inner          1         11\n\
+             loop           3          3\n\
+             outer          0          0\n"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! Encoders and decoders for the canonical protobuf schema of the table, so that tables
+//! and deltas can be consumed from other languages. The schema is in
+//! `proto/symbol_table.proto`, and is also available as [`SCHEMA`]. This requires the
+//! `protobuf` feature.
+//!
+//! ```
+//! use gregtatum_symbol_table::{protobuf, SymbolTable};
+//!
+//! let symbol_table = SymbolTable::new();
+//! symbol_table.get("hello");
+//! symbol_table.get("world");
+//!
+//! let bytes = protobuf::encode_table(&symbol_table);
+//! let decoded = protobuf::decode_table(&bytes).unwrap();
+//! assert_eq!(decoded.iter().collect::<Vec<_>>(), ["hello", "world"]);
+//! ```
+
+use std::fmt;
+
+use crate::{varint, Delta, SymbolIndex, SymbolTable};
+
+/// The protobuf schema for the encoded messages.
+pub const SCHEMA: &str = include_str!("../proto/symbol_table.proto");
+
+const VARINT: u64 = 0;
+const FIXED64: u64 = 1;
+const LENGTH_DELIMITED: u64 = 2;
+const FIXED32: u64 = 5;
+
+/// The error for bytes that aren't a valid encoding of a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The message ended in the middle of a field.
+    Truncated,
+    /// A field has a wire type that isn't supported, or doesn't match the schema.
+    InvalidWireType,
+    /// A string isn't valid UTF-8.
+    InvalidUtf8,
+    /// The table has the same string at two indexes.
+    Duplicate {
+        /// The index of the repeated string.
+        index: SymbolIndex,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "The message ended in the middle of a field."),
+            DecodeError::InvalidWireType => write!(f, "A field has an invalid wire type."),
+            DecodeError::InvalidUtf8 => write!(f, "A string isn't valid UTF-8."),
+            DecodeError::Duplicate { index } => {
+                write!(f, "The string at index {index} is a duplicate.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Encodes every string of a table as a `SymbolTable` message.
+pub fn encode_table(symbol_table: &SymbolTable) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for string in symbol_table.iter() {
+        encode_string(&mut bytes, 1, string);
+    }
+    bytes
+}
+
+/// Decodes a `SymbolTable` message into a new table, where each string has the same
+/// index as in the encoded table. Unknown fields are skipped.
+pub fn decode_table<'strings>(bytes: &[u8]) -> Result<SymbolTable<'strings>, DecodeError> {
+    let symbol_table = SymbolTable::new();
+    decode_fields(bytes, |field, value| {
+        match (field, value) {
+            (1, Value::Bytes(string)) => {
+                let string = std::str::from_utf8(string).map_err(|_| DecodeError::InvalidUtf8)?;
+                let index = symbol_table.len();
+                if symbol_table.intern_index(string) != index {
+                    return Err(DecodeError::Duplicate { index });
+                }
+            }
+            (1, _) => return Err(DecodeError::InvalidWireType),
+            _ => {}
+        }
+        Ok(())
+    })?;
+    Ok(symbol_table)
+}
+
+/// Encodes a delta as a `Delta` message.
+///
+/// ```
+/// use gregtatum_symbol_table::{protobuf, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// symbol_table.get("a");
+/// let mark = symbol_table.mark();
+/// symbol_table.get("b");
+///
+/// let delta = symbol_table.delta_since(mark);
+/// let bytes = protobuf::encode_delta(&delta);
+/// assert_eq!(bytes, [0x08, 0x01, 0x12, 0x01, b'b']);
+/// assert_eq!(protobuf::decode_delta(&bytes), Ok(delta));
+/// ```
+pub fn encode_delta(delta: &Delta) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if delta.start() != 0 {
+        varint::write_u64(&mut bytes, 1 << 3 | VARINT);
+        varint::write_u64(&mut bytes, delta.start() as u64);
+    }
+    for string in delta.strings() {
+        encode_string(&mut bytes, 2, string);
+    }
+    bytes
+}
+
+/// Decodes a `Delta` message. Unknown fields are skipped.
+pub fn decode_delta(bytes: &[u8]) -> Result<Delta, DecodeError> {
+    let mut start = 0;
+    let mut strings = Vec::new();
+    decode_fields(bytes, |field, value| {
+        match (field, value) {
+            (1, Value::Varint(value)) => start = value as SymbolIndex,
+            (2, Value::Bytes(string)) => strings
+                .push(String::from_utf8(string.to_vec()).map_err(|_| DecodeError::InvalidUtf8)?),
+            (1 | 2, _) => return Err(DecodeError::InvalidWireType),
+            _ => {}
+        }
+        Ok(())
+    })?;
+    Ok(Delta::new(start, strings))
+}
+
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+fn encode_string(bytes: &mut Vec<u8>, field: u64, string: &str) {
+    varint::write_u64(bytes, field << 3 | LENGTH_DELIMITED);
+    varint::write_u64(bytes, string.len() as u64);
+    bytes.extend_from_slice(string.as_bytes());
+}
+
+fn decode_varint(bytes: &mut &[u8]) -> Result<u64, DecodeError> {
+    varint::read_u64(bytes).map_err(|_| DecodeError::Truncated)
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], DecodeError> {
+    if len > bytes.len() {
+        return Err(DecodeError::Truncated);
+    }
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
+
+/// Calls the callback with the number and value of every field of a message, in order.
+fn decode_fields<'a>(
+    mut bytes: &'a [u8],
+    mut callback: impl FnMut(u64, Value<'a>) -> Result<(), DecodeError>,
+) -> Result<(), DecodeError> {
+    while !bytes.is_empty() {
+        let key = decode_varint(&mut bytes)?;
+        let value = match key & 0x7 {
+            VARINT => Value::Varint(decode_varint(&mut bytes)?),
+            LENGTH_DELIMITED => {
+                let len = decode_varint(&mut bytes)?;
+                let len = usize::try_from(len).map_err(|_| DecodeError::Truncated)?;
+                Value::Bytes(take(&mut bytes, len)?)
+            }
+            FIXED64 => {
+                take(&mut bytes, 8)?;
+                Value::Fixed
+            }
+            FIXED32 => {
+                take(&mut bytes, 4)?;
+                Value::Fixed
+            }
+            _ => return Err(DecodeError::InvalidWireType),
+        };
+        callback(key >> 3, value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode_table(&[0x0a, 0x05, b'a']).err(),
+            Some(DecodeError::Truncated)
+        );
+        assert_eq!(
+            decode_table(&[0x0a, 0x01, 0xff]).err(),
+            Some(DecodeError::InvalidUtf8)
+        );
+        assert_eq!(
+            decode_table(&[0x0a, 0x01, b'a', 0x0a, 0x01, b'a']).err(),
+            Some(DecodeError::Duplicate { index: 1 })
+        );
+        assert_eq!(
+            decode_table(&[0x0b]).err(),
+            Some(DecodeError::InvalidWireType)
+        );
+        assert_eq!(
+            decode_table(&[0x08, 0x01]).err(),
+            Some(DecodeError::InvalidWireType)
+        );
+        assert_eq!(
+            decode_delta(&[0x0a, 0x00]),
+            Err(DecodeError::InvalidWireType)
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_are_skipped() {
+        let mut bytes = vec![
+            0x18, 0xac, 0x02, 0x21, 0, 0, 0, 0, 0, 0, 0, 0, 0x2d, 0, 0, 0, 0,
+        ];
+        encode_string(&mut bytes, 1, "kept");
+        encode_string(&mut bytes, 9, "skipped");
+        let symbol_table = decode_table(&bytes).unwrap();
+        assert_eq!(symbol_table.iter().collect::<Vec<_>>(), ["kept"]);
+
+        let long = "x".repeat(300);
+        let delta = Delta::new(1 << 40, vec![long.clone(), String::new()]);
+        assert_eq!(decode_delta(&encode_delta(&delta)), Ok(delta));
+    }
+}
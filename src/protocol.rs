@@ -0,0 +1,409 @@
+//! A small framed protocol for keeping two [`SymbolTable`]s in sync, such as between a
+//! language server and its client, so that both sides agree on the index of every
+//! string and only need to exchange integers afterwards.
+//!
+//! The protocol is sans-io: the [`SymbolSender`] and [`SymbolReceiver`] state machines
+//! only produce and consume bytes, and the caller is responsible for moving the bytes
+//! over a socket, pipe, or any other transport.
+//!
+//! Every frame is a little-endian `u32` length prefix followed by the body. The body
+//! starts with a tag byte and a `u64` sequence number:
+//!
+//! - `1`, a [`Frame::Delta`]: a `u32` start index, a `u32` count of strings, and then
+//!   each string as a `u32` length prefix followed by its UTF-8 bytes.
+//! - `2`, a [`Frame::Ack`]: a `u32` index, up to which the receiver has every string.
+//!
+//! ```
+//! use gregtatum_symbol_table::protocol::{SymbolReceiver, SymbolSender};
+//! use gregtatum_symbol_table::SymbolTable;
+//!
+//! let server = SymbolTable::new();
+//! let client = SymbolTable::new();
+//! let mut sender = SymbolSender::new();
+//! let mut receiver = SymbolReceiver::new();
+//!
+//! let vec = server.get("Vec");
+//! let bytes = sender.poll_transmit(&server).unwrap();
+//!
+//! // The bytes are sent to the client, which replies with an acknowledgement.
+//! let ack = receiver.receive(&client, &bytes).unwrap();
+//! sender.receive(&ack).unwrap();
+//!
+//! // The server can now send the index alone.
+//! assert!(sender.is_acknowledged(vec.index()));
+//! assert_eq!(&client[vec.index()], "Vec");
+//! ```
+
+use std::fmt;
+
+use crate::{Delta, DeltaError, SymbolIndex, SymbolTable};
+
+const DELTA_TAG: u8 = 1;
+const ACK_TAG: u8 = 2;
+
+/// A single message of the protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+    /// New strings, starting at an index.
+    Delta {
+        /// The sequence number of the frame, starting at 0.
+        sequence: u64,
+        /// The strings and the index of the first one.
+        delta: Delta,
+    },
+    /// Acknowledges the delta with a sequence number.
+    Ack {
+        /// The sequence number of the acknowledged delta.
+        sequence: u64,
+        /// The receiver has every string before this index.
+        end: SymbolIndex,
+    },
+}
+
+impl Frame {
+    /// Encodes the frame, including its length prefix.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        match self {
+            Frame::Delta { sequence, delta } => {
+                body.push(DELTA_TAG);
+                body.extend_from_slice(&sequence.to_le_bytes());
+                push_u32(&mut body, delta.start());
+                push_u32(&mut body, delta.strings().len());
+                for string in delta.strings() {
+                    push_u32(&mut body, string.len());
+                    body.extend_from_slice(string.as_bytes());
+                }
+            }
+            Frame::Ack { sequence, end } => {
+                body.push(ACK_TAG);
+                body.extend_from_slice(&sequence.to_le_bytes());
+                push_u32(&mut body, *end);
+            }
+        }
+        let mut bytes = Vec::with_capacity(4 + body.len());
+        push_u32(&mut bytes, body.len());
+        bytes.extend_from_slice(&body);
+        bytes
+    }
+
+    /// Decodes the body of a frame, without its length prefix.
+    fn decode(body: &[u8]) -> Result<Frame, ProtocolError> {
+        let mut reader = Reader { bytes: body };
+        let tag = reader.take(1)?[0];
+        let sequence = u64::from_le_bytes(reader.take(8)?.try_into().unwrap());
+        let frame = match tag {
+            DELTA_TAG => {
+                let start = reader.u32()?;
+                let count = reader.u32()?;
+                // Don't trust the count for the allocation, as each string needs at least
+                // 4 bytes.
+                let mut strings = Vec::with_capacity(count.min(reader.bytes.len() / 4));
+                for _ in 0..count {
+                    let len = reader.u32()?;
+                    let string = std::str::from_utf8(reader.take(len)?)
+                        .map_err(|_| ProtocolError::Malformed)?;
+                    strings.push(string.to_owned());
+                }
+                Frame::Delta {
+                    sequence,
+                    delta: Delta::new(start, strings),
+                }
+            }
+            ACK_TAG => Frame::Ack {
+                sequence,
+                end: reader.u32()?,
+            },
+            _ => return Err(ProtocolError::Malformed),
+        };
+        if !reader.bytes.is_empty() {
+            return Err(ProtocolError::Malformed);
+        }
+        Ok(frame)
+    }
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: usize) {
+    let value = u32::try_from(value).expect("The protocol is limited to u32 lengths.");
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProtocolError> {
+        if len > self.bytes.len() {
+            return Err(ProtocolError::Malformed);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<usize, ProtocolError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()) as usize)
+    }
+}
+
+/// Splits a stream of bytes into frames, buffering partial frames until the rest of
+/// their bytes arrive.
+#[derive(Clone, Debug, Default)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+}
+
+impl FrameDecoder {
+    /// Creates an empty decoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffers bytes that were read from the transport.
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Decodes the next complete frame, or returns `None` if more bytes are needed.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::protocol::{Frame, FrameDecoder};
+    ///
+    /// let bytes = Frame::Ack { sequence: 0, end: 3 }.encode();
+    /// let mut decoder = FrameDecoder::new();
+    /// decoder.push(&bytes[..5]);
+    /// assert_eq!(decoder.next_frame(), Ok(None));
+    /// decoder.push(&bytes[5..]);
+    /// assert_eq!(decoder.next_frame(), Ok(Some(Frame::Ack { sequence: 0, end: 3 })));
+    /// ```
+    pub fn next_frame(&mut self) -> Result<Option<Frame>, ProtocolError> {
+        let Some(prefix) = self.buffer.get(..4) else {
+            return Ok(None);
+        };
+        let len = u32::from_le_bytes(prefix.try_into().unwrap()) as usize;
+        let Some(body) = self.buffer.get(4..4 + len) else {
+            return Ok(None);
+        };
+        let frame = Frame::decode(body);
+        self.buffer.drain(..4 + len);
+        frame.map(Some)
+    }
+}
+
+/// The errors for bytes that don't follow the protocol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// A frame couldn't be decoded.
+    Malformed,
+    /// A frame was received that wasn't expected by this side of the protocol.
+    UnexpectedFrame,
+    /// A frame skipped over a sequence number.
+    OutOfOrder {
+        /// The next sequence number that was expected.
+        expected: u64,
+        /// The sequence number of the frame.
+        sequence: u64,
+    },
+    /// A delta couldn't be applied to the table.
+    Delta(DeltaError),
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::Malformed => write!(f, "A frame couldn't be decoded."),
+            ProtocolError::UnexpectedFrame => write!(f, "An unexpected frame was received."),
+            ProtocolError::OutOfOrder { expected, sequence } => write!(
+                f,
+                "Expected the frame with sequence number {expected}, but received {sequence}."
+            ),
+            ProtocolError::Delta(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// The sending side of the protocol, which ships the strings that were interned into a
+/// table since the last transmission, and tracks which of them were acknowledged.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolSender {
+    next_sequence: u64,
+    acknowledged_sequence: u64,
+    sent: SymbolIndex,
+    acknowledged: SymbolIndex,
+    decoder: FrameDecoder,
+}
+
+impl SymbolSender {
+    /// Creates a sender for a receiver that has no strings yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encodes a delta frame of the strings interned since the last transmission, or
+    /// returns `None` if there are no new strings.
+    pub fn poll_transmit(&mut self, symbol_table: &SymbolTable) -> Option<Vec<u8>> {
+        let delta = symbol_table.delta_since(crate::Mark::at(self.sent));
+        if delta.is_empty() {
+            return None;
+        }
+        self.sent = delta.end();
+        let frame = Frame::Delta {
+            sequence: self.next_sequence,
+            delta,
+        };
+        self.next_sequence += 1;
+        Some(frame.encode())
+    }
+
+    /// Handles bytes from the receiver, which contain acknowledgements.
+    pub fn receive(&mut self, bytes: &[u8]) -> Result<(), ProtocolError> {
+        self.decoder.push(bytes);
+        while let Some(frame) = self.decoder.next_frame()? {
+            match frame {
+                Frame::Ack { sequence, end } => {
+                    if sequence >= self.next_sequence || end > self.sent {
+                        return Err(ProtocolError::UnexpectedFrame);
+                    }
+                    if end > self.acknowledged {
+                        self.acknowledged = end;
+                        self.acknowledged_sequence = sequence + 1;
+                    }
+                }
+                Frame::Delta { .. } => return Err(ProtocolError::UnexpectedFrame),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the receiver acknowledged the string for an index, so the index
+    /// can be sent on its own.
+    pub fn is_acknowledged(&self, index: SymbolIndex) -> bool {
+        index < self.acknowledged
+    }
+
+    /// Forgets the strings that were sent but not acknowledged, so that they are sent
+    /// again by the next [`poll_transmit`](SymbolSender::poll_transmit). This is for
+    /// when frames may have been lost, such as after reconnecting.
+    pub fn retransmit_unacknowledged(&mut self) {
+        self.sent = self.acknowledged;
+        self.next_sequence = self.acknowledged_sequence;
+    }
+}
+
+/// The receiving side of the protocol, which applies deltas to a table and produces
+/// acknowledgements.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolReceiver {
+    next_sequence: u64,
+    decoder: FrameDecoder,
+}
+
+impl SymbolReceiver {
+    /// Creates a receiver for a sender that hasn't sent anything yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles bytes from the sender, applying every complete delta to the table, and
+    /// returns the acknowledgement frames to send back, which may be empty. Deltas that
+    /// were already applied, such as ones that were retransmitted, are acknowledged
+    /// again.
+    pub fn receive(
+        &mut self,
+        symbol_table: &SymbolTable,
+        bytes: &[u8],
+    ) -> Result<Vec<u8>, ProtocolError> {
+        self.decoder.push(bytes);
+        let mut acks = Vec::new();
+        while let Some(frame) = self.decoder.next_frame()? {
+            let Frame::Delta { sequence, delta } = frame else {
+                return Err(ProtocolError::UnexpectedFrame);
+            };
+            if sequence > self.next_sequence {
+                return Err(ProtocolError::OutOfOrder {
+                    expected: self.next_sequence,
+                    sequence,
+                });
+            }
+            symbol_table
+                .apply_delta(&delta)
+                .map_err(ProtocolError::Delta)?;
+            self.next_sequence = self.next_sequence.max(sequence + 1);
+            acks.extend(
+                Frame::Ack {
+                    sequence,
+                    end: delta.end(),
+                }
+                .encode(),
+            );
+        }
+        Ok(acks)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_frames_round_trip() {
+        let frames = [
+            Frame::Delta {
+                sequence: 7,
+                delta: Delta::new(3, vec!["".into(), "ünïcödé".into()]),
+            },
+            Frame::Ack {
+                sequence: u64::MAX,
+                end: 0,
+            },
+        ];
+        let mut decoder = FrameDecoder::new();
+        for frame in &frames {
+            decoder.push(&frame.encode());
+        }
+        for frame in frames {
+            assert_eq!(decoder.next_frame(), Ok(Some(frame)));
+        }
+        assert_eq!(decoder.next_frame(), Ok(None));
+
+        decoder.push(&[2, 0, 0, 0, 9, 9]);
+        assert_eq!(decoder.next_frame(), Err(ProtocolError::Malformed));
+        assert_eq!(decoder.next_frame(), Ok(None));
+    }
+
+    #[test]
+    fn test_retransmission() {
+        let server = SymbolTable::new();
+        let client = SymbolTable::new();
+        let mut sender = SymbolSender::new();
+        let mut receiver = SymbolReceiver::new();
+
+        server.get("a");
+        let lost = sender.poll_transmit(&server).unwrap();
+        server.get("b");
+        let out_of_order = sender.poll_transmit(&server).unwrap();
+        assert_eq!(sender.poll_transmit(&server), None);
+        assert_eq!(
+            receiver.receive(&client, &out_of_order),
+            Err(ProtocolError::OutOfOrder {
+                expected: 0,
+                sequence: 1
+            })
+        );
+
+        sender.retransmit_unacknowledged();
+        let resent = sender.poll_transmit(&server).unwrap();
+        let ack = receiver.receive(&client, &resent).unwrap();
+        sender.receive(&ack).unwrap();
+        assert!(sender.is_acknowledged(1));
+
+        // A late duplicate of the lost frame is acknowledged again.
+        let ack = receiver.receive(&client, &lost).unwrap();
+        sender.receive(&ack).unwrap();
+        assert!(sender.is_acknowledged(1));
+        assert_eq!(client.iter().collect::<Vec<_>>(), ["a", "b"]);
+    }
+}
@@ -0,0 +1,404 @@
+//! A compact, self-describing format for structured records, such as repeated log
+//! lines, where the keys and repeated values are written as the varint identifiers of
+//! interned strings. The strings themselves are written once, in chunks that precede
+//! the first record to use them, so a stream can be decoded without any other schema.
+//!
+//! A stream is a sequence of chunks, which each start with a tag byte. Every integer is
+//! a LEB128 [`varint`](crate::varint):
+//!
+//! - `1`, strings: the identifier of the first string, the count of strings, and then
+//!   each string as its length followed by its UTF-8 bytes.
+//! - `2`, a record: the count of fields, and then each field as the identifier of its
+//!   key, followed by its value. A value is a tag byte, followed by its payload:
+//!   - `0` for null, `1` for false, and `2` for true, which have no payload.
+//!   - `3`, an integer, zigzag encoded.
+//!   - `4`, a float, as 8 little-endian bytes.
+//!   - `5`, an interned string, as its identifier.
+//!   - `6`, a string that isn't interned, as its length followed by its UTF-8 bytes.
+//!
+//! ```
+//! use gregtatum_symbol_table::record::{RecordReader, RecordWriter, Value};
+//! use gregtatum_symbol_table::SymbolTable;
+//!
+//! let symbol_table = SymbolTable::new();
+//! let level = symbol_table.get("level");
+//! let message = symbol_table.get("message");
+//! let mut writer = RecordWriter::new(&symbol_table);
+//! for i in 0..3 {
+//!     writer.write(&[
+//!         (level, Value::Symbol(symbol_table.get("INFO"))),
+//!         (message, Value::String(format!("Request {i}"))),
+//!     ]);
+//! }
+//! let bytes = writer.into_bytes();
+//!
+//! let other_table = SymbolTable::new();
+//! let mut reader = RecordReader::new(&other_table);
+//! let records = reader.read_all(&bytes).unwrap();
+//! assert_eq!(records.len(), 3);
+//! assert_eq!(records[2][0].0, "level");
+//! assert_eq!(records[2][1].1, Value::String("Request 2".into()));
+//! ```
+
+use std::fmt;
+
+use crate::varint::{self, VarintError};
+use crate::{Mark, Symbol, SymbolIndex, SymbolTable};
+
+const STRINGS_TAG: u8 = 1;
+const RECORD_TAG: u8 = 2;
+
+const NULL_TAG: u8 = 0;
+const FALSE_TAG: u8 = 1;
+const TRUE_TAG: u8 = 2;
+const INT_TAG: u8 = 3;
+const FLOAT_TAG: u8 = 4;
+const SYMBOL_TAG: u8 = 5;
+const STRING_TAG: u8 = 6;
+
+/// The value of a field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'strings> {
+    /// The absence of a value.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A signed integer.
+    Int(i64),
+    /// A floating point number.
+    Float(f64),
+    /// A string that is interned, which is written as its identifier. This suits values
+    /// that repeat, like levels or service names.
+    Symbol(Symbol<'strings>),
+    /// A string that is written inline, which suits values that rarely repeat, like
+    /// messages.
+    String(String),
+}
+
+/// The fields of a record, in order, with their keys.
+pub type Record<'strings> = Vec<(Symbol<'strings>, Value<'strings>)>;
+
+/// The error for bytes that aren't a valid stream of records.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RecordError {
+    /// The bytes ended in the middle of a chunk.
+    Truncated,
+    /// A chunk isn't valid, such as having an unknown tag or invalid UTF-8.
+    Malformed,
+    /// A record uses an identifier that no strings chunk has defined.
+    UnknownId {
+        /// The identifier.
+        id: u64,
+    },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::Truncated => write!(f, "The bytes ended in the middle of a chunk."),
+            RecordError::Malformed => write!(f, "A chunk is malformed."),
+            RecordError::UnknownId { id } => {
+                write!(f, "The identifier {id} wasn't defined before it was used.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl From<VarintError> for RecordError {
+    fn from(error: VarintError) -> Self {
+        match error {
+            VarintError::Truncated => RecordError::Truncated,
+            VarintError::Overflow => RecordError::Malformed,
+        }
+    }
+}
+
+/// Encodes records, along with the strings of the table that they use. The identifiers
+/// in the stream are the indexes of the table.
+pub struct RecordWriter<'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    // The strings before this have been written.
+    mark: Mark,
+    bytes: Vec<u8>,
+}
+
+impl<'strings> RecordWriter<'strings> {
+    /// Creates a writer for records whose symbols are from the table.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        RecordWriter {
+            symbol_table,
+            mark: Mark::at(0),
+            bytes: Vec::new(),
+        }
+    }
+
+    /// Encodes a record, preceded by any strings that were interned since the last
+    /// record. The symbols must be from the writer's table.
+    pub fn write(&mut self, fields: &[(Symbol<'strings>, Value<'strings>)]) {
+        // Slices are interned as full strings, before the new strings are written.
+        let fields: Vec<(SymbolIndex, &Value)> = fields
+            .iter()
+            .map(|(key, value)| (key.deslice().index(), value))
+            .collect();
+        let values: Vec<Option<SymbolIndex>> = fields
+            .iter()
+            .map(|(_, value)| match value {
+                Value::Symbol(symbol) => Some(symbol.deslice().index()),
+                _ => None,
+            })
+            .collect();
+
+        let delta = self.symbol_table.delta_since(self.mark);
+        if !delta.is_empty() {
+            self.bytes.push(STRINGS_TAG);
+            write_usize(&mut self.bytes, delta.start());
+            write_usize(&mut self.bytes, delta.strings().len());
+            for string in delta.strings() {
+                write_str(&mut self.bytes, string);
+            }
+            self.mark = Mark::at(delta.end());
+        }
+
+        let bytes = &mut self.bytes;
+        bytes.push(RECORD_TAG);
+        write_usize(bytes, fields.len());
+        for ((key, value), symbol) in fields.into_iter().zip(values) {
+            write_usize(bytes, key);
+            match value {
+                Value::Null => bytes.push(NULL_TAG),
+                Value::Bool(false) => bytes.push(FALSE_TAG),
+                Value::Bool(true) => bytes.push(TRUE_TAG),
+                Value::Int(int) => {
+                    bytes.push(INT_TAG);
+                    varint::write_u64(bytes, varint::zigzag(*int));
+                }
+                Value::Float(float) => {
+                    bytes.push(FLOAT_TAG);
+                    bytes.extend_from_slice(&float.to_le_bytes());
+                }
+                Value::Symbol(_) => {
+                    bytes.push(SYMBOL_TAG);
+                    write_usize(bytes, symbol.unwrap());
+                }
+                Value::String(string) => {
+                    bytes.push(STRING_TAG);
+                    write_str(bytes, string);
+                }
+            }
+        }
+    }
+
+    /// The bytes that have been encoded so far.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Takes the bytes that have been encoded so far, such as to flush them to a file.
+    /// The writer continues the same stream, so strings that were already written
+    /// aren't written again.
+    pub fn take_bytes(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.bytes)
+    }
+
+    /// Finishes writing, and returns the encoded bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Decodes a stream of records, interning the strings of the stream into a table. The
+/// table doesn't need to match the writer's table, as the identifiers of the stream are
+/// translated to the indexes of the reader's table.
+pub struct RecordReader<'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    // The index in the table of each identifier of the stream.
+    indexes: Vec<SymbolIndex>,
+}
+
+impl<'strings> RecordReader<'strings> {
+    /// Creates a reader that interns the strings of the stream into the table.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        RecordReader {
+            symbol_table,
+            indexes: Vec::new(),
+        }
+    }
+
+    /// Reads chunks from the front of the bytes until a record is decoded, and advances
+    /// the bytes past them. Returns `None` once the bytes are empty.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn read(&mut self, bytes: &mut &[u8]) -> Result<Option<Record<'strings>>, RecordError> {
+        while let Some((&tag, rest)) = bytes.split_first() {
+            *bytes = rest;
+            match tag {
+                STRINGS_TAG => self.read_strings(bytes)?,
+                RECORD_TAG => return self.read_record(bytes).map(Some),
+                _ => return Err(RecordError::Malformed),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads every record of the bytes.
+    pub fn read_all(&mut self, mut bytes: &[u8]) -> Result<Vec<Record<'strings>>, RecordError> {
+        let mut records = Vec::new();
+        while let Some(record) = self.read(&mut bytes)? {
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    fn read_strings(&mut self, bytes: &mut &[u8]) -> Result<(), RecordError> {
+        let start = read_usize(bytes)?;
+        if start != self.indexes.len() {
+            return Err(RecordError::Malformed);
+        }
+        let count = read_usize(bytes)?;
+        for _ in 0..count {
+            let string = read_str(bytes)?;
+            self.indexes.push(self.symbol_table.get(string).index());
+        }
+        Ok(())
+    }
+
+    fn read_record(&mut self, bytes: &mut &[u8]) -> Result<Record<'strings>, RecordError> {
+        let count = read_usize(bytes)?;
+        // Don't trust the count for the allocation, as each field needs at least 2 bytes.
+        let mut record = Vec::with_capacity(count.min(bytes.len() / 2));
+        for _ in 0..count {
+            let key = self.read_symbol(bytes)?;
+            let (&tag, rest) = bytes.split_first().ok_or(RecordError::Truncated)?;
+            *bytes = rest;
+            let value = match tag {
+                NULL_TAG => Value::Null,
+                FALSE_TAG => Value::Bool(false),
+                TRUE_TAG => Value::Bool(true),
+                INT_TAG => Value::Int(varint::unzigzag(varint::read_u64(bytes)?)),
+                FLOAT_TAG => {
+                    let float = take(bytes, 8)?;
+                    Value::Float(f64::from_le_bytes(float.try_into().unwrap()))
+                }
+                SYMBOL_TAG => Value::Symbol(self.read_symbol(bytes)?),
+                STRING_TAG => Value::String(read_str(bytes)?.to_owned()),
+                _ => return Err(RecordError::Malformed),
+            };
+            record.push((key, value));
+        }
+        Ok(record)
+    }
+
+    fn read_symbol(&self, bytes: &mut &[u8]) -> Result<Symbol<'strings>, RecordError> {
+        let id = varint::read_u64(bytes)?;
+        let index = usize::try_from(id)
+            .ok()
+            .and_then(|id| self.indexes.get(id))
+            .ok_or(RecordError::UnknownId { id })?;
+        Ok(Symbol::new(self.symbol_table, *index))
+    }
+}
+
+fn write_usize(bytes: &mut Vec<u8>, value: usize) {
+    varint::write_u64(bytes, value as u64);
+}
+
+fn write_str(bytes: &mut Vec<u8>, string: &str) {
+    write_usize(bytes, string.len());
+    bytes.extend_from_slice(string.as_bytes());
+}
+
+fn read_usize(bytes: &mut &[u8]) -> Result<usize, RecordError> {
+    usize::try_from(varint::read_u64(bytes)?).map_err(|_| RecordError::Malformed)
+}
+
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], RecordError> {
+    if len > bytes.len() {
+        return Err(RecordError::Truncated);
+    }
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Ok(taken)
+}
+
+fn read_str<'a>(bytes: &mut &'a [u8]) -> Result<&'a str, RecordError> {
+    let len = read_usize(bytes)?;
+    std::str::from_utf8(take(bytes, len)?).map_err(|_| RecordError::Malformed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let symbol_table = SymbolTable::new();
+        let value = symbol_table.get("value");
+        let mut writer = RecordWriter::new(&symbol_table);
+        writer.write(&[]);
+        writer.write(&[
+            (value, Value::Null),
+            (value, Value::Bool(true)),
+            (value, Value::Bool(false)),
+            (value, Value::Int(i64::MIN)),
+            (value, Value::Int(-1)),
+            (value, Value::Float(0.5)),
+            (
+                value,
+                Value::Symbol(symbol_table.get("a\nb").slice(2..3).unwrap()),
+            ),
+        ]);
+        let first = writer.take_bytes();
+        writer.write(&[(symbol_table.get("late"), Value::Symbol(value))]);
+        let second = writer.take_bytes();
+
+        // The reader's table has other strings at the same indexes.
+        let other_table = SymbolTable::new();
+        other_table.get("unrelated");
+        let mut reader = RecordReader::new(&other_table);
+        let mut records = reader.read_all(&first).unwrap();
+        records.extend(reader.read_all(&second).unwrap());
+        assert!(records[0].is_empty());
+        let values: Vec<_> = records[1].iter().map(|(_, value)| value.clone()).collect();
+        assert_eq!(
+            values,
+            [
+                Value::Null,
+                Value::Bool(true),
+                Value::Bool(false),
+                Value::Int(i64::MIN),
+                Value::Int(-1),
+                Value::Float(0.5),
+                Value::Symbol(other_table.get("b")),
+            ]
+        );
+        assert_eq!(
+            records[2],
+            [(
+                other_table.get("late"),
+                Value::Symbol(other_table.get("value"))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_invalid_streams() {
+        let symbol_table = SymbolTable::new();
+        let mut reader = RecordReader::new(&symbol_table);
+        assert_eq!(reader.read_all(&[9]), Err(RecordError::Malformed));
+        assert_eq!(
+            reader.read_all(&[2, 1, 0]),
+            Err(RecordError::UnknownId { id: 0 })
+        );
+        assert_eq!(
+            reader.read_all(&[1, 0, 1, 5, b'a']),
+            Err(RecordError::Truncated)
+        );
+        assert_eq!(reader.read_all(&[1, 3, 0]), Err(RecordError::Malformed));
+        assert_eq!(
+            reader.read_all(&[1, 0, 1, 1, b'a', 2, 1, 0, 7]),
+            Err(RecordError::Malformed)
+        );
+    }
+}
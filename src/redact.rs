@@ -0,0 +1,181 @@
+//! Redacted namespaces, where sensitive strings are only stored as a salted hash and a
+//! redacted display form.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{SymbolIndex, SymbolTable};
+
+/// The redacted namespaces of a table, the index for each salted hash, and the display
+/// forms that are in use.
+#[derive(Default)]
+pub(crate) struct Redaction {
+    namespaces: Vec<(String, u64)>,
+    hashes: FxHashMap<u128, SymbolIndex>,
+    displays: FxHashSet<String>,
+}
+
+impl Redaction {
+    /// Remaps the index of every redacted string.
+    pub(crate) fn remap(&mut self, remap: impl Fn(SymbolIndex) -> SymbolIndex) {
+        for index in self.hashes.values_mut() {
+            *index = remap(*index);
+        }
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Redacts every string that starts with a prefix, such as `"user:"`, when it's
+    /// interned from now on. The table never stores the raw string, only a salted hash of
+    /// it, which is used for looking it up, and a redacted display form, which is the
+    /// prefix followed by the last 4 hex digits of the hash, like `"user:…a1b2"`. When
+    /// those digits are already used by another string, more digits are added until the
+    /// display form is unique, so every string of the table stays distinct.
+    ///
+    /// Equal strings are still the same symbol, and unequal strings are different
+    /// symbols. The salt should be kept
+    /// secret, so that the hashes can't be checked against a list of guesses. Strings
+    /// that were interned before the namespace was redacted are kept as is.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.redact_namespace("user:", 0x5eed);
+    ///
+    /// let alice = symbol_table.get("user:alice@example.com");
+    /// assert_eq!(symbol_table.get("user:alice@example.com"), alice);
+    /// assert_ne!(symbol_table.get("user:bob@example.com").index(), alice.index());
+    /// assert!(symbol_table.has("user:alice@example.com"));
+    ///
+    /// assert!(alice.str().starts_with("user:…"));
+    /// assert!(!symbol_table.iter().any(|string| string.contains("alice")));
+    /// ```
+    pub fn redact_namespace(&self, prefix: &str, salt: u64) {
+        self.redaction
+            .borrow_mut()
+            .namespaces
+            .push((prefix.to_owned(), salt));
+    }
+
+    /// Returns the salted hash of a string and the prefix of its namespace, if it's in a
+    /// redacted namespace.
+    pub(crate) fn redact(&self, string: &str) -> Option<(u128, String)> {
+        let redaction = self.redaction.borrow();
+        let (prefix, salt) = redaction
+            .namespaces
+            .iter()
+            .find(|(prefix, _)| string.starts_with(prefix.as_str()))?;
+        Some((salted_hash(*salt, prefix, string), prefix.clone()))
+    }
+
+    /// Looks up a string that was interned in a redacted namespace.
+    pub(crate) fn lookup_redacted(&self, string: &str) -> Option<SymbolIndex> {
+        if self.redaction.borrow().namespaces.is_empty() {
+            return None;
+        }
+        let (hash, _) = self.redact(string)?;
+        self.redaction.borrow().hashes.get(&hash).copied()
+    }
+
+    /// Adds the display form of a redacted string, which can only be looked up by its
+    /// hash. The display form uses the fewest hex digits of the hash, from 4 up, that
+    /// don't collide with another string of the table.
+    pub(crate) fn push_redacted(&self, hash: u128, prefix: &str) -> SymbolIndex {
+        let redacted = self.redacted_display(hash, prefix, |_| false);
+        let index = self.push_unindexed(redacted.clone());
+        let mut redaction = self.redaction.borrow_mut();
+        redaction.hashes.insert(hash, index);
+        redaction.displays.insert(redacted);
+        index
+    }
+
+    /// The display form of a redacted string, with the fewest hex digits of the hash
+    /// that don't collide with another string of the table, or with a string that
+    /// `is_taken`, such as one of a fork.
+    pub(crate) fn redacted_display(
+        &self,
+        hash: u128,
+        prefix: &str,
+        is_taken: impl Fn(&str) -> bool,
+    ) -> String {
+        let hex = format!("{hash:032x}");
+        (4..=hex.len())
+            .map(|digits| format!("{prefix}…{}", &hex[hex.len() - digits..]))
+            .find(|display| !self.is_display_taken(display) && !is_taken(display))
+            .unwrap_or_else(|| format!("{prefix}…{hex}…{}", self.len()))
+    }
+
+    /// Returns true if a display form is already a string of the table.
+    fn is_display_taken(&self, display: &str) -> bool {
+        self.redaction.borrow().displays.contains(display)
+            || self.lookup_sorted(display).is_some()
+            || self.indexes.get(display).is_some()
+            || self.lookup_large(display).is_some()
+    }
+}
+
+/// Hashes the string twice with SipHash to get 128 bits, so that collisions between
+/// different strings are vanishingly unlikely.
+fn salted_hash(salt: u64, prefix: &str, string: &str) -> u128 {
+    let half = |lane: u8| {
+        let mut hasher = DefaultHasher::new();
+        (salt, lane, prefix, string).hash(&mut hasher);
+        hasher.finish() as u128
+    };
+    half(0) << 64 | half(1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_redaction() {
+        let symbol_table = SymbolTable::new();
+        let before = symbol_table.get("user:kept");
+        symbol_table.redact_namespace("user:", 1);
+        symbol_table.redact_namespace("email:", 2);
+        let user = symbol_table.get("user:secret");
+        let email = symbol_table.get("email:secret");
+        let public = symbol_table.get("secret");
+
+        assert_eq!(before, "user:kept");
+        assert_eq!(symbol_table.maybe_get("user:kept"), Some(before));
+        assert_eq!(public, "secret");
+        assert_eq!(user.str().len(), "user:…".len() + 4);
+        assert!(email.str().starts_with("email:…"));
+        assert!(user.is("user:secret"));
+        assert!(!user.is("user:other"));
+        assert_eq!(symbol_table.maybe_get("user:other"), None);
+
+        // The display form is looked up as a redacted string, not as itself.
+        let display = user.str().to_owned();
+        assert_ne!(symbol_table.get(display).index(), user.index());
+
+        // Different salts produce different hashes for the same string.
+        assert_ne!(
+            salted_hash(1, "user:", "user:secret"),
+            salted_hash(3, "user:", "user:secret")
+        );
+    }
+
+    #[test]
+    fn test_display_forms_are_unique() {
+        let symbol_table = SymbolTable::new();
+        symbol_table.redact_namespace("user:", 7);
+        for i in 0..5000 {
+            symbol_table.get(format!("user:{i}"));
+        }
+        assert!(symbol_table
+            .iter()
+            .any(|string| string.len() > "user:…".len() + 4));
+
+        let mut bytes = Vec::new();
+        symbol_table.write_to(&mut bytes).unwrap();
+        let loaded = SymbolTable::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.to_vec(), symbol_table.to_vec());
+        assert_eq!(symbol_table.freeze().len(), 5000);
+    }
+}
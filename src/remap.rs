@@ -0,0 +1,137 @@
+//! Building new tables from existing ones, with a mapping from the old indexes to the
+//! new ones.
+
+use std::borrow::Cow;
+
+use crate::{SymbolIndex, SymbolTable};
+
+/// Maps the indexes of a [`SymbolTable`] to the indexes of a table that was built from
+/// it, such as by [`SymbolTable::map`](struct.SymbolTable.html#method.map). Several old
+/// indexes can map to the same new index when strings were merged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexRemapping {
+    new_indexes: Vec<SymbolIndex>,
+}
+
+impl IndexRemapping {
+    pub(crate) fn from_new_indexes(new_indexes: Vec<SymbolIndex>) -> Self {
+        IndexRemapping { new_indexes }
+    }
+
+    /// Gets the new index for an old index, or `None` if it's out of range of the old
+    /// table.
+    pub fn get(&self, old_index: SymbolIndex) -> Option<SymbolIndex> {
+        self.new_indexes.get(old_index).copied()
+    }
+
+    /// Remaps a list of old indexes in place, such as the symbols stored in a record.
+    /// Panics if an index is out of range of the old table.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let record = [symbol_table.get("b").index(), symbol_table.get("a").index()];
+    /// let (sorted, remapping) = symbol_table.map(|string| string.into());
+    ///
+    /// let mut new_record = record;
+    /// remapping.remap(&mut new_record);
+    /// assert_eq!(new_record, record);
+    /// assert_eq!(&sorted[new_record[0]], "b");
+    /// ```
+    pub fn remap(&self, indexes: &mut [SymbolIndex]) {
+        for index in indexes {
+            *index = self.new_indexes[*index];
+        }
+    }
+
+    /// The number of indexes in the old table.
+    pub fn len(&self) -> usize {
+        self.new_indexes.len()
+    }
+
+    /// Returns true if the old table was empty.
+    pub fn is_empty(&self) -> bool {
+        self.new_indexes.is_empty()
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Applies a transformation to every string, in order, and builds a new table of the
+    /// deduplicated results, along with the remapping from the old indexes to the new
+    /// ones. This is useful for anonymizing the identifiers in a dump of the table. The
+    /// new table is in the next [`generation`](struct.SymbolTable.html#method.generation),
+    /// so that identifiers of this table are stale in it.
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let user = symbol_table.get("user_alice");
+    /// let other = symbol_table.get("user_bob");
+    /// let render = symbol_table.get("render");
+    ///
+    /// let (anonymized, remapping) = symbol_table.map(|string| {
+    ///     if string.starts_with("user_") {
+    ///         Cow::Borrowed("user_<redacted>")
+    ///     } else {
+    ///         Cow::Borrowed(string)
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(anonymized.len(), 2);
+    /// assert_eq!(remapping.get(user.index()), remapping.get(other.index()));
+    /// assert_eq!(&anonymized[remapping.get(render.index()).unwrap()], "render");
+    /// ```
+    pub fn map<'new, F>(&self, mut f: F) -> (SymbolTable<'new>, IndexRemapping)
+    where
+        F: FnMut(&str) -> Cow<str>,
+    {
+        let symbol_table = SymbolTable {
+            generation: self.generation.wrapping_add(1),
+            ..SymbolTable::new()
+        };
+        let new_indexes = self
+            .iter()
+            .map(|string| match f(string) {
+                Cow::Borrowed(string) => symbol_table.intern_index(string),
+                Cow::Owned(string) => symbol_table.intern_index(string),
+            })
+            .collect();
+        (symbol_table, IndexRemapping { new_indexes })
+    }
+
+    /// Interns a string, and returns only its index, which doesn't need to borrow the
+    /// table for its lifetime.
+    pub(crate) fn intern_index<T: Into<String> + AsRef<str>>(&self, string: T) -> SymbolIndex {
+        match self.lookup_index(string.as_ref()) {
+            Some(index) => index,
+            None => self.push_new(string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_map_remapping() {
+        let symbol_table = SymbolTable::new();
+        for string in ["A", "a", "B", "b", "c"] {
+            symbol_table.get(string);
+        }
+        let (lowercase, remapping) = symbol_table.map(|string| Cow::Owned(string.to_lowercase()));
+        assert_eq!(lowercase.iter().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(remapping.len(), 5);
+        assert_eq!(
+            (0..6).map(|index| remapping.get(index)).collect::<Vec<_>>(),
+            [Some(0), Some(0), Some(1), Some(1), Some(2), None]
+        );
+
+        let (empty, remapping) = SymbolTable::new().map(|string| string.into());
+        assert!(empty.is_empty());
+        assert!(remapping.is_empty());
+    }
+}
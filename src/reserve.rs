@@ -0,0 +1,115 @@
+//! Pinning strings to specific indexes, for protocols that hard-code their identifiers.
+
+use std::fmt;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// The error for a string that can't be reserved at an index, see
+/// [`SymbolTable::reserve_id`](struct.SymbolTable.html#method.reserve_id).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReserveError {
+    /// A different string is already at the index.
+    Occupied {
+        /// The index.
+        index: SymbolIndex,
+        /// The string at the index.
+        existing: String,
+    },
+    /// The string is already interned at a different index.
+    AlreadyInterned {
+        /// The index of the string.
+        index: SymbolIndex,
+    },
+    /// The index is past the end of the table, which would leave a gap in the indexes.
+    Gap {
+        /// The number of strings in the table, which is the next index.
+        len: usize,
+    },
+}
+
+impl fmt::Display for ReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReserveError::Occupied { index, existing } => {
+                write!(f, "The index {index} is already used by {existing:?}.")
+            }
+            ReserveError::AlreadyInterned { index } => {
+                write!(f, "The string is already interned at index {index}.")
+            }
+            ReserveError::Gap { len } => write!(
+                f,
+                "The index is past the end of the table, which has {len} strings."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReserveError {}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Interns a string at a specific index, or checks that it's already there, so that a
+    /// protocol that hard-codes its identifiers can enforce them when the table is set
+    /// up. The indexes of a table have no gaps, so reservations need to be made in
+    /// order, starting at the end of the table.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{ReserveError, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// for (index, method) in ["ping", "subscribe", "publish"].iter().enumerate() {
+    ///     symbol_table.reserve_id(index, method).unwrap();
+    /// }
+    /// assert_eq!(symbol_table.get("subscribe").index(), 1);
+    ///
+    /// // Reserving again is fine, as long as it agrees.
+    /// assert!(symbol_table.reserve_id(1, "subscribe").is_ok());
+    /// assert_eq!(
+    ///     symbol_table.reserve_id(1, "unsubscribe"),
+    ///     Err(ReserveError::Occupied { index: 1, existing: "subscribe".into() })
+    /// );
+    /// assert_eq!(
+    ///     symbol_table.reserve_id(5, "unsubscribe"),
+    ///     Err(ReserveError::Gap { len: 3 })
+    /// );
+    /// ```
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn reserve_id(
+        &'strings self,
+        index: SymbolIndex,
+        string: &str,
+    ) -> Result<Symbol<'strings>, ReserveError> {
+        if let Some(existing) = self.lookup_index(string) {
+            if existing != index {
+                return Err(ReserveError::AlreadyInterned { index: existing });
+            }
+            return Ok(Symbol::new(self, index));
+        }
+        if index < self.len() {
+            return Err(ReserveError::Occupied {
+                index,
+                existing: self[index].to_owned(),
+            });
+        }
+        if index > self.len() {
+            return Err(ReserveError::Gap { len: self.len() });
+        }
+        Ok(self.get(string))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_reserve_conflicts() {
+        let symbol_table = SymbolTable::new();
+        symbol_table.get("a");
+        assert_eq!(
+            symbol_table.reserve_id(1, "a"),
+            Err(ReserveError::AlreadyInterned { index: 0 })
+        );
+        assert_eq!(symbol_table.reserve_id(1, "b").unwrap(), "b");
+        assert_eq!(symbol_table.len(), 2);
+    }
+}
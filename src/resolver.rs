@@ -0,0 +1,474 @@
+//! A two-phase alternative to the [`SymbolTable`], where strings are interned by a
+//! mutable [`SymbolTableBuilder`], which then builds an immutable [`SymbolResolver`].
+
+use std::hash::{Hash, Hasher};
+
+use fxhash::FxHasher;
+
+use crate::{SymbolIndex, SymbolTable};
+
+/// Marks an empty slot in the hash table.
+const EMPTY_SLOT: u32 = u32::MAX;
+
+/// The strings are stored in a single buffer, and are looked up through an open
+/// addressing hash table of indexes, so each string is only stored once.
+#[derive(Clone, Default)]
+struct Strings {
+    buffer: String,
+    // The end offset of each string in the buffer.
+    ends: Vec<usize>,
+    // Indexes of strings, or EMPTY_SLOT. The length is always a power of two.
+    slots: Vec<u32>,
+}
+
+impl Strings {
+    fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        let end = *self.ends.get(index)?;
+        let start = match index {
+            0 => 0,
+            _ => self.ends[index - 1],
+        };
+        Some(&self.buffer[start..end])
+    }
+
+    /// Finds either the slot containing the string, or the empty slot where it belongs.
+    fn find_slot(&self, string: &str) -> usize {
+        let mask = self.slots.len() - 1;
+        let mut slot = fxhash::hash64(string) as usize & mask;
+        loop {
+            match self.slots[slot] {
+                EMPTY_SLOT => return slot,
+                index if self.resolve(index as usize) == Some(string) => return slot,
+                _ => slot = (slot + 1) & mask,
+            }
+        }
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        match self.slots[self.find_slot(string)] {
+            EMPTY_SLOT => None,
+            index => Some(index as SymbolIndex),
+        }
+    }
+
+    fn insert(&mut self, string: &str) -> SymbolIndex {
+        if let Some(index) = self.lookup(string) {
+            return index;
+        }
+        let index = self.len();
+        assert!(
+            index < EMPTY_SLOT as usize,
+            "A SymbolTableBuilder can hold at most u32::MAX - 1 strings."
+        );
+        // Keep the load factor at or below one half.
+        if (index + 1) * 2 > self.slots.len() {
+            self.grow();
+        }
+        let slot = self.find_slot(string);
+        self.buffer.push_str(string);
+        self.ends.push(self.buffer.len());
+        self.slots[slot] = index as u32;
+        index
+    }
+
+    fn grow(&mut self) {
+        let capacity = (self.slots.len() * 2).max(16);
+        self.slots = vec![EMPTY_SLOT; capacity];
+        for index in 0..self.len() {
+            let string = self.resolve(index).expect("The index is in range.");
+            let slot = self.find_slot(string);
+            self.slots[slot] = index as u32;
+        }
+    }
+}
+
+/// The mutable, single-threaded half of a two-phase symbol table. Strings are interned
+/// through `&mut self`, so there is no interior mutability overhead. Once all of the
+/// strings are interned, [`build`](SymbolTableBuilder::build) produces an immutable
+/// [`SymbolResolver`].
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTableBuilder;
+///
+/// let mut builder = SymbolTableBuilder::new();
+/// let hello = builder.insert("hello");
+/// let world = builder.insert("world");
+/// assert_eq!(builder.insert("hello"), hello);
+///
+/// let resolver = builder.build();
+/// assert_eq!(resolver.resolve(hello), Some("hello"));
+/// assert_eq!(resolver.lookup("world"), Some(world));
+/// ```
+#[derive(Clone, Default)]
+pub struct SymbolTableBuilder {
+    strings: Strings,
+}
+
+impl SymbolTableBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns a string, and returns its index.
+    pub fn insert(&mut self, string: &str) -> SymbolIndex {
+        self.strings.insert(string)
+    }
+
+    /// Looks up the index of a string if it's already interned.
+    pub fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        self.strings.lookup(string)
+    }
+
+    /// Resolves the string for an index.
+    pub fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        self.strings.resolve(index)
+    }
+
+    /// Get the amount of strings in the builder.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns true if no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Finishes the build, producing an immutable resolver. The indexes of the strings
+    /// are preserved.
+    pub fn build(self) -> SymbolResolver {
+        let mut strings = self.strings;
+        strings.buffer.shrink_to_fit();
+        strings.ends.shrink_to_fit();
+        SymbolResolver {
+            strings,
+            perfect_hash: None,
+        }
+    }
+}
+
+impl<'a> Extend<&'a str> for SymbolTableBuilder {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        for string in iter {
+            self.insert(string);
+        }
+    }
+}
+
+/// The immutable half of a two-phase symbol table, produced by
+/// [`SymbolTableBuilder::build`] or [`SymbolTable::freeze`]. All of the strings are
+/// stored in a single buffer. Resolving and looking up strings never allocates, and
+/// the resolver is `Send` and `Sync`, so it can be shared across threads.
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTableBuilder;
+///
+/// let mut builder = SymbolTableBuilder::new();
+/// builder.extend(["a", "b", "c"]);
+/// let resolver = builder.build();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| assert_eq!(resolver.lookup("b"), Some(1)));
+///     scope.spawn(|| assert_eq!(resolver.resolve(2), Some("c")));
+/// });
+/// ```
+#[derive(Clone, Default)]
+pub struct SymbolResolver {
+    strings: Strings,
+    perfect_hash: Option<PerfectHash>,
+}
+
+impl SymbolResolver {
+    /// Replaces the hash table used for looking up strings with a perfect hash, so
+    /// that every lookup is a single probe with no collisions. Building the perfect
+    /// hash is slower than building a regular hash table, so this is meant for static
+    /// tables, such as keywords or opcodes, that are queried many times. If no perfect
+    /// hash is found after a bounded number of attempts, which can happen for strings
+    /// that were crafted to collide, the regular hash table is kept.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTableBuilder;
+    ///
+    /// let mut builder = SymbolTableBuilder::new();
+    /// builder.extend(["add", "sub", "mul", "div"]);
+    /// let opcodes = builder.build().with_perfect_hash();
+    ///
+    /// assert!(opcodes.has_perfect_hash());
+    /// assert_eq!(opcodes.lookup("mul"), Some(2));
+    /// assert_eq!(opcodes.lookup("mod"), None);
+    /// ```
+    pub fn with_perfect_hash(mut self) -> SymbolResolver {
+        if let Some(perfect_hash) = PerfectHash::new(&self.strings) {
+            self.strings.slots = Vec::new();
+            self.perfect_hash = Some(perfect_hash);
+        }
+        self
+    }
+
+    /// Returns true if lookups use a perfect hash. See
+    /// [`with_perfect_hash`](SymbolResolver::with_perfect_hash).
+    pub fn has_perfect_hash(&self) -> bool {
+        self.perfect_hash.is_some()
+    }
+
+    /// Resolves the string for an index.
+    pub fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        self.strings.resolve(index)
+    }
+
+    /// Looks up the index of a string.
+    pub fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        match &self.perfect_hash {
+            Some(perfect_hash) => {
+                let index = perfect_hash.index(string)?;
+                (self.strings.resolve(index)? == string).then_some(index)
+            }
+            None => self.strings.lookup(string),
+        }
+    }
+
+    /// Check if the resolver has a string.
+    pub fn has(&self, string: &str) -> bool {
+        self.lookup(string).is_some()
+    }
+
+    /// Get the amount of strings in the resolver.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Returns true if there are no strings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate through all of the strings in index order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        (0..self.len()).filter_map(|index| self.resolve(index))
+    }
+}
+
+/// A perfect hash built with the "hash and displace" approach of CHD and
+/// PTHash. Keys are hashed into buckets, and each bucket is assigned a pilot value that
+/// displaces all of its keys into free slots. A lookup then hashes the key, reads the
+/// pilot of its bucket, and probes exactly one slot.
+#[derive(Clone)]
+struct PerfectHash {
+    seed: u64,
+    pilots: Vec<u32>,
+    // Indexes of strings, or EMPTY_SLOT.
+    slots: Vec<u32>,
+}
+
+impl PerfectHash {
+    /// The average number of keys per bucket.
+    const BUCKET_SIZE: usize = 4;
+    /// The ratio of keys to slots.
+    const LOAD_FACTOR: f64 = 0.9;
+    /// How many pilots to try for a bucket before starting over with a new seed.
+    const MAX_PILOT: u32 = 1 << 16;
+    /// How many seeds to try before giving up on a perfect hash.
+    const MAX_SEED: u64 = 64;
+
+    fn new(strings: &Strings) -> Option<PerfectHash> {
+        let bucket_count = strings.len() / Self::BUCKET_SIZE + 1;
+        let slot_count = ((strings.len() as f64 / Self::LOAD_FACTOR) as usize).max(1);
+        (0..Self::MAX_SEED).find_map(|seed| Self::try_new(strings, seed, bucket_count, slot_count))
+    }
+
+    fn try_new(
+        strings: &Strings,
+        seed: u64,
+        bucket_count: usize,
+        slot_count: usize,
+    ) -> Option<PerfectHash> {
+        let mut buckets: Vec<Vec<(SymbolIndex, u64)>> = vec![Vec::new(); bucket_count];
+        for index in 0..strings.len() {
+            let hash = Self::hash(seed, strings.resolve(index)?);
+            buckets[hash as usize % bucket_count].push((index, hash));
+        }
+        // Place the largest buckets first, while there are the most free slots.
+        let mut bucket_order: Vec<usize> = (0..bucket_count).collect();
+        bucket_order.sort_by_key(|&bucket| std::cmp::Reverse(buckets[bucket].len()));
+
+        let mut pilots = vec![0; bucket_count];
+        let mut slots = vec![EMPTY_SLOT; slot_count];
+        let mut positions = Vec::new();
+        for bucket in bucket_order {
+            let keys = &buckets[bucket];
+            if keys.is_empty() {
+                break;
+            }
+            let pilot = (0..Self::MAX_PILOT).find(|&pilot| {
+                positions.clear();
+                for &(_, hash) in keys {
+                    let position = Self::position(hash, pilot, slot_count);
+                    if slots[position] != EMPTY_SLOT || positions.contains(&position) {
+                        return false;
+                    }
+                    positions.push(position);
+                }
+                true
+            })?;
+            pilots[bucket] = pilot;
+            for (&(index, _), &position) in keys.iter().zip(&positions) {
+                slots[position] = index as u32;
+            }
+        }
+        Some(PerfectHash {
+            seed,
+            pilots,
+            slots,
+        })
+    }
+
+    /// Returns the only index that the string could have.
+    fn index(&self, string: &str) -> Option<SymbolIndex> {
+        let hash = Self::hash(self.seed, string);
+        let pilot = self.pilots[hash as usize % self.pilots.len()];
+        match self.slots[Self::position(hash, pilot, self.slots.len())] {
+            EMPTY_SLOT => None,
+            index => Some(index as SymbolIndex),
+        }
+    }
+
+    /// Hashes the seed along with the string, so that strings which collide for one
+    /// seed are unlikely to collide for the next.
+    fn hash(seed: u64, string: &str) -> u64 {
+        let mut hasher = FxHasher::default();
+        (seed, string).hash(&mut hasher);
+        mix(hasher.finish())
+    }
+
+    fn position(hash: u64, pilot: u32, slot_count: usize) -> usize {
+        (mix(hash ^ mix(pilot as u64 + 1)) % slot_count as u64) as usize
+    }
+}
+
+/// The splitmix64 finalizer, which spreads the bits of the fxhash output.
+fn mix(mut value: u64) -> u64 {
+    value ^= value >> 30;
+    value = value.wrapping_mul(0xbf58476d1ce4e5b9);
+    value ^= value >> 27;
+    value = value.wrapping_mul(0x94d049bb133111eb);
+    value ^ (value >> 31)
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Freezes the current contents of the table into an immutable [`SymbolResolver`],
+    /// preserving the indexes.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let hello = symbol_table.get("hello");
+    ///
+    /// let resolver = symbol_table.freeze();
+    /// assert_eq!(resolver.lookup("hello"), Some(hello.index()));
+    /// ```
+    pub fn freeze(&self) -> SymbolResolver {
+        let mut builder = SymbolTableBuilder::new();
+        builder.extend(self.iter());
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolver_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SymbolResolver>();
+    }
+
+    #[test]
+    fn test_many_strings() {
+        let mut builder = SymbolTableBuilder::new();
+        for i in 0..1000 {
+            assert_eq!(builder.insert(&i.to_string()), i);
+        }
+        builder.insert("");
+        assert_eq!(builder.insert("500"), 500);
+        let resolver = builder.build();
+        assert_eq!(resolver.len(), 1001);
+        for i in 0..1000 {
+            assert_eq!(resolver.lookup(&i.to_string()), Some(i));
+            assert_eq!(resolver.resolve(i), Some(i.to_string().as_str()));
+        }
+        assert_eq!(resolver.lookup(""), Some(1000));
+        assert_eq!(resolver.resolve(1000), Some(""));
+        assert_eq!(resolver.resolve(1001), None);
+        assert_eq!(resolver.lookup("missing"), None);
+    }
+
+    #[test]
+    fn test_empty_resolver() {
+        let resolver = SymbolTableBuilder::new().build();
+        assert!(resolver.is_empty());
+        assert_eq!(resolver.lookup(""), None);
+        assert_eq!(resolver.iter().count(), 0);
+
+        let resolver = resolver.with_perfect_hash();
+        assert_eq!(resolver.lookup(""), None);
+    }
+
+    #[test]
+    fn test_perfect_hash() {
+        let mut builder = SymbolTableBuilder::new();
+        for i in 0..10_000 {
+            builder.insert(&format!("key{i}"));
+        }
+        let resolver = builder.build().with_perfect_hash();
+        assert!(
+            resolver.strings.slots.is_empty(),
+            "The hash table is dropped."
+        );
+        for i in 0..10_000 {
+            assert_eq!(resolver.lookup(&format!("key{i}")), Some(i));
+            assert_eq!(resolver.lookup(&format!("missing{i}")), None);
+        }
+
+        // Strings with the same fxhash still get a perfect hash with another seed.
+        let [a, b] = fxhash_collision();
+        assert_eq!(fxhash::hash64(&a), fxhash::hash64(&b));
+        let mut builder = SymbolTableBuilder::new();
+        builder.extend([a.as_str(), b.as_str()]);
+        let resolver = builder.build().with_perfect_hash();
+        assert!(resolver.has_perfect_hash());
+        assert_eq!(resolver.lookup(&b), Some(1));
+    }
+
+    /// Finds two ASCII strings of two words each with the same fxhash, by choosing the
+    /// second words to cancel out the difference of the states after the first words.
+    fn fxhash_collision() -> [String; 2] {
+        const K: u64 = 0x517cc1b727220a95;
+        let state = |word: [u8; 8]| u64::from_ne_bytes(word).wrapping_mul(K).rotate_left(5);
+        let first = *b"aaaaaaaa";
+        for i in 0u32.. {
+            let mut other = first;
+            for (byte, digit) in other.iter_mut().zip(format!("{i:08}").bytes()) {
+                *byte = digit;
+            }
+            let difference = (state(first) ^ state(other)).to_ne_bytes();
+            if difference.iter().all(|byte| byte.is_ascii()) {
+                let second = [b'a'; 8];
+                let other_second: Vec<u8> =
+                    second.iter().zip(difference).map(|(a, b)| a ^ b).collect();
+                let string = |words: [&[u8]; 2]| String::from_utf8(words.concat()).unwrap();
+                return [string([&first, &second]), string([&other, &other_second])];
+            }
+        }
+        unreachable!()
+    }
+}
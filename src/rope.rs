@@ -0,0 +1,470 @@
+//! Interning of large documents as ropes of chunks, so that each edit of a document is
+//! a new version that shares the unchanged chunks with the previous one, and slices of
+//! a version can be remapped to later ones.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// The length in bytes that documents are split into chunks at, unless it's changed
+/// with [`RopeTable::with_chunk_len`].
+const DEFAULT_CHUNK_LEN: usize = 1024;
+
+/// The identifier of a version of a document in a [`RopeTable`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RopeId(u32);
+
+impl RopeId {
+    /// The identifier as a `u32`.
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    /// The identifier as an index, for indexing into arrays.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Display for RopeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A version of a document, as its chunks along with the end offset of each chunk.
+struct Rope {
+    chunks: Vec<SymbolIndex>,
+    ends: Vec<usize>,
+    edit: Option<Edit>,
+}
+
+/// The edit that made a version from its parent, which replaced a byte range of the
+/// parent with a string of `len` bytes.
+struct Edit {
+    parent: RopeId,
+    range: Range<usize>,
+    len: usize,
+}
+
+impl Rope {
+    fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    fn start(&self, chunk: usize) -> usize {
+        match chunk {
+            0 => 0,
+            _ => self.ends[chunk - 1],
+        }
+    }
+}
+
+/// Interns versions of large documents, such as the contents of a file in an editor at
+/// each revision. A document is split into chunks, which are interned into the symbol
+/// table, and an edit makes a new version that only interns the chunks that the edit
+/// touched. Since chunks are interned, versions share every chunk that they have in
+/// common, and a slice within a chunk is a sliced [`Symbol`] that stays valid however
+/// the document is edited later.
+///
+/// ```
+/// use gregtatum_symbol_table::{RopeTable, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// let mut ropes = RopeTable::with_chunk_len(&symbol_table, 8);
+/// let v1 = ropes.intern("fn main() {\n    run();\n}\n");
+/// let run = ropes.slice(v1, 16..19).unwrap();
+///
+/// let v2 = ropes.edit(v1, 16..19, "start").unwrap();
+/// assert_eq!(ropes.to_string(v2), "fn main() {\n    start();\n}\n");
+/// assert_eq!(ropes.to_string(v1), "fn main() {\n    run();\n}\n");
+/// assert_eq!(run, "run");
+///
+/// // The versions share the chunks that the edit didn't touch.
+/// let shared = ropes.chunks(v2).filter(|chunk| ropes.chunks(v1).any(|other| other == *chunk));
+/// assert_eq!(shared.count(), 3);
+/// ```
+pub struct RopeTable<'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    chunk_len: usize,
+    ropes: Vec<Rope>,
+}
+
+impl<'strings> RopeTable<'strings> {
+    /// Creates an empty table that interns the chunks of documents into the symbol table.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        RopeTable::with_chunk_len(symbol_table, DEFAULT_CHUNK_LEN)
+    }
+
+    /// Creates an empty table that splits documents into chunks of about `chunk_len`
+    /// bytes. Smaller chunks make edits cheaper, and larger chunks make more slices
+    /// fit in a single chunk.
+    pub fn with_chunk_len(symbol_table: &'strings SymbolTable<'strings>, chunk_len: usize) -> Self {
+        RopeTable {
+            symbol_table,
+            chunk_len: chunk_len.max(1),
+            ropes: Vec::new(),
+        }
+    }
+
+    /// Splits text into chunks on char boundaries, and interns them.
+    fn push_chunks(&self, text: &str, chunks: &mut Vec<SymbolIndex>) {
+        let mut rest = text;
+        while !rest.is_empty() {
+            let mut end = self.chunk_len.min(rest.len());
+            while !rest.is_char_boundary(end) {
+                end += 1;
+            }
+            chunks.push(self.symbol_table.get(&rest[..end]).index());
+            rest = &rest[end..];
+        }
+    }
+
+    fn push_rope(&mut self, chunks: Vec<SymbolIndex>, edit: Option<Edit>) -> RopeId {
+        let mut end = 0;
+        let ends = chunks
+            .iter()
+            .map(|&chunk| {
+                end += self.symbol_table.str(chunk).len();
+                end
+            })
+            .collect();
+        let id = u32::try_from(self.ropes.len()).expect("Too many versions for a u32 id.");
+        self.ropes.push(Rope { chunks, ends, edit });
+        RopeId(id)
+    }
+
+    /// Interns a document as a new version with no history.
+    pub fn intern(&mut self, text: &str) -> RopeId {
+        let mut chunks = Vec::new();
+        self.push_chunks(text, &mut chunks);
+        self.push_rope(chunks, None)
+    }
+
+    /// Makes a new version of a document by replacing a byte range of it. Only the
+    /// chunks that overlap the range are interned again, and the rest are shared with
+    /// the previous version, which stays available. Returns `None` if the version
+    /// doesn't exist, or if the range is out of bounds or isn't on char boundaries.
+    pub fn edit(
+        &mut self,
+        version: RopeId,
+        range: Range<usize>,
+        replacement: &str,
+    ) -> Option<RopeId> {
+        let rope = self.ropes.get(version.index())?;
+        if range.start > range.end || range.end > rope.len() {
+            return None;
+        }
+        // The chunks from the one that contains the start of the range, to the one that
+        // contains its end, are replaced.
+        let first = rope.ends.partition_point(|&end| end <= range.start);
+        let last = rope.ends.partition_point(|&end| end < range.end).max(first);
+        let mut text = String::new();
+        for &chunk in rope.chunks.get(first..=last).unwrap_or_default() {
+            text.push_str(self.symbol_table.str(chunk));
+        }
+        let offset = if first < rope.chunks.len() {
+            rope.start(first)
+        } else {
+            rope.len()
+        };
+        let local = range.start - offset..range.end - offset;
+        if !text.is_char_boundary(local.start) || !text.is_char_boundary(local.end) {
+            return None;
+        }
+        text.replace_range(local, replacement);
+
+        let mut chunks = rope.chunks[..first.min(rope.chunks.len())].to_vec();
+        self.push_chunks(&text, &mut chunks);
+        chunks.extend_from_slice(rope.chunks.get(last + 1..).unwrap_or_default());
+        let edit = Edit {
+            parent: version,
+            range,
+            len: replacement.len(),
+        };
+        Some(self.push_rope(chunks, Some(edit)))
+    }
+
+    /// Makes a new version of a document from its changed text, such as a file that was
+    /// saved again. The text is diffed against the version by skipping their common
+    /// prefix and suffix, and the bytes in between are edited with
+    /// [`edit`](RopeTable::edit), so that [`VersionedSymbol`]s of the version can be
+    /// remapped to the new one. Returns `None` if the version doesn't exist.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{RopeTable, SymbolTable};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let mut ropes = RopeTable::new(&symbol_table);
+    /// let v1 = ropes.intern("let x = 1;");
+    /// let v2 = ropes.reintern(v1, "let x = 10;").unwrap();
+    /// assert_eq!(ropes.to_string(v2), "let x = 10;");
+    /// assert_eq!(ropes.parent(v2), Some(v1));
+    /// ```
+    pub fn reintern(&mut self, version: RopeId, text: &str) -> Option<RopeId> {
+        self.ropes.get(version.index())?;
+        let old = self.to_string(version);
+        let mut prefix = old
+            .bytes()
+            .zip(text.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(prefix) || !text.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let max_suffix = old.len().min(text.len()) - prefix;
+        let mut suffix = old
+            .bytes()
+            .rev()
+            .zip(text.bytes().rev())
+            .take(max_suffix)
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(old.len() - suffix)
+            || !text.is_char_boundary(text.len() - suffix)
+        {
+            suffix -= 1;
+        }
+        self.edit(
+            version,
+            prefix..old.len() - suffix,
+            &text[prefix..text.len() - suffix],
+        )
+    }
+
+    /// The version that a version was edited from, or `None` if it was interned with no
+    /// history, or doesn't exist.
+    pub fn parent(&self, version: RopeId) -> Option<RopeId> {
+        let edit = self.ropes.get(version.index())?.edit.as_ref()?;
+        Some(edit.parent)
+    }
+
+    /// The length in bytes of a version, or 0 if it doesn't exist.
+    pub fn len(&self, version: RopeId) -> usize {
+        self.ropes.get(version.index()).map_or(0, Rope::len)
+    }
+
+    /// The number of versions, across all documents.
+    pub fn versions(&self) -> usize {
+        self.ropes.len()
+    }
+
+    /// Iterates over the chunks of a version, in order.
+    pub fn chunks(&self, version: RopeId) -> impl Iterator<Item = Symbol<'strings>> + '_ {
+        let symbol_table = self.symbol_table;
+        self.ropes
+            .get(version.index())
+            .into_iter()
+            .flat_map(|rope| rope.chunks.iter())
+            .map(move |&chunk| Symbol::new(symbol_table, chunk))
+    }
+
+    /// Concatenates the chunks of a version, or returns an empty string if it doesn't
+    /// exist.
+    pub fn to_string(&self, version: RopeId) -> String {
+        let mut text = String::with_capacity(self.len(version));
+        for chunk in self.chunks(version) {
+            text.push_str(chunk.str());
+        }
+        text
+    }
+
+    /// A byte range of a version. A range within one chunk is a sliced symbol of that
+    /// chunk, which costs nothing, while a range that spans chunks is interned as a new
+    /// string. Returns `None` if the version doesn't exist, or if the range is out of
+    /// bounds or isn't on char boundaries.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn slice(&self, version: RopeId, range: Range<usize>) -> Option<Symbol<'strings>> {
+        let rope = self.ropes.get(version.index())?;
+        if range.start > range.end || range.end > rope.len() {
+            return None;
+        }
+        if range.is_empty() {
+            return Some(self.symbol_table.get(""));
+        }
+        let first = rope.ends.partition_point(|&end| end <= range.start);
+        let last = rope.ends.partition_point(|&end| end < range.end);
+        let offset = rope.start(first);
+        if first == last {
+            let chunk = Symbol::new(self.symbol_table, rope.chunks[first]);
+            return chunk.slice(range.start - offset..range.end - offset);
+        }
+        let mut text = String::new();
+        for &chunk in &rope.chunks[first..=last] {
+            text.push_str(self.symbol_table.str(chunk));
+        }
+        let string = text.get(range.start - offset..range.end - offset)?;
+        Some(self.symbol_table.get(string))
+    }
+
+    /// A byte range of a version as a [`VersionedSymbol`], which remembers where it is
+    /// in the version, so it can be remapped to later versions with
+    /// [`remap`](RopeTable::remap). Returns `None` in the same cases as
+    /// [`slice`](RopeTable::slice).
+    pub fn versioned(
+        &self,
+        version: RopeId,
+        range: Range<usize>,
+    ) -> Option<VersionedSymbol<'strings>> {
+        Some(VersionedSymbol {
+            symbol: self.slice(version, range.clone())?,
+            version,
+            start: range.start,
+        })
+    }
+
+    /// Remaps a symbol to a later version of its document, by following the edits
+    /// between the versions. Edits before the symbol shift it, and edits after it leave
+    /// it in place. Returns `None` if an edit overlapped the symbol, as its text could
+    /// have changed, or if the version wasn't edited from the symbol's version.
+    pub fn remap(
+        &self,
+        symbol: VersionedSymbol<'strings>,
+        version: RopeId,
+    ) -> Option<VersionedSymbol<'strings>> {
+        let mut edits = Vec::new();
+        let mut current = version;
+        while current != symbol.version {
+            let edit = self.ropes.get(current.index())?.edit.as_ref()?;
+            edits.push(edit);
+            current = edit.parent;
+        }
+        let mut start = symbol.start;
+        let len = symbol.symbol.str().len();
+        for edit in edits.into_iter().rev() {
+            if edit.range.end <= start {
+                start = start - edit.range.len() + edit.len;
+            } else if edit.range.start < start + len {
+                return None;
+            }
+        }
+        Some(VersionedSymbol {
+            symbol: symbol.symbol,
+            version,
+            start,
+        })
+    }
+
+    /// Returns true if a symbol can be remapped to a version of its document with
+    /// [`remap`](RopeTable::remap), as no edit since the symbol's version overlapped it.
+    pub fn is_valid(&self, symbol: VersionedSymbol<'strings>, version: RopeId) -> bool {
+        self.remap(symbol, version).is_some()
+    }
+}
+
+/// A slice of a version of a document in a [`RopeTable`], along with where it is in the
+/// version. Incremental parsers can keep the spans of their tokens as versioned symbols,
+/// and after an edit, remap the ones that the edit didn't touch with
+/// [`RopeTable::remap`] instead of reparsing them.
+///
+/// ```
+/// use gregtatum_symbol_table::{RopeTable, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// let mut ropes = RopeTable::new(&symbol_table);
+/// let v1 = ropes.intern("let a = 1;\nlet b = 2;");
+/// let b = ropes.versioned(v1, 15..16).unwrap();
+/// let two = ropes.versioned(v1, 19..20).unwrap();
+///
+/// let v2 = ropes.reintern(v1, "let a = 10;\nlet b = 2;").unwrap();
+/// let b = ropes.remap(b, v2).unwrap();
+/// assert_eq!((b.symbol(), b.range()), (symbol_table.get("b"), 16..17));
+/// assert_eq!(b.version(), v2);
+///
+/// // The edit replaced the `2`, so it has to be reparsed.
+/// let v3 = ropes.reintern(v2, "let a = 10;\nlet b = 3;").unwrap();
+/// assert!(ropes.is_valid(b, v3));
+/// assert!(!ropes.is_valid(two, v3));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VersionedSymbol<'strings> {
+    symbol: Symbol<'strings>,
+    version: RopeId,
+    start: usize,
+}
+
+impl<'strings> VersionedSymbol<'strings> {
+    /// The symbol, which is a slice of a chunk of the document, or an interned string if
+    /// it spans chunks. It stays valid after the document is edited.
+    pub fn symbol(&self) -> Symbol<'strings> {
+        self.symbol
+    }
+
+    /// The version of the document that the symbol is in.
+    pub fn version(&self) -> RopeId {
+        self.version
+    }
+
+    /// The byte range of the symbol in its version of the document.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.start + self.symbol.str().len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_edits_match_strings() {
+        let symbol_table = SymbolTable::new();
+        let mut ropes = RopeTable::with_chunk_len(&symbol_table, 4);
+        let mut expected = String::from("héllo wörld, this is a rope");
+        let mut version = ropes.intern(&expected);
+        for (range, replacement) in [
+            (0..0, ">"),
+            (2..4, ""),
+            (7..15, "_"),
+            (0..1, "a very long replacement"),
+            (30..30, "end"),
+        ] {
+            version = ropes.edit(version, range.clone(), replacement).unwrap();
+            expected.replace_range(range, replacement);
+            assert_eq!(ropes.to_string(version), expected);
+            assert_eq!(ropes.len(version), expected.len());
+        }
+        for range in [0..3, 2..9, 10..10, 0..expected.len()] {
+            assert_eq!(
+                ropes.slice(version, range.clone()).unwrap(),
+                expected[range]
+            );
+        }
+        let len = expected.len();
+        assert_eq!(ropes.slice(version, 0..len + 1), None);
+        assert_eq!(ropes.edit(version, len..len + 1, ""), None);
+
+        // Edits of an empty document add its first chunks.
+        let empty = ropes.intern("");
+        let edited = ropes.edit(empty, 0..0, "text").unwrap();
+        assert_eq!(ropes.to_string(edited), "text");
+        assert_eq!(ropes.versions(), 8);
+    }
+
+    #[test]
+    fn test_remap_versioned_symbols() {
+        let symbol_table = SymbolTable::new();
+        let mut ropes = RopeTable::with_chunk_len(&symbol_table, 4);
+        let v1 = ropes.intern("one two three");
+        let [one, two, three, empty] =
+            [0..3, 4..7, 8..13, 3..3].map(|range| ropes.versioned(v1, range).unwrap());
+        let v2 = ropes.edit(v1, 3..3, ",").unwrap();
+        let v3 = ropes.reintern(v2, "1, two three").unwrap();
+        let other = ropes.intern("one two three");
+
+        assert_eq!(ropes.remap(one, v2).map(|s| s.range()), Some(0..3));
+        assert_eq!(ropes.remap(one, v3), None);
+        assert_eq!(ropes.remap(two, v3).map(|s| s.range()), Some(3..6));
+        assert_eq!(ropes.remap(three, v3).map(|s| s.range()), Some(7..12));
+        assert_eq!(ropes.remap(three, v1), Some(three));
+        assert_eq!(ropes.remap(three, other), None);
+        // Insertions at the start of a symbol shift it, and ones within it replace it.
+        assert_eq!(ropes.remap(empty, v2).map(|s| s.range()), Some(4..4));
+        let inserted = ropes.edit(v1, 5..5, "w").unwrap();
+        assert!(!ropes.is_valid(two, inserted));
+
+        // Symbols can't be remapped to earlier versions.
+        let two = ropes.remap(two, v3).unwrap();
+        assert_eq!(ropes.remap(two, v1), None);
+    }
+}
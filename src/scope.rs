@@ -0,0 +1,154 @@
+//! Lexically scoped name resolution, such as for an interpreter or compiler.
+
+use fxhash::FxHashMap;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// Resolves names to values through a stack of nested scopes, where a definition in an
+/// inner scope shadows the definitions of the same name in outer scopes until its scope
+/// is popped. The resolver starts out with a global scope, which can't be popped.
+///
+/// Each name has its own stack of definitions, so a lookup is a single hash map lookup
+/// no matter how deeply the scopes are nested.
+///
+/// ```
+/// use gregtatum_symbol_table::{ScopedResolver, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// let [x, y] = ["x", "y"].map(|name| symbol_table.get(name));
+/// let mut resolver = ScopedResolver::new(&symbol_table);
+/// resolver.define(x, "global x");
+///
+/// resolver.push_scope();
+/// resolver.define(x, "local x");
+/// resolver.define(y, "local y");
+/// assert_eq!(resolver.lookup(x), Some(&"local x"));
+///
+/// let popped = resolver.pop_scope();
+/// assert_eq!(popped, [(x, "local x"), (y, "local y")]);
+/// assert_eq!(resolver.lookup(x), Some(&"global x"));
+/// assert_eq!(resolver.lookup(y), None);
+/// ```
+pub struct ScopedResolver<'strings, V> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    // The definitions of each name, as the depth of their scope and their value, with
+    // the innermost definition last.
+    definitions: FxHashMap<SymbolIndex, Vec<(usize, V)>>,
+    // The names defined in each scope, in the order that they were defined.
+    scopes: Vec<Vec<SymbolIndex>>,
+}
+
+impl<'strings, V> ScopedResolver<'strings, V> {
+    /// Creates a resolver with an empty global scope.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        ScopedResolver {
+            symbol_table,
+            definitions: FxHashMap::default(),
+            scopes: vec![Vec::new()],
+        }
+    }
+
+    /// The number of scopes, which is 1 when only the global scope is on the stack.
+    pub fn depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Enters a new innermost scope.
+    pub fn push_scope(&mut self) {
+        self.scopes.push(Vec::new());
+    }
+
+    /// Leaves the innermost scope, and returns its definitions in the order that they
+    /// were made, such as for reporting unused variables. This panics when only the
+    /// global scope is left.
+    pub fn pop_scope(&mut self) -> Vec<(Symbol<'strings>, V)> {
+        assert!(self.scopes.len() > 1, "The global scope can't be popped.");
+        let names = self.scopes.pop().unwrap();
+        // Remove the definitions from last to first, since a name can be defined more
+        // than once in a scope.
+        let mut popped: Vec<_> = names
+            .into_iter()
+            .rev()
+            .map(|name| {
+                let stack = self.definitions.get_mut(&name).unwrap();
+                let (_, value) = stack.pop().unwrap();
+                if stack.is_empty() {
+                    self.definitions.remove(&name);
+                }
+                (Symbol::new(self.symbol_table, name), value)
+            })
+            .collect();
+        popped.reverse();
+        popped
+    }
+
+    /// Defines a name in the innermost scope, shadowing any other definition of the
+    /// name, including an earlier one in the same scope. The symbol must be from the
+    /// resolver's table.
+    pub fn define(&mut self, name: Symbol<'strings>, value: V) {
+        let name = name.deslice().index();
+        let depth = self.scopes.len();
+        self.definitions
+            .entry(name)
+            .or_default()
+            .push((depth, value));
+        self.scopes.last_mut().unwrap().push(name);
+    }
+
+    /// The innermost definition of a name.
+    pub fn lookup(&self, name: Symbol) -> Option<&V> {
+        self.lookup_with_depth(name).map(|(_, value)| value)
+    }
+
+    /// The innermost definition of a name, for changing it in place.
+    pub fn lookup_mut(&mut self, name: Symbol) -> Option<&mut V> {
+        let (_, value) = self
+            .definitions
+            .get_mut(&name.deslice().index())?
+            .last_mut()?;
+        Some(value)
+    }
+
+    /// The innermost definition of a name, along with the depth of its scope, where the
+    /// global scope has a depth of 1. This is useful for resolving closure captures.
+    pub fn lookup_with_depth(&self, name: Symbol) -> Option<(usize, &V)> {
+        let (depth, value) = self.definitions.get(&name.deslice().index())?.last()?;
+        Some((*depth, value))
+    }
+
+    /// The definition of a name in the innermost scope only, such as for reporting a
+    /// duplicate definition.
+    pub fn lookup_local(&self, name: Symbol) -> Option<&V> {
+        self.lookup_with_depth(name)
+            .filter(|(depth, _)| *depth == self.scopes.len())
+            .map(|(_, value)| value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shadowing_in_one_scope() {
+        let symbol_table = SymbolTable::new();
+        let x = symbol_table.get("x");
+        let mut resolver = ScopedResolver::new(&symbol_table);
+        resolver.define(x, 1);
+        resolver.push_scope();
+        assert_eq!(resolver.lookup_local(x), None);
+        resolver.define(x, 2);
+        resolver.define(x, 3);
+        *resolver.lookup_mut(x).unwrap() += 10;
+        assert_eq!(resolver.lookup_with_depth(x), Some((2, &13)));
+        assert_eq!(resolver.lookup_local(x), Some(&13));
+
+        assert_eq!(resolver.pop_scope(), [(x, 2), (x, 13)]);
+        assert_eq!(resolver.depth(), 1);
+        assert_eq!(resolver.lookup_local(x), Some(&1));
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            resolver.pop_scope();
+        }))
+        .is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Symbols that own a reference to their table, rather than borrowing it.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+impl SymbolTable<'static> {
+    /// Creates an empty table that is owned by an [`Rc`], for interning
+    /// [`SharedSymbol`]s with
+    /// [`get_shared`](struct.SymbolTable.html#method.get_shared). The symbols hold a
+    /// clone of the `Rc`, so they have no lifetime parameter, and can be stored in
+    /// long-lived structs without borrowing the table.
+    ///
+    /// The table isn't thread-safe, so it's shared with an `Rc` rather than an `Arc`,
+    /// which would cost atomic reference counting without making the symbols `Send`.
+    /// See [`SyncSymbolTable`](crate::SyncSymbolTable) for symbols that can be sent to
+    /// other threads.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SharedSymbol, SymbolTable};
+    ///
+    /// struct Route {
+    ///     name: SharedSymbol,
+    /// }
+    ///
+    /// let symbol_table = SymbolTable::shared();
+    /// let route = Route { name: symbol_table.get_shared("index") };
+    /// drop(symbol_table);
+    /// assert_eq!(route.name, "index");
+    /// ```
+    pub fn shared() -> Rc<SymbolTable<'static>> {
+        Rc::new(SymbolTable::new())
+    }
+
+    /// Interns a string into a table that is owned by an [`Rc`], and returns a symbol
+    /// that holds a clone of the `Rc`.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn get_shared(self: &Rc<Self>, string: &str) -> SharedSymbol {
+        SharedSymbol {
+            index: self.get(string).index(),
+            symbol_table: Rc::clone(self),
+        }
+    }
+}
+
+/// A symbol that holds a clone of the [`Rc`] of its table, rather than a borrow, see
+/// [`SymbolTable::shared`](struct.SymbolTable.html#method.shared). Cloning it costs a
+/// reference count increment. It can be borrowed as a [`Symbol`] with
+/// [`symbol`](SharedSymbol::symbol) to use the rest of the symbol API.
+#[derive(Clone)]
+pub struct SharedSymbol {
+    symbol_table: Rc<SymbolTable<'static>>,
+    index: SymbolIndex,
+}
+
+impl SharedSymbol {
+    /// Converts a symbol of a table that is owned by an `Rc` into a shared symbol. The
+    /// symbol must be from the table, and if it's a slice, its string is interned.
+    pub fn new(symbol_table: &Rc<SymbolTable<'static>>, symbol: Symbol) -> SharedSymbol {
+        symbol_table.get_shared(symbol.str())
+    }
+
+    /// The index of the string in its table.
+    pub fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    /// The string of the symbol.
+    pub fn str(&self) -> &str {
+        &self.symbol_table[self.index]
+    }
+
+    /// Borrows the symbol as a [`Symbol`].
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::shared();
+    /// let name = symbol_table.get_shared("user_name");
+    /// assert_eq!(name.symbol().to_pascal_case(), "UserName");
+    /// ```
+    pub fn symbol(&self) -> Symbol<'_> {
+        Symbol::new(&self.symbol_table, self.index)
+    }
+
+    /// The table of the symbol.
+    pub fn symbol_table(&self) -> &Rc<SymbolTable<'static>> {
+        &self.symbol_table
+    }
+}
+
+impl PartialEq for SharedSymbol {
+    fn eq(&self, other: &Self) -> bool {
+        if Rc::ptr_eq(&self.symbol_table, &other.symbol_table) {
+            self.index == other.index
+        } else {
+            self.str() == other.str()
+        }
+    }
+}
+
+impl Eq for SharedSymbol {}
+
+impl Hash for SharedSymbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.str().hash(state);
+    }
+}
+
+impl PartialEq<&str> for SharedSymbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.str() == *other
+    }
+}
+
+impl PartialEq<str> for SharedSymbol {
+    fn eq(&self, other: &str) -> bool {
+        self.str() == other
+    }
+}
+
+impl fmt::Display for SharedSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.str())
+    }
+}
+
+impl fmt::Debug for SharedSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.str())
+    }
+}
+
+impl AsRef<str> for SharedSymbol {
+    fn as_ref(&self) -> &str {
+        self.str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shared_symbols() {
+        let symbol_table = SymbolTable::shared();
+        let a = symbol_table.get_shared("a");
+        let slice = symbol_table.get("ab").slice(1..2).unwrap();
+        let b = SharedSymbol::new(&symbol_table, slice);
+        assert_eq!(b.index(), 2);
+        assert_eq!(b.symbol(), "b");
+        assert_eq!(a.clone(), a);
+        assert_ne!(a, b);
+        assert_eq!(Rc::strong_count(&symbol_table), 3);
+
+        // Symbols of different tables are compared by their strings.
+        assert_eq!(SymbolTable::shared().get_shared("a"), a);
+    }
+}
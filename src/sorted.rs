@@ -0,0 +1,169 @@
+//! Iterating over the strings of the table in sorted order, such as for displaying them
+//! in a list, and loading tables from strings that are already sorted.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// The error for strings that aren't sorted and unique, see
+/// [`SymbolTable::from_sorted_unique`](struct.SymbolTable.html#method.from_sorted_unique).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnsortedError {
+    /// The index of the first string that isn't greater than the string before it.
+    pub index: SymbolIndex,
+}
+
+impl fmt::Display for UnsortedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The string at index {} isn't greater than the string before it.",
+            self.index
+        )
+    }
+}
+
+impl std::error::Error for UnsortedError {}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Creates a table from strings that are sorted by their bytes and unique, such as a
+    /// dictionary that was deduplicated when it was built. The strings aren't hashed, as
+    /// they are looked up by a binary search instead, which makes loading tens of
+    /// millions of strings much faster, at the cost of slower lookups of the loaded
+    /// strings. Strings that are interned afterwards are hashed as usual. Each string's
+    /// index is its position. This fails if a string isn't greater than the one before
+    /// it, which is checked as the strings are loaded.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SymbolTable, UnsortedError};
+    ///
+    /// let words = ["apple", "banana", "cherry"];
+    /// let symbol_table = SymbolTable::from_sorted_unique(words).unwrap();
+    /// assert_eq!(symbol_table.get("banana").index(), 1);
+    /// assert_eq!(symbol_table.get("date").index(), 3);
+    ///
+    /// let unsorted = SymbolTable::from_sorted_unique(["b", "a"]);
+    /// assert_eq!(unsorted.err(), Some(UnsortedError { index: 1 }));
+    /// ```
+    pub fn from_sorted_unique<T, I>(strings: I) -> Result<SymbolTable<'strings>, UnsortedError>
+    where
+        T: Into<String> + AsRef<str>,
+        I: IntoIterator<Item = T>,
+    {
+        let mut symbol_table = SymbolTable::new();
+        for (index, string) in strings.into_iter().enumerate() {
+            if index > 0 && symbol_table.str(index - 1) >= string.as_ref() {
+                return Err(UnsortedError { index });
+            }
+            symbol_table.push_unindexed(string.into());
+        }
+        symbol_table.sorted_len = symbol_table.len();
+        Ok(symbol_table)
+    }
+
+    /// Looks up a string that was loaded by
+    /// [`from_sorted_unique`](struct.SymbolTable.html#method.from_sorted_unique).
+    pub(crate) fn lookup_sorted(&self, string: &str) -> Option<SymbolIndex> {
+        let (mut low, mut high) = (0, self.sorted_len);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.str(middle).cmp(string) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Some(middle),
+            }
+        }
+        None
+    }
+
+    /// Iterates over every symbol, sorted by the bytes of its string. This sorts the
+    /// whole table on every call.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// for name in ["zebra", "apple", "Mango"] {
+    ///     symbol_table.get(name);
+    /// }
+    /// let sorted: Vec<_> = symbol_table.iter_sorted().collect();
+    /// assert_eq!(sorted, ["Mango", "apple", "zebra"]);
+    /// ```
+    pub fn iter_sorted(&'strings self) -> impl Iterator<Item = Symbol<'strings>> {
+        self.iter_sorted_by(str::cmp)
+    }
+
+    /// Iterates over every symbol, sorted by a comparison of the strings. Strings that
+    /// compare as equal keep their index order. This is the place to plug in a
+    /// locale-aware collation, such as the `compare` method of an ICU collator, for
+    /// strings that are shown to users.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// for name in ["zebra", "Äpfel", "apple", "Mango"] {
+    ///     symbol_table.get(name);
+    /// }
+    ///
+    /// // A stand-in for a collator, which orders case insensitively and folds accents.
+    /// let collation_key = |name: &str| name.to_lowercase().replace('ä', "a");
+    /// let sorted: Vec<_> = symbol_table
+    ///     .iter_sorted_by(|a, b| collation_key(a).cmp(&collation_key(b)))
+    ///     .collect();
+    /// assert_eq!(sorted, ["Äpfel", "apple", "Mango", "zebra"]);
+    /// ```
+    pub fn iter_sorted_by<F>(
+        &'strings self,
+        mut compare: F,
+    ) -> impl Iterator<Item = Symbol<'strings>>
+    where
+        F: FnMut(&str, &str) -> Ordering,
+    {
+        let mut indexes: Vec<SymbolIndex> = (0..self.len()).collect();
+        indexes.sort_by(|&a, &b| compare(&self[a], &self[b]));
+        indexes
+            .into_iter()
+            .map(move |index| Symbol::new(self, index))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sorted_by_is_stable() {
+        let symbol_table = SymbolTable::new();
+        for name in ["b", "B", "a", "A"] {
+            symbol_table.get(name);
+        }
+        let sorted: Vec<_> = symbol_table
+            .iter_sorted_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()))
+            .collect();
+        assert_eq!(sorted, ["a", "A", "b", "B"]);
+        assert_eq!(SymbolTable::new().iter_sorted().count(), 0);
+    }
+
+    #[test]
+    fn test_from_sorted_unique() {
+        let words: Vec<String> = (0..1000).map(|i| format!("{i:04}")).collect();
+        let mut symbol_table = SymbolTable::from_sorted_unique(&words).unwrap();
+        for (index, word) in words.iter().enumerate() {
+            assert_eq!(symbol_table.maybe_get(word).unwrap().index(), index);
+        }
+        assert_eq!(symbol_table.maybe_get("1000"), None);
+        assert_eq!(symbol_table.get("1000").index(), 1000);
+        assert_eq!(
+            SymbolTable::from_sorted_unique(["a", "a"]).err(),
+            Some(UnsortedError { index: 1 })
+        );
+
+        // The loaded strings are hashed when the table is reindexed.
+        symbol_table.get("0999");
+        symbol_table.reindex_by_frequency();
+        assert_eq!(symbol_table.get("0999").index(), 0);
+        assert_eq!(symbol_table.get("0000").index(), 2);
+    }
+}
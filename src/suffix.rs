@@ -0,0 +1,120 @@
+//! An optional index of reversed strings, for enumerating the symbols that end with a
+//! suffix, such as filtering interned paths by file extension.
+
+use std::collections::BTreeMap;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// The suffix index is keyed by the reversed bytes, since reversing the bytes of a
+/// multi-byte character doesn't produce valid UTF-8.
+pub(crate) type SuffixIndex = BTreeMap<Vec<u8>, SymbolIndex>;
+
+impl<'strings> SymbolTable<'strings> {
+    /// Starts maintaining an index of the reversed strings, so that
+    /// [`ends_with_lookup`](struct.SymbolTable.html#method.ends_with_lookup) only visits
+    /// the matching symbols. The strings that are already interned are indexed, as well
+    /// as every string interned afterwards. This costs a reversed copy of every string.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.index_suffixes();
+    /// symbol_table.get("src/lib.rs");
+    /// symbol_table.get("README.md");
+    /// symbol_table.get("src/main.rs");
+    ///
+    /// let rust_files: Vec<_> = symbol_table.ends_with_lookup(".rs").collect();
+    /// assert_eq!(rust_files, ["src/lib.rs", "src/main.rs"]);
+    /// ```
+    pub fn index_suffixes(&self) {
+        let mut suffixes = self.suffixes.borrow_mut();
+        if suffixes.is_none() {
+            let large = self.large_indexes();
+            *suffixes = Some(
+                (0..self.len())
+                    .filter(|index| !large.contains(index))
+                    .map(|index| (reversed(self.str(index)), index))
+                    .collect(),
+            );
+        }
+    }
+
+    /// Enumerates the symbols that end with a suffix, in the order that they were
+    /// interned. This uses the reversed string index if
+    /// [`index_suffixes`](struct.SymbolTable.html#method.index_suffixes) was called,
+    /// and otherwise checks every string.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// symbol_table.get("image.png");
+    /// symbol_table.get("photo.jpg");
+    /// assert_eq!(symbol_table.ends_with_lookup(".png").collect::<Vec<_>>(), ["image.png"]);
+    /// ```
+    pub fn ends_with_lookup(
+        &'strings self,
+        suffix: &str,
+    ) -> impl Iterator<Item = Symbol<'strings>> {
+        let mut indexes: Vec<SymbolIndex> = match &*self.suffixes.borrow() {
+            Some(suffixes) => {
+                let reversed_suffix = reversed(suffix);
+                // Large strings aren't in the index, so they are checked one by one.
+                suffixes
+                    .range(reversed_suffix.clone()..)
+                    .take_while(|(reversed, _)| reversed.starts_with(&reversed_suffix))
+                    .map(|(_, &index)| index)
+                    .chain(
+                        self.large_indexes()
+                            .into_iter()
+                            .filter(|&index| self.str(index).ends_with(suffix)),
+                    )
+                    .collect()
+            }
+            None => (0..self.len())
+                .filter(|&index| self.str(index).ends_with(suffix))
+                .collect(),
+        };
+        indexes.sort_unstable();
+        indexes.into_iter().map(|index| Symbol::new(self, index))
+    }
+
+    /// Adds a newly interned string to the suffix index, if it's enabled.
+    pub(crate) fn index_suffix(&self, index: SymbolIndex) {
+        if self.is_large_index(index) {
+            return;
+        }
+        if let Some(suffixes) = &mut *self.suffixes.borrow_mut() {
+            suffixes.insert(reversed(self.str(index)), index);
+        }
+    }
+}
+
+fn reversed(string: &str) -> Vec<u8> {
+    string.bytes().rev().collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_index_matches_scan() {
+        let strings = ["a.rs", "b.rs", "rs", "", "ü.rs", "c.rs.bak", "s"];
+        let scanned = SymbolTable::new();
+        let indexed = SymbolTable::new();
+        indexed.get(strings[0]);
+        indexed.index_suffixes();
+        for string in strings {
+            scanned.get(string);
+            indexed.get(string);
+        }
+        for suffix in ["rs", ".rs", "ü.rs", "", "s", "bak", "missing"] {
+            let expected: Vec<_> = scanned.ends_with_lookup(suffix).map(String::from).collect();
+            let actual: Vec<_> = indexed.ends_with_lookup(suffix).map(String::from).collect();
+            assert_eq!(actual, expected, "{suffix:?}");
+        }
+        assert_eq!(indexed.ends_with_lookup("").count(), strings.len());
+    }
+}
@@ -0,0 +1,245 @@
+//! A thread-safe table, whose symbols can be sent between threads and async tasks.
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use elsa::sync::{FrozenMap, FrozenVec};
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// A thread-safe table of interned strings, which is shared through an [`Arc`]. Its
+/// symbols are [`SendSymbol`]s, which hold a clone of the `Arc` rather than a borrow,
+/// so they are `Send` and `Sync`, and can be passed through channels, or held across
+/// `.await` points. The [`SymbolTable`] is faster for code that stays on one thread, as
+/// it has no locks.
+///
+/// Lookups take a read lock, and interning a new string takes a write lock, so the
+/// table is best for workloads where most strings are already interned.
+///
+/// ```
+/// use gregtatum_symbol_table::SyncSymbolTable;
+///
+/// let symbol_table = SyncSymbolTable::new();
+/// let hello = symbol_table.get("hello");
+/// let handle = std::thread::spawn({
+///     let symbol_table = symbol_table.clone();
+///     move || (hello.str().len(), symbol_table.get("world"))
+/// });
+/// let (len, world) = handle.join().unwrap();
+/// assert_eq!(len, 5);
+/// assert_eq!(world, "world");
+/// assert_eq!(symbol_table.get("world"), world);
+/// ```
+#[derive(Default)]
+pub struct SyncSymbolTable {
+    strings: FrozenVec<Box<str>>,
+    indexes: FrozenMap<Box<str>, Box<SymbolIndex>>,
+    // Serializes interning, so that two threads that intern the same string at the same
+    // time get the same index.
+    intern_lock: Mutex<()>,
+}
+
+impl SyncSymbolTable {
+    /// Creates an empty table.
+    pub fn new() -> Arc<SyncSymbolTable> {
+        Arc::new(SyncSymbolTable::default())
+    }
+
+    /// Interns a string, and returns a symbol that holds a clone of the table's `Arc`.
+    pub fn get(self: &Arc<Self>, string: &str) -> SendSymbol {
+        SendSymbol {
+            symbol_table: Arc::clone(self),
+            index: self.intern(string),
+        }
+    }
+
+    /// Interns a string, and returns its index.
+    pub fn intern(&self, string: &str) -> SymbolIndex {
+        if let Some(index) = self.lookup(string) {
+            return index;
+        }
+        let _guard = self
+            .intern_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // Another thread could have interned the string while waiting for the lock.
+        if let Some(index) = self.lookup(string) {
+            return index;
+        }
+        let index = self.strings.push_get_index(string.into());
+        self.indexes.insert(string.into(), Box::new(index));
+        index
+    }
+
+    /// Looks up the index of a string without interning it.
+    pub fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        self.indexes.get(string).copied()
+    }
+
+    /// The symbol for an index, or `None` if it's out of range.
+    pub fn symbol(self: &Arc<Self>, index: SymbolIndex) -> Option<SendSymbol> {
+        self.resolve(index)?;
+        Some(SendSymbol {
+            symbol_table: Arc::clone(self),
+            index,
+        })
+    }
+
+    /// The string for an index, or `None` if it's out of range.
+    pub fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        self.strings.get(index)
+    }
+
+    /// The number of interned strings.
+    pub fn len(&self) -> usize {
+        self.indexes.len()
+    }
+
+    /// Returns true if no strings are interned.
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+}
+
+/// A symbol of a [`SyncSymbolTable`], which can be sent between threads. It holds a
+/// clone of the table's [`Arc`] and the index of its string, so cloning it costs a
+/// reference count increment. Symbols of the same table are compared by index.
+#[derive(Clone)]
+pub struct SendSymbol {
+    symbol_table: Arc<SyncSymbolTable>,
+    index: SymbolIndex,
+}
+
+impl SendSymbol {
+    /// The index of the string in its table.
+    pub fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    /// The string of the symbol.
+    pub fn str(&self) -> &str {
+        self.symbol_table
+            .resolve(self.index)
+            .expect("A SendSymbol's index is in its table.")
+    }
+
+    /// The table of the symbol.
+    pub fn symbol_table(&self) -> &Arc<SyncSymbolTable> {
+        &self.symbol_table
+    }
+
+    /// Interns a symbol of a [`SymbolTable`] into a [`SyncSymbolTable`], such as for
+    /// sending it to another thread.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SendSymbol, SymbolTable, SyncSymbolTable};
+    ///
+    /// let shared = SyncSymbolTable::new();
+    /// let local = SymbolTable::new();
+    /// let symbol = local.get("request_id");
+    ///
+    /// let sent = SendSymbol::from_symbol(&shared, symbol);
+    /// let received = std::thread::spawn(move || sent).join().unwrap();
+    /// assert_eq!(received.to_symbol(&local), symbol);
+    /// ```
+    pub fn from_symbol(symbol_table: &Arc<SyncSymbolTable>, symbol: Symbol) -> SendSymbol {
+        symbol_table.get(symbol.str())
+    }
+
+    /// Interns the symbol into a [`SymbolTable`], such as the table of the thread that
+    /// received it.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn to_symbol<'strings>(
+        &self,
+        symbol_table: &'strings SymbolTable<'strings>,
+    ) -> Symbol<'strings> {
+        symbol_table.get(self.str())
+    }
+}
+
+impl PartialEq for SendSymbol {
+    fn eq(&self, other: &Self) -> bool {
+        if Arc::ptr_eq(&self.symbol_table, &other.symbol_table) {
+            self.index == other.index
+        } else {
+            self.str() == other.str()
+        }
+    }
+}
+
+impl Eq for SendSymbol {}
+
+impl Hash for SendSymbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.str().hash(state);
+    }
+}
+
+impl PartialEq<&str> for SendSymbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.str() == *other
+    }
+}
+
+impl PartialEq<str> for SendSymbol {
+    fn eq(&self, other: &str) -> bool {
+        self.str() == other
+    }
+}
+
+impl fmt::Display for SendSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.str())
+    }
+}
+
+impl fmt::Debug for SendSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.str())
+    }
+}
+
+impl AsRef<str> for SendSymbol {
+    fn as_ref(&self) -> &str {
+        self.str()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_interning() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SendSymbol>();
+
+        let symbol_table = SyncSymbolTable::new();
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let symbol_table = symbol_table.clone();
+                std::thread::spawn(move || {
+                    (0..100)
+                        .map(|i| symbol_table.get(&i.to_string()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let results: Vec<_> = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect();
+        assert_eq!(symbol_table.len(), 100);
+        for symbols in &results {
+            assert_eq!(symbols, &results[0]);
+        }
+        let symbol = &results[0][42];
+        assert_eq!(symbol_table.resolve(symbol.index()), Some("42"));
+        assert_eq!(symbol_table.symbol(symbol.index()).as_ref(), Some(symbol));
+        assert_eq!(symbol_table.symbol(100), None);
+
+        // Symbols of different tables are compared by their strings.
+        assert_eq!(SyncSymbolTable::new().get("42"), *symbol);
+    }
+}
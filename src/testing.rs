@@ -0,0 +1,306 @@
+//! Helpers for snapshot tests of the tables that a program builds, such as the
+//! identifiers that a compiler interns for a test input. A table is dumped to a text
+//! form with one string per line, which is compared against a golden file that is
+//! checked into the repository. When they differ, the assertion panics with a line diff.
+//!
+//! Golden files are written, rather than compared, when the `UPDATE_GOLDEN` environment
+//! variable is set, which is how they are created and updated:
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test
+//! ```
+//!
+//! ```no_run
+//! use gregtatum_symbol_table::{testing, SymbolTable};
+//!
+//! let symbol_table = SymbolTable::new();
+//! for identifier in ["main", "args"] {
+//!     symbol_table.get(identifier);
+//! }
+//! testing::assert_golden(&symbol_table, "tests/golden/main.txt");
+//! ```
+//!
+//! The [`MockInterner`] is a [`DynInterner`] with scripted failures, for testing the
+//! code that handles [`InternError`]s.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use elsa::{FrozenMap, FrozenVec};
+use fxhash::{FxBuildHasher, FxHashMap};
+
+use crate::{DynInterner, InternError, SymbolIndex, SymbolTable};
+
+/// The environment variable that makes the assertions write the golden files.
+pub const UPDATE_GOLDEN: &str = "UPDATE_GOLDEN";
+
+/// Dumps a table to its text form for golden files, with a line for each string in
+/// index order. The strings are quoted and escaped, so that each one fits on a line,
+/// and whitespace at the ends of a string is visible.
+///
+/// ```
+/// use gregtatum_symbol_table::{testing, SymbolTable};
+///
+/// let symbol_table = SymbolTable::new();
+/// symbol_table.get("main");
+/// symbol_table.get("line\n");
+/// assert_eq!(testing::to_golden(&symbol_table), "0: \"main\"\n1: \"line\\n\"\n");
+/// ```
+pub fn to_golden(symbol_table: &SymbolTable) -> String {
+    let mut text = String::new();
+    for (index, string) in symbol_table.iter().enumerate() {
+        writeln!(text, "{index}: {string:?}").expect("Writing to a String can't fail.");
+    }
+    text
+}
+
+/// Asserts that a table matches a golden file, see [`to_golden`]. This panics with a
+/// line diff if they differ, or if the file doesn't exist. The file is written instead
+/// when the [`UPDATE_GOLDEN`] environment variable is set.
+#[track_caller]
+pub fn assert_golden(symbol_table: &SymbolTable, path: impl AsRef<Path>) {
+    assert_golden_text(&to_golden(symbol_table), path);
+}
+
+/// Asserts that some text matches a golden file, such as text that was built from the
+/// symbols of a table, see [`assert_golden`].
+#[track_caller]
+pub fn assert_golden_text(actual: &str, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    if std::env::var_os(UPDATE_GOLDEN).is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create the golden file's directory.");
+        }
+        fs::write(path, actual).expect("Failed to write the golden file.");
+        return;
+    }
+    if let Err(message) = compare_golden(actual, path) {
+        panic!("{message}");
+    }
+}
+
+/// Compares some text to a golden file, whatever the [`UPDATE_GOLDEN`] environment
+/// variable is, and returns the message to panic with if they differ.
+fn compare_golden(actual: &str, path: &Path) -> Result<(), String> {
+    let expected = fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Failed to read the golden file {}: {error}\nRun with {UPDATE_GOLDEN}=1 to create it.",
+            path.display()
+        )
+    })?;
+    if expected != actual {
+        return Err(format!(
+            "The golden file {} doesn't match.\nRun with {UPDATE_GOLDEN}=1 to update it.\n{}",
+            path.display(),
+            diff(&expected, actual)
+        ));
+    }
+    Ok(())
+}
+
+/// A line diff from the expected text to the actual text. Lines that were removed start
+/// with `-`, lines that were added start with `+`, and unchanged lines start with a
+/// space.
+///
+/// ```
+/// use gregtatum_symbol_table::testing;
+///
+/// assert_eq!(testing::diff("a\nb\nc\n", "a\nc\nd\n"), " a\n-b\n c\n+d\n");
+/// ```
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    // Only the lines between the common prefix and suffix need the quadratic table, which
+    // keeps it small for golden files with a few changed lines.
+    let prefix = expected
+        .iter()
+        .zip(&actual)
+        .take_while(|(expected, actual)| expected == actual)
+        .count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(expected, actual)| expected == actual)
+        .count();
+    let mut text = String::new();
+    for line in &expected[..prefix] {
+        text.push_str(&format!(" {line}\n"));
+    }
+    let changed_expected = &expected[prefix..expected.len() - suffix];
+    let changed_actual = &actual[prefix..actual.len() - suffix];
+    diff_lines(changed_expected, changed_actual, &mut text);
+    for line in &expected[expected.len() - suffix..] {
+        text.push_str(&format!(" {line}\n"));
+    }
+    text
+}
+
+/// Diffs the lines with their longest common subsequence.
+fn diff_lines(expected: &[&str], actual: &[&str], text: &mut String) {
+    // The lengths of the longest common subsequences of the suffixes of the lines.
+    let mut lengths = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lengths[i][j] = if expected[i] == actual[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            text.push_str(&format!(" {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == actual.len()
+            || (i < expected.len() && lengths[i + 1][j] >= lengths[i][j + 1])
+        {
+            text.push_str(&format!("-{}\n", expected[i]));
+            i += 1;
+        } else {
+            text.push_str(&format!("+{}\n", actual[j]));
+            j += 1;
+        }
+    }
+}
+
+/// A [`DynInterner`] whose failures are scripted, so that the code that handles
+/// [`InternError`]s can be tested without building a table that is actually full or
+/// invalid. It interns strings like a real table until a scripted failure applies, and
+/// counts the calls to [`try_intern`](DynInterner::try_intern).
+///
+/// ```
+/// use gregtatum_symbol_table::testing::MockInterner;
+/// use gregtatum_symbol_table::{DynInterner, InternError};
+///
+/// fn intern_all(interner: &dyn DynInterner, strings: &[&str]) -> Result<usize, InternError> {
+///     for string in strings {
+///         interner.try_intern(string)?;
+///     }
+///     Ok(interner.len())
+/// }
+///
+/// let interner = MockInterner::new().with_capacity(2);
+/// assert_eq!(intern_all(&interner, &["a", "b"]), Ok(2));
+/// assert_eq!(intern_all(&interner, &["c"]), Err(InternError::Full { capacity: 2 }));
+///
+/// let interner = MockInterner::new().reject("", "Identifiers can't be empty.");
+/// interner.fail_next(InternError::ReadOnly);
+/// assert_eq!(interner.try_intern("a"), Err(InternError::ReadOnly));
+/// assert_eq!(interner.try_intern("a"), Ok(0));
+/// assert!(matches!(interner.try_intern(""), Err(InternError::Invalid { .. })));
+/// assert_eq!(interner.calls(), 3);
+/// ```
+#[derive(Default)]
+pub struct MockInterner {
+    strings: FrozenVec<String>,
+    indexes: FrozenMap<String, Box<SymbolIndex>, FxBuildHasher>,
+    capacity: Option<usize>,
+    rejected: FxHashMap<String, String>,
+    failures: RefCell<VecDeque<InternError>>,
+    calls: Cell<usize>,
+}
+
+impl MockInterner {
+    /// Creates an empty interner that doesn't fail.
+    pub fn new() -> MockInterner {
+        MockInterner::default()
+    }
+
+    /// Makes interning a new string fail with [`InternError::Full`] once the interner
+    /// has `capacity` strings.
+    pub fn with_capacity(mut self, capacity: usize) -> MockInterner {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Makes interning a string fail with [`InternError::Invalid`], with a reason.
+    pub fn reject(mut self, string: &str, reason: &str) -> MockInterner {
+        self.rejected.insert(string.to_owned(), reason.to_owned());
+        self
+    }
+
+    /// Makes the next call to [`try_intern`](DynInterner::try_intern) fail with an
+    /// error, whatever the string is. Calling this again queues more failures.
+    pub fn fail_next(&self, error: InternError) {
+        self.failures.borrow_mut().push_back(error);
+    }
+
+    /// The number of calls to [`try_intern`](DynInterner::try_intern), including the
+    /// ones that failed.
+    pub fn calls(&self) -> usize {
+        self.calls.get()
+    }
+}
+
+impl DynInterner for MockInterner {
+    fn intern(&self, string: &str) -> Option<SymbolIndex> {
+        self.try_intern(string).ok()
+    }
+
+    fn try_intern(&self, string: &str) -> Result<SymbolIndex, InternError> {
+        self.calls.set(self.calls.get() + 1);
+        if let Some(error) = self.failures.borrow_mut().pop_front() {
+            return Err(error);
+        }
+        if let Some(reason) = self.rejected.get(string) {
+            return Err(InternError::Invalid {
+                reason: reason.clone(),
+            });
+        }
+        if let Some(index) = self.lookup(string) {
+            return Ok(index);
+        }
+        if let Some(capacity) = self.capacity.filter(|&capacity| self.len() >= capacity) {
+            return Err(InternError::Full { capacity });
+        }
+        let index = self.strings.len();
+        self.strings.push(string.to_owned());
+        self.indexes.insert(string.to_owned(), Box::new(index));
+        Ok(index)
+    }
+
+    fn lookup(&self, string: &str) -> Option<SymbolIndex> {
+        self.indexes.get(string).copied()
+    }
+
+    fn resolve(&self, index: SymbolIndex) -> Option<&str> {
+        self.strings.get(index)
+    }
+
+    fn len(&self) -> usize {
+        self.strings.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_golden_files() {
+        let path = std::env::temp_dir().join(format!("golden-{}.txt", std::process::id()));
+        let symbol_table = SymbolTable::new();
+        symbol_table.get("a");
+        fs::write(&path, "0: \"a\"\n").unwrap();
+        assert_eq!(compare_golden(&to_golden(&symbol_table), &path), Ok(()));
+
+        symbol_table.get("b");
+        let message = compare_golden(&to_golden(&symbol_table), &path).unwrap_err();
+        assert!(message.ends_with("\n 0: \"a\"\n+1: \"b\"\n"), "{message}");
+        fs::remove_file(&path).unwrap();
+        assert!(compare_golden("", &path).is_err());
+
+        assert_eq!(diff("", ""), "");
+        assert_eq!(diff("a\n", ""), "-a\n");
+        assert_eq!(diff("a\nb\n", "b\na\n"), "-a\n b\n+a\n");
+        assert_eq!(diff("a\nb\nc\n", "a\nd\nc\n"), " a\n-b\n+d\n c\n");
+        assert_eq!(diff("a\na\n", "a\n"), " a\n-a\n");
+    }
+}
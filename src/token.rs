@@ -0,0 +1,204 @@
+//! Tokens annotated with user-defined kinds, so that the [`SymbolTable`](crate::SymbolTable)
+//! can serve as the token buffer of a hand-written parser.
+
+use std::ops::Range;
+
+use crate::Symbol;
+
+/// A [`Symbol`], typically a slice of interned source text, annotated with a
+/// user-defined kind.
+///
+/// ```
+/// use gregtatum_symbol_table::{SymbolTable, Token};
+///
+/// #[derive(Copy, Clone, Debug, PartialEq)]
+/// enum Kind {
+///     Keyword,
+///     Identifier,
+/// }
+///
+/// let symbol_table = SymbolTable::new();
+/// let source = symbol_table.get("let x");
+/// let token = Token::new(source.slice(0..3).unwrap(), Kind::Keyword);
+/// assert_eq!(token.symbol, "let");
+/// assert_eq!(token.kind, Kind::Keyword);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Token<'strings, K> {
+    pub symbol: Symbol<'strings>,
+    pub kind: K,
+}
+
+impl<'strings, K> Token<'strings, K> {
+    /// Creates a new token.
+    pub fn new(symbol: Symbol<'strings>, kind: K) -> Self {
+        Token { symbol, kind }
+    }
+}
+
+/// An ordered list of [`Token`]s, typically the output of a lexer, with utilities for
+/// working with ranges of tokens. Tokens are expected to be pushed in source order,
+/// as slices of the same parent symbol.
+///
+/// ```
+/// use gregtatum_symbol_table::{SymbolTable, TokenStream};
+///
+/// #[derive(Copy, Clone, Debug, PartialEq)]
+/// enum Kind {
+///     Number,
+///     Operator,
+/// }
+///
+/// let symbol_table = SymbolTable::new();
+/// let source = symbol_table.get("1 + 2");
+/// let mut tokens = TokenStream::new();
+/// tokens.push(source.slice(0..1).unwrap(), Kind::Number);
+/// tokens.push(source.slice(2..3).unwrap(), Kind::Operator);
+/// tokens.push(source.slice(4..5).unwrap(), Kind::Number);
+///
+/// assert_eq!(tokens.len(), 3);
+/// assert_eq!(tokens.span(0..3).unwrap(), "1 + 2");
+/// assert_eq!(tokens.of_kind(&Kind::Number).count(), 2);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenStream<'strings, K> {
+    tokens: Vec<Token<'strings, K>>,
+}
+
+impl<'strings, K> Default for TokenStream<'strings, K> {
+    fn default() -> Self {
+        TokenStream { tokens: Vec::new() }
+    }
+}
+
+impl<'strings, K> TokenStream<'strings, K> {
+    /// Creates an empty token stream.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a token to the end of the stream.
+    pub fn push(&mut self, symbol: Symbol<'strings>, kind: K) {
+        self.tokens.push(Token::new(symbol, kind));
+    }
+
+    /// The number of tokens in the stream.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns true if there are no tokens in the stream.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Gets a token by its position in the stream.
+    pub fn get(&self, index: usize) -> Option<&Token<'strings, K>> {
+        self.tokens.get(index)
+    }
+
+    /// Iterates over the tokens in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Token<'strings, K>> {
+        self.tokens.iter()
+    }
+
+    /// Gets the tokens in a range of positions, or `None` if it's out of bounds.
+    pub fn slice(&self, range: Range<usize>) -> Option<&[Token<'strings, K>]> {
+        self.tokens.get(range)
+    }
+
+    /// Joins the spans of a range of tokens, such as to get the source text of an
+    /// expression. Returns `None` if the range is empty or out of bounds, or if the
+    /// tokens don't share a parent symbol.
+    pub fn span(&self, range: Range<usize>) -> Option<Symbol<'strings>> {
+        let tokens = self.slice(range)?;
+        let first = tokens.first()?;
+        let last = tokens.last()?;
+        first.symbol.join_span(last.symbol)
+    }
+
+    /// Iterates over the tokens of a given kind.
+    pub fn of_kind<'a>(&'a self, kind: &'a K) -> impl Iterator<Item = &'a Token<'strings, K>>
+    where
+        K: PartialEq,
+    {
+        self.tokens.iter().filter(move |token| token.kind == *kind)
+    }
+
+    /// Finds the position of the token that contains a byte offset of the parent
+    /// symbol, such as for finding the token under a cursor.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SymbolTable, TokenStream};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let source = symbol_table.get("foo  bar");
+    /// let mut tokens = TokenStream::new();
+    /// tokens.push(source.slice(0..3).unwrap(), ());
+    /// tokens.push(source.slice(5..8).unwrap(), ());
+    ///
+    /// assert_eq!(tokens.position_at(1), Some(0));
+    /// assert_eq!(tokens.position_at(4), None);
+    /// assert_eq!(tokens.position_at(5), Some(1));
+    /// ```
+    pub fn position_at(&self, offset: usize) -> Option<usize> {
+        let index = self
+            .tokens
+            .partition_point(|token| token.symbol.range().start <= offset);
+        let index = index.checked_sub(1)?;
+        self.tokens[index]
+            .symbol
+            .range()
+            .contains(&offset)
+            .then_some(index)
+    }
+}
+
+impl<'strings, K> FromIterator<Token<'strings, K>> for TokenStream<'strings, K> {
+    fn from_iter<I: IntoIterator<Item = Token<'strings, K>>>(iter: I) -> Self {
+        TokenStream {
+            tokens: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, 'strings, K> IntoIterator for &'a TokenStream<'strings, K> {
+    type Item = &'a Token<'strings, K>;
+    type IntoIter = std::slice::Iter<'a, Token<'strings, K>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tokens.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SymbolTable;
+
+    #[test]
+    fn test_token_stream() {
+        let symbol_table = SymbolTable::new();
+        let source = symbol_table.get("a bb ccc");
+        let tokens: TokenStream<usize> = [(0..1), (2..4), (5..8)]
+            .into_iter()
+            .map(|range| Token::new(source.slice(range.clone()).unwrap(), range.len()))
+            .collect();
+
+        assert_eq!(tokens.span(1..3).unwrap(), "bb ccc");
+        assert_eq!(tokens.span(1..1), None);
+        assert_eq!(tokens.span(2..4), None);
+        assert_eq!(tokens.slice(0..2).unwrap().len(), 2);
+        assert_eq!(tokens.get(2).unwrap().symbol, "ccc");
+        assert_eq!(
+            tokens
+                .of_kind(&2)
+                .map(|token| token.symbol)
+                .collect::<Vec<_>>(),
+            ["bb"]
+        );
+        assert_eq!(tokens.position_at(7), Some(2));
+        assert_eq!(tokens.position_at(8), None);
+        assert_eq!((&tokens).into_iter().count(), 3);
+    }
+}
@@ -0,0 +1,135 @@
+//! Call-site statistics for interning, to find code that repeatedly interns the same
+//! strings, such as inside of a loop.
+
+use std::panic::Location;
+
+use crate::{SymbolIndex, SymbolTable};
+
+/// A code location that interned the same string more than once, as reported by
+/// [`SymbolTable::interning_hot_spots`](struct.SymbolTable.html#method.interning_hot_spots).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InterningHotSpot {
+    /// The call site of [`SymbolTable::get`](struct.SymbolTable.html#method.get).
+    pub location: &'static Location<'static>,
+    /// The index of the string that was interned.
+    pub index: SymbolIndex,
+    /// How many times the call site interned the string.
+    pub count: usize,
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Records a call to `get` for the caller's location.
+    #[track_caller]
+    pub(crate) fn track_call_site(&self, index: SymbolIndex) {
+        *self
+            .call_sites
+            .borrow_mut()
+            .entry((Location::caller(), index))
+            .or_default() += 1;
+    }
+
+    /// Reports the call sites that interned the same string more than once, sorted by
+    /// the number of calls, most frequent first. These are good candidates for hoisting
+    /// the interning out of a loop. This requires the `track-interning` feature, which
+    /// records the call site of every call to
+    /// [`get`](struct.SymbolTable.html#method.get).
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// for _ in 0..10 {
+    ///     symbol_table.get("loop_invariant");
+    /// }
+    /// symbol_table.get("once");
+    ///
+    /// let hot_spots = symbol_table.interning_hot_spots();
+    /// assert_eq!(hot_spots.len(), 1);
+    /// assert_eq!(&symbol_table[hot_spots[0].index], "loop_invariant");
+    /// assert_eq!(hot_spots[0].count, 10);
+    /// println!("Interned 10 times at {}", hot_spots[0].location);
+    /// ```
+    pub fn interning_hot_spots(&self) -> Vec<InterningHotSpot> {
+        let mut hot_spots: Vec<_> = self
+            .call_sites
+            .borrow()
+            .iter()
+            .filter(|(_, &count)| count > 1)
+            .map(|(&(location, index), &count)| InterningHotSpot {
+                location,
+                index,
+                count,
+            })
+            .collect();
+        hot_spots.sort_by_key(|hot_spot| {
+            (
+                std::cmp::Reverse(hot_spot.count),
+                hot_spot.location.file(),
+                hot_spot.location.line(),
+                hot_spot.location.column(),
+                hot_spot.index,
+            )
+        });
+        hot_spots
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_call_sites_are_separate() {
+        let symbol_table = SymbolTable::new();
+        for _ in 0..3 {
+            symbol_table.get("a");
+            symbol_table.get_in_place(b"a", 1);
+        }
+        for _ in 0..2 {
+            symbol_table.get("a");
+        }
+        let hot_spots = symbol_table.interning_hot_spots();
+        let counts: Vec<_> = hot_spots.iter().map(|hot_spot| hot_spot.count).collect();
+        assert_eq!(counts, [3, 3, 2]);
+        assert!(hot_spots
+            .iter()
+            .all(|hot_spot| hot_spot.location.file().ends_with("tracking.rs")));
+
+        // The wrappers around get report their own callers.
+        let symbol_table = SymbolTable::new();
+        let interner: &dyn crate::DynInterner = &symbol_table;
+        for _ in 0..2 {
+            symbol_table.get_header("Host");
+            symbol_table.get_path(["a", "b"]);
+            symbol_table.get_cstr(c"c");
+            interner.intern("d");
+            interner.try_intern("e").unwrap();
+        }
+        let hot_spots = symbol_table.interning_hot_spots();
+        assert_eq!(hot_spots.len(), 7);
+        assert!(hot_spots
+            .iter()
+            .all(|hot_spot| hot_spot.location.file().ends_with("tracking.rs")));
+    }
+
+    #[test]
+    fn test_wrappers_report_their_callers() {
+        let symbol_table = SymbolTable::new();
+        let mut column = crate::SymbolColumn::new(&symbol_table);
+        let mut frames = crate::FrameTable::new(&symbol_table);
+        let mut trie = crate::SymbolTrie::new(&symbol_table, '/');
+        let slice = symbol_table.get("std::io").slice(0..3).unwrap();
+        for _ in 0..2 {
+            crate::SymbolColumn::from_dictionary(&symbol_table, &[0], &["column"]);
+            column.extend(["extend"]);
+            frames.intern("main", "main.rs", 1);
+            trie.insert("trie", ());
+            slice.deslice();
+        }
+        let hot_spots = symbol_table.interning_hot_spots();
+        assert_eq!(hot_spots.len(), 6);
+        assert!(hot_spots
+            .iter()
+            .all(|hot_spot| hot_spot.location.file().ends_with("tracking.rs")));
+    }
+}
@@ -0,0 +1,190 @@
+//! A trie of hierarchical identifiers, such as dotted config keys or module paths.
+
+use std::collections::BTreeMap;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// Stores values for hierarchical identifiers, like `"server.http.port"`, as a tree of
+/// component symbols. This supports iterating over whole subtrees, and finding the
+/// nearest ancestor of a path that has a value, which is what config systems and
+/// module resolvers need.
+///
+/// Empty components are ignored, so `"a..b"` is the same path as `"a.b"`.
+///
+/// ```
+/// use gregtatum_symbol_table::{SymbolTable, SymbolTrie};
+///
+/// let symbol_table = SymbolTable::new();
+/// let mut config = SymbolTrie::new(&symbol_table, '.');
+/// config.insert("server", "defaults");
+/// config.insert("server.http.port", "8080");
+/// config.insert("server.http.host", "localhost");
+///
+/// assert_eq!(config.get("server.http.port"), Some(&"8080"));
+///
+/// let (ancestor, value) = config.nearest_ancestor("server.tls.cert").unwrap();
+/// assert_eq!(ancestor, "server");
+/// assert_eq!(*value, "defaults");
+///
+/// let http: Vec<_> = config.subtree("server.http").map(|(path, _)| path).collect();
+/// assert_eq!(http, ["server.http.port", "server.http.host"]);
+/// ```
+pub struct SymbolTrie<'strings, V> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    separator: char,
+    // The first node is the root.
+    nodes: Vec<Node<V>>,
+    len: usize,
+}
+
+struct Node<V> {
+    // Maps the index of a component to the index of the child node. This is ordered by
+    // symbol index so that iteration is deterministic.
+    children: BTreeMap<SymbolIndex, usize>,
+    // The full path and its value, if one was inserted for this node.
+    entry: Option<(SymbolIndex, V)>,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Node {
+            children: BTreeMap::new(),
+            entry: None,
+        }
+    }
+}
+
+impl<'strings, V> SymbolTrie<'strings, V> {
+    /// Creates an empty trie, where paths are split into components by the separator.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>, separator: char) -> Self {
+        SymbolTrie {
+            symbol_table,
+            separator,
+            nodes: vec![Node::new()],
+            len: 0,
+        }
+    }
+
+    /// Inserts a value for a path, interning the path and each of its components.
+    /// Returns the previous value for the path.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn insert(&mut self, path: &str, value: V) -> Option<V> {
+        let mut node = 0;
+        for component in path.split(self.separator).filter(|part| !part.is_empty()) {
+            let component = self.symbol_table.get(component).index();
+            node = match self.nodes[node].children.get(&component) {
+                Some(&child) => child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::new());
+                    self.nodes[node].children.insert(component, child);
+                    child
+                }
+            };
+        }
+        let path = self.symbol_table.get(path).index();
+        let previous = self.nodes[node].entry.replace((path, value));
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous.map(|(_, value)| value)
+    }
+
+    /// Gets the value for a path.
+    pub fn get(&self, path: &str) -> Option<&V> {
+        let node = *self.walk(path).last()?.as_ref()?;
+        self.nodes[node].entry.as_ref().map(|(_, value)| value)
+    }
+
+    /// Finds the deepest path with a value that is the path itself, or one of its
+    /// ancestors.
+    pub fn nearest_ancestor(&self, path: &str) -> Option<(Symbol<'strings>, &V)> {
+        self.walk(path)
+            .into_iter()
+            .map_while(|node| node)
+            .filter_map(|node| self.entry(node))
+            .last()
+    }
+
+    /// Iterates over the path and value of every entry at or below a path, depth first.
+    /// Children are visited in the order that their components were first interned.
+    pub fn subtree(&self, path: &str) -> impl Iterator<Item = (Symbol<'strings>, &V)> {
+        let mut stack: Vec<usize> = self
+            .walk(path)
+            .last()
+            .copied()
+            .flatten()
+            .into_iter()
+            .collect();
+        std::iter::from_fn(move || loop {
+            let node = stack.pop()?;
+            stack.extend(self.nodes[node].children.values().rev());
+            if let Some(entry) = self.entry(node) {
+                return Some(entry);
+            }
+        })
+    }
+
+    /// The number of paths with values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no values have been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the nodes along a path, starting at the root, without interning any
+    /// components. The node is `None` for components past the end of the trie.
+    fn walk(&self, path: &str) -> Vec<Option<usize>> {
+        let mut node = Some(0);
+        let mut nodes = vec![node];
+        for component in path.split(self.separator).filter(|part| !part.is_empty()) {
+            node = node.and_then(|node| {
+                let component = self.symbol_table.maybe_get(component)?;
+                self.nodes[node].children.get(&component.index()).copied()
+            });
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    fn entry(&self, node: usize) -> Option<(Symbol<'strings>, &V)> {
+        let (path, value) = self.nodes[node].entry.as_ref()?;
+        Some((Symbol::new(self.symbol_table, *path), value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_trie() {
+        let symbol_table = SymbolTable::new();
+        let mut modules = SymbolTrie::new(&symbol_table, '/');
+        assert!(modules.is_empty());
+        assert_eq!(modules.insert("/std/vec", 1), None);
+        assert_eq!(modules.insert("std/collections/hash_map", 2), None);
+        assert_eq!(modules.insert("std//vec/", 3), Some(1));
+        assert_eq!(modules.len(), 2);
+
+        assert_eq!(modules.get("std/vec"), Some(&3));
+        assert_eq!(modules.get("std"), None);
+        assert_eq!(modules.get("std/missing"), None);
+        assert_eq!(modules.nearest_ancestor("std"), None);
+        assert_eq!(
+            modules.nearest_ancestor("std/vec/Vec/new").unwrap().0,
+            "std//vec/"
+        );
+        assert_eq!(modules.subtree("").count(), 2);
+        assert_eq!(modules.subtree("std/collections").count(), 1);
+        assert_eq!(modules.subtree("core").count(), 0);
+        assert!(!symbol_table.has("core"), "Lookups don't intern.");
+
+        modules.insert("", 0);
+        assert_eq!(modules.get("/"), Some(&0));
+        assert_eq!(modules.nearest_ancestor("core").unwrap().1, &0);
+    }
+}
@@ -0,0 +1,173 @@
+//! Equivalence classes of symbols, such as for alias analysis or type inference.
+
+use std::collections::BTreeMap;
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// A union-find, or disjoint-set forest, over the symbols of a table, for merging
+/// symbols into equivalence classes. Since symbol indexes are dense, the forest is
+/// backed by a vector indexed by symbol, which grows as symbols are added. Every symbol
+/// starts out in a class of its own.
+///
+/// ```
+/// use gregtatum_symbol_table::{SymbolTable, SymbolUnionFind};
+///
+/// let symbol_table = SymbolTable::new();
+/// let [size, usize_, len, count] = ["Size", "usize", "Len", "Count"].map(|name| symbol_table.get(name));
+/// let mut aliases = SymbolUnionFind::new(&symbol_table);
+/// aliases.union(size, usize_);
+/// aliases.union(len, size);
+///
+/// assert!(aliases.same_class(len, usize_));
+/// assert!(!aliases.same_class(len, count));
+/// assert_eq!(aliases.class_of(usize_), ["Size", "usize", "Len"]);
+/// assert_eq!(aliases.find(count), "Count");
+/// ```
+pub struct SymbolUnionFind<'strings> {
+    symbol_table: &'strings SymbolTable<'strings>,
+    parents: Vec<SymbolIndex>,
+    // The number of symbols in the class of each root.
+    sizes: Vec<u32>,
+}
+
+impl<'strings> SymbolUnionFind<'strings> {
+    /// Creates a union-find where every symbol of the table is in a class of its own.
+    pub fn new(symbol_table: &'strings SymbolTable<'strings>) -> Self {
+        SymbolUnionFind {
+            symbol_table,
+            parents: Vec::new(),
+            sizes: Vec::new(),
+        }
+    }
+
+    /// Makes room for the symbols that were added to the table since the last call.
+    fn grow_to(&mut self, index: SymbolIndex) {
+        if index >= self.parents.len() {
+            self.parents.extend(self.parents.len()..=index);
+            self.sizes.resize(index + 1, 1);
+        }
+    }
+
+    fn find_index(&mut self, index: SymbolIndex) -> SymbolIndex {
+        self.grow_to(index);
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // Compress the path, so that later finds are faster.
+        let mut index = index;
+        while self.parents[index] != root {
+            let parent = self.parents[index];
+            self.parents[index] = root;
+            index = parent;
+        }
+        root
+    }
+
+    /// The representative symbol of the class of a symbol. Two symbols are in the same
+    /// class if they have the same representative, which can change after a
+    /// [`union`](Self::union). The symbol must be from the table.
+    pub fn find(&mut self, symbol: Symbol<'strings>) -> Symbol<'strings> {
+        let root = self.find_index(symbol.deslice().index());
+        Symbol::new(self.symbol_table, root)
+    }
+
+    /// Merges the classes of two symbols. Returns false if they were already in the
+    /// same class.
+    pub fn union(&mut self, a: Symbol<'strings>, b: Symbol<'strings>) -> bool {
+        let a = self.find_index(a.deslice().index());
+        let b = self.find_index(b.deslice().index());
+        if a == b {
+            return false;
+        }
+        // Attach the smaller tree to the larger one, to keep the trees shallow.
+        let (root, child) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        true
+    }
+
+    /// Returns true if two symbols are in the same class.
+    pub fn same_class(&mut self, a: Symbol<'strings>, b: Symbol<'strings>) -> bool {
+        self.find_index(a.deslice().index()) == self.find_index(b.deslice().index())
+    }
+
+    /// The symbols in the class of a symbol, in index order.
+    pub fn class_of(&mut self, symbol: Symbol<'strings>) -> Vec<Symbol<'strings>> {
+        let root = self.find_index(symbol.deslice().index());
+        let mut class = Vec::new();
+        for index in 0..self.parents.len() {
+            if self.find_index(index) == root {
+                class.push(Symbol::new(self.symbol_table, index));
+            }
+        }
+        class
+    }
+
+    /// Iterates over every class with more than one symbol, with the symbols of each
+    /// class in index order, and the classes in the order of their first symbol.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SymbolTable, SymbolUnionFind};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let [a, b, c, d] = ["a", "b", "c", "d"].map(|name| symbol_table.get(name));
+    /// let mut classes = SymbolUnionFind::new(&symbol_table);
+    /// classes.union(d, b);
+    /// classes.union(a, c);
+    /// let classes: Vec<_> = classes.classes().collect();
+    /// assert_eq!(classes, [vec!["a", "c"], vec!["b", "d"]]);
+    /// ```
+    pub fn classes(&mut self) -> impl Iterator<Item = Vec<Symbol<'strings>>> {
+        let mut classes: BTreeMap<SymbolIndex, Vec<Symbol<'strings>>> = BTreeMap::new();
+        let mut firsts = Vec::new();
+        for index in 0..self.parents.len() {
+            let root = self.find_index(index);
+            if self.sizes[root] < 2 {
+                continue;
+            }
+            let class = classes.entry(root).or_default();
+            if class.is_empty() {
+                firsts.push(root);
+            }
+            class.push(Symbol::new(self.symbol_table, index));
+        }
+        firsts
+            .into_iter()
+            .map(move |root| classes.remove(&root).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let symbol_table = SymbolTable::new();
+        let symbols: Vec<_> = (0..6).map(|i| symbol_table.get(i.to_string())).collect();
+        let mut classes = SymbolUnionFind::new(&symbol_table);
+        assert!(classes.union(symbols[0], symbols[1]));
+        assert!(classes.union(symbols[2], symbols[3]));
+        assert!(classes.union(symbols[1], symbols[3]));
+        assert!(!classes.union(symbols[0], symbols[2]));
+        assert!(classes.same_class(symbols[0], symbols[3]));
+        assert!(!classes.same_class(symbols[0], symbols[4]));
+
+        // Symbols added after the union-find was created are in classes of their own.
+        let late = symbol_table.get("late");
+        assert_eq!(classes.find(late), late);
+        assert_eq!(classes.class_of(late), ["late"]);
+        assert_eq!(classes.class_of(symbols[5]), ["5"]);
+        assert_eq!(classes.classes().count(), 1);
+
+        // Slices are found by their full symbol.
+        let slice = symbol_table.get("01").slice(1..2).unwrap();
+        assert!(classes.same_class(slice, symbols[1]));
+        assert_eq!(classes.find(slice), classes.find(symbols[0]));
+    }
+}
@@ -0,0 +1,285 @@
+//! Interning of URLs, with cheap access to their components as sliced [`Symbol`]s.
+
+use std::ops::Range;
+
+use crate::{Symbol, SymbolTable};
+
+/// A URL that has been interned once into the [`SymbolTable`], where each component
+/// is available as a slice of the full URL [`Symbol`]. Components can be compared
+/// against each other, or desliced into full [`Symbol`]s for cheap index comparisons.
+///
+/// This is a lightweight splitter for the generic URI syntax, and does not perform
+/// any normalization or percent decoding.
+///
+/// ```
+/// use gregtatum_symbol_table::SymbolTable;
+///
+/// let symbol_table = SymbolTable::new();
+/// let url = symbol_table
+///     .get_url("https://example.com:8080/docs/intro?lang=en&theme#install")
+///     .unwrap();
+///
+/// assert_eq!(url.scheme(), "https");
+/// assert_eq!(url.host().unwrap(), "example.com");
+/// assert_eq!(url.port().unwrap(), "8080");
+/// assert_eq!(url.path(), "/docs/intro");
+/// assert_eq!(url.path_segments().collect::<Vec<_>>(), ["docs", "intro"]);
+/// assert_eq!(url.query_keys().collect::<Vec<_>>(), ["lang", "theme"]);
+/// assert_eq!(url.fragment().unwrap(), "install");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct UrlSymbol<'strings> {
+    symbol: Symbol<'strings>,
+    scheme: (u32, u32),
+    host: Option<(u32, u32)>,
+    port: Option<(u32, u32)>,
+    path: (u32, u32),
+    query: Option<(u32, u32)>,
+    fragment: Option<(u32, u32)>,
+}
+
+impl<'strings> UrlSymbol<'strings> {
+    /// The full URL.
+    pub fn symbol(&self) -> Symbol<'strings> {
+        self.symbol
+    }
+
+    /// The scheme, without the trailing `:`, e.g. `"https"`.
+    pub fn scheme(&self) -> Symbol<'strings> {
+        self.component(self.scheme)
+    }
+
+    /// The host of the authority, e.g. `"example.com"`. The brackets are kept for IPv6
+    /// addresses. This is `None` for URLs without an authority, like `"mailto:"` URLs.
+    pub fn host(&self) -> Option<Symbol<'strings>> {
+        self.host.map(|range| self.component(range))
+    }
+
+    /// The port of the authority, without the leading `:`.
+    pub fn port(&self) -> Option<Symbol<'strings>> {
+        self.port.map(|range| self.component(range))
+    }
+
+    /// The full path, including the leading `/` if there is one. This may be empty.
+    pub fn path(&self) -> Symbol<'strings> {
+        self.component(self.path)
+    }
+
+    /// Iterates over the `/` separated segments of the path, skipping empty segments.
+    pub fn path_segments(&self) -> impl Iterator<Item = Symbol<'strings>> {
+        self.split(Some(self.path), '/')
+            .filter(|range| !range.is_empty())
+            .map(component(self.symbol))
+    }
+
+    /// The query, without the leading `?`.
+    pub fn query(&self) -> Option<Symbol<'strings>> {
+        self.query.map(|range| self.component(range))
+    }
+
+    /// Iterates over the keys of the `&` separated query pairs, in order.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let url = symbol_table.get_url("http://example.com/?a=1&b&c=3").unwrap();
+    /// assert_eq!(url.query_keys().collect::<Vec<_>>(), ["a", "b", "c"]);
+    /// ```
+    pub fn query_keys(&self) -> impl Iterator<Item = Symbol<'strings>> + 'strings {
+        self.query_pairs().map(|(key, _)| key)
+    }
+
+    /// Iterates over the `&` separated query pairs, in order. The value is `None` when
+    /// a pair has no `=`.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// let url = symbol_table.get_url("http://example.com/?a=1&b").unwrap();
+    /// let pairs: Vec<_> = url.query_pairs().collect();
+    /// assert_eq!(pairs[0].0, "a");
+    /// assert_eq!(pairs[0].1.unwrap(), "1");
+    /// assert_eq!(pairs[1].0, "b");
+    /// assert_eq!(pairs[1].1, None);
+    /// ```
+    pub fn query_pairs(
+        &self,
+    ) -> impl Iterator<Item = (Symbol<'strings>, Option<Symbol<'strings>>)> + 'strings {
+        let symbol = self.symbol;
+        let url = symbol.str();
+        self.split(self.query, '&')
+            .filter(|range| !range.is_empty())
+            .map(move |range| {
+                let to_symbol = component(symbol);
+                match url[range.clone()].find('=') {
+                    Some(offset) => {
+                        let equals = range.start + offset;
+                        (
+                            to_symbol(range.start..equals),
+                            Some(to_symbol(equals + 1..range.end)),
+                        )
+                    }
+                    None => (to_symbol(range), None),
+                }
+            })
+    }
+
+    /// The fragment, without the leading `#`.
+    pub fn fragment(&self) -> Option<Symbol<'strings>> {
+        self.fragment.map(|range| self.component(range))
+    }
+
+    fn component(&self, range: (u32, u32)) -> Symbol<'strings> {
+        component(self.symbol)(range.0 as usize..range.1 as usize)
+    }
+
+    /// Splits a component into byte ranges of the full URL.
+    fn split(
+        &self,
+        range: Option<(u32, u32)>,
+        separator: char,
+    ) -> impl Iterator<Item = Range<usize>> + 'strings {
+        let url = self.symbol.str();
+        let (start, end) = range.map_or((0, 0), |(start, end)| (start as usize, end as usize));
+        let mut offset = start;
+        url[start..end].split(separator).map(move |part| {
+            let part_start = offset;
+            offset += part.len() + separator.len_utf8();
+            part_start..part_start + part.len()
+        })
+    }
+}
+
+/// Creates a slice of the full URL symbol. The ranges are all computed on valid char
+/// boundaries during parsing.
+fn component<'strings>(symbol: Symbol<'strings>) -> impl Fn(Range<usize>) -> Symbol<'strings> {
+    move |range| {
+        symbol
+            .slice(range)
+            .expect("URL components are always valid slices")
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Interns a URL and splits it into its components. Returns `None` if the string
+    /// doesn't start with a valid scheme, or if it's in a
+    /// [redacted namespace](struct.SymbolTable.html#method.redact_namespace), as only the
+    /// redacted form would be stored, which has no components to slice. Redacted URLs
+    /// aren't interned. See [`UrlSymbol`] for an example.
+    #[cfg_attr(feature = "track-interning", track_caller)]
+    pub fn get_url(&'strings self, url: &str) -> Option<UrlSymbol<'strings>> {
+        let scheme_end = url.find(':')?;
+        let mut scheme_chars = url[..scheme_end].chars();
+        if !scheme_chars.next()?.is_ascii_alphabetic()
+            || !scheme_chars.all(|ch| ch.is_ascii_alphanumeric() || "+-.".contains(ch))
+            || self.redact(url).is_some()
+        {
+            return None;
+        }
+
+        // Split off the fragment and query from the end.
+        let fragment_start = url.find('#');
+        let before_fragment = fragment_start.unwrap_or(url.len());
+        let query_start = url[..before_fragment].find('?');
+        let before_query = query_start.unwrap_or(before_fragment);
+
+        let mut path_start = scheme_end + 1;
+        let mut host = None;
+        let mut port = None;
+        if url[path_start..before_query].starts_with("//") {
+            let authority_start = path_start + 2;
+            let authority_end = url[authority_start..before_query]
+                .find('/')
+                .map_or(before_query, |offset| authority_start + offset);
+            let host_start = url[authority_start..authority_end]
+                .rfind('@')
+                .map_or(authority_start, |offset| authority_start + offset + 1);
+            // Only look for the port after the closing bracket of an IPv6 address.
+            let port_search_start = url[host_start..authority_end]
+                .rfind(']')
+                .map_or(host_start, |offset| host_start + offset);
+            let host_end = match url[port_search_start..authority_end].rfind(':') {
+                Some(offset) => {
+                    let colon = port_search_start + offset;
+                    port = Some(range(colon + 1, authority_end));
+                    colon
+                }
+                None => authority_end,
+            };
+            host = Some(range(host_start, host_end));
+            path_start = authority_end;
+        }
+
+        Some(UrlSymbol {
+            symbol: self.get(url),
+            scheme: range(0, scheme_end),
+            host,
+            port,
+            path: range(path_start, before_query),
+            query: query_start.map(|start| range(start + 1, before_fragment)),
+            fragment: fragment_start.map(|start| range(start + 1, url.len())),
+        })
+    }
+}
+
+fn range(start: usize, end: usize) -> (u32, u32) {
+    (start as u32, end as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_url_components() {
+        let symbol_table = SymbolTable::new();
+        let url = symbol_table
+            .get_url("https://user:pass@[::1]:443/a//b/?x=1#top")
+            .unwrap();
+        assert_eq!(url.symbol(), "https://user:pass@[::1]:443/a//b/?x=1#top");
+        assert_eq!(url.host().unwrap(), "[::1]");
+        assert_eq!(url.port().unwrap(), "443");
+        assert_eq!(url.path(), "/a//b/");
+        assert_eq!(url.path_segments().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(url.query().unwrap(), "x=1");
+        assert_eq!(url.fragment().unwrap(), "top");
+
+        let mailto = symbol_table.get_url("mailto:someone@example.com").unwrap();
+        assert_eq!(mailto.scheme(), "mailto");
+        assert_eq!(mailto.host(), None);
+        assert_eq!(mailto.path(), "someone@example.com");
+        assert_eq!(mailto.query_keys().count(), 0);
+
+        assert!(symbol_table.get_url("not a url").is_none());
+        assert!(symbol_table.get_url("1http://example.com").is_none());
+
+        // Redacted URLs aren't stored, so they have no components.
+        symbol_table.redact_namespace("https:", 1);
+        let len = symbol_table.len();
+        assert!(symbol_table.get_url("https://example.com/x").is_none());
+        assert_eq!(symbol_table.len(), len);
+    }
+
+    #[test]
+    fn test_url_components_are_shared() {
+        let symbol_table = SymbolTable::new();
+        let a = symbol_table.get_url("https://example.com/a").unwrap();
+        let b = symbol_table.get_url("http://example.com/b").unwrap();
+        assert_eq!(a.host(), b.host());
+        assert_eq!(
+            a.host().unwrap().deslice(),
+            b.host().unwrap().deslice(),
+            "Desliced components are cheaply comparable."
+        );
+        assert_eq!(symbol_table.len(), 3);
+
+        // The query pairs don't borrow the URL.
+        let keys = symbol_table
+            .get_url("https://example.com/?k=v")
+            .unwrap()
+            .query_keys();
+        assert_eq!(keys.collect::<Vec<_>>(), ["k"]);
+    }
+}
@@ -0,0 +1,216 @@
+//! Compact encodings for sequences of symbol indexes, such as long token streams. Each
+//! index is written as a LEB128 varint, so that small indexes take a single byte.
+//! Sequences can optionally be delta encoded, where each index is written as the
+//! zigzag-encoded difference from the previous one, which keeps sorted or clustered
+//! indexes small.
+//!
+//! ```
+//! use gregtatum_symbol_table::varint;
+//!
+//! let ids = [1000, 1001, 1003, 1002];
+//! let plain = varint::encode_ids(&ids, false);
+//! let delta = varint::encode_ids(&ids, true);
+//! assert_eq!(plain.len(), 8);
+//! assert_eq!(delta.len(), 5);
+//! assert_eq!(varint::decode_ids(&delta, true).unwrap(), ids);
+//! ```
+
+use std::fmt;
+
+use crate::SymbolIndex;
+
+/// The error for bytes that aren't a valid varint encoding.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VarintError {
+    /// The bytes ended in the middle of a varint.
+    Truncated,
+    /// A varint, or an index that was decoded from a delta, doesn't fit in its type.
+    Overflow,
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VarintError::Truncated => write!(f, "The bytes ended in the middle of a varint."),
+            VarintError::Overflow => write!(f, "A varint is too large for its type."),
+        }
+    }
+}
+
+impl std::error::Error for VarintError {}
+
+/// Appends a value as a LEB128 varint.
+///
+/// ```
+/// use gregtatum_symbol_table::varint;
+///
+/// let mut bytes = Vec::new();
+/// varint::write_u64(&mut bytes, 300);
+/// assert_eq!(bytes, [0xac, 0x02]);
+/// ```
+pub fn write_u64(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Reads a LEB128 varint from the front of the bytes, and advances past it.
+///
+/// ```
+/// use gregtatum_symbol_table::varint;
+///
+/// let mut bytes: &[u8] = &[0xac, 0x02, 0x01];
+/// assert_eq!(varint::read_u64(&mut bytes), Ok(300));
+/// assert_eq!(bytes, [0x01]);
+/// ```
+pub fn read_u64(bytes: &mut &[u8]) -> Result<u64, VarintError> {
+    let mut value: u64 = 0;
+    for (i, &byte) in bytes.iter().enumerate() {
+        if i == 9 && byte > 1 {
+            return Err(VarintError::Overflow);
+        }
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte < 0x80 {
+            *bytes = &bytes[i + 1..];
+            return Ok(value);
+        }
+    }
+    Err(VarintError::Truncated)
+}
+
+/// Encodes a stream of indexes one at a time, such as when writing a log.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IdEncoder {
+    delta: bool,
+    previous: SymbolIndex,
+}
+
+impl IdEncoder {
+    /// Creates an encoder, which delta encodes the indexes if `delta` is true.
+    pub fn new(delta: bool) -> Self {
+        IdEncoder { delta, previous: 0 }
+    }
+
+    /// Appends the encoding of the next index.
+    pub fn encode(&mut self, bytes: &mut Vec<u8>, index: SymbolIndex) {
+        if self.delta {
+            let difference = (index as i64).wrapping_sub(self.previous as i64);
+            write_u64(bytes, zigzag(difference));
+            self.previous = index;
+        } else {
+            write_u64(bytes, index as u64);
+        }
+    }
+}
+
+/// Decodes a stream of indexes that was written by an [`IdEncoder`], as an iterator.
+/// The iterator stops after the first error.
+#[derive(Clone, Debug)]
+pub struct IdDecoder<'a> {
+    bytes: &'a [u8],
+    delta: bool,
+    previous: SymbolIndex,
+}
+
+impl<'a> IdDecoder<'a> {
+    /// Creates a decoder, which decodes delta encoded indexes if `delta` is true.
+    pub fn new(bytes: &'a [u8], delta: bool) -> Self {
+        IdDecoder {
+            bytes,
+            delta,
+            previous: 0,
+        }
+    }
+
+    fn decode(&mut self) -> Result<SymbolIndex, VarintError> {
+        let value = read_u64(&mut self.bytes)?;
+        if !self.delta {
+            return SymbolIndex::try_from(value).map_err(|_| VarintError::Overflow);
+        }
+        let index = (self.previous as i64)
+            .checked_add(unzigzag(value))
+            .and_then(|index| SymbolIndex::try_from(index).ok())
+            .ok_or(VarintError::Overflow)?;
+        self.previous = index;
+        Ok(index)
+    }
+}
+
+impl Iterator for IdDecoder<'_> {
+    type Item = Result<SymbolIndex, VarintError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let result = self.decode();
+        if result.is_err() {
+            self.bytes = &[];
+        }
+        Some(result)
+    }
+}
+
+/// Encodes a sequence of indexes, delta encoding them if `delta` is true.
+pub fn encode_ids(indexes: &[SymbolIndex], delta: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(indexes.len());
+    let mut encoder = IdEncoder::new(delta);
+    for &index in indexes {
+        encoder.encode(&mut bytes, index);
+    }
+    bytes
+}
+
+/// Decodes a sequence of indexes that was encoded by [`encode_ids`] with the same
+/// `delta` option.
+pub fn decode_ids(bytes: &[u8], delta: bool) -> Result<Vec<SymbolIndex>, VarintError> {
+    IdDecoder::new(bytes, delta).collect()
+}
+
+pub(crate) fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub(crate) fn unzigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let mut bytes = Vec::new();
+            write_u64(&mut bytes, value);
+            assert_eq!(read_u64(&mut bytes.as_slice()), Ok(value));
+        }
+        for value in [0, 1, -1, i64::MIN, i64::MAX] {
+            assert_eq!(unzigzag(zigzag(value)), value);
+        }
+        let indexes = [5, 0, usize::MAX >> 2, 3, 3];
+        for delta in [false, true] {
+            assert_eq!(
+                decode_ids(&encode_ids(&indexes, delta), delta).unwrap(),
+                indexes
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(decode_ids(&[0x80], false), Err(VarintError::Truncated));
+        assert_eq!(
+            read_u64(&mut [0xff; 10].as_slice()),
+            Err(VarintError::Overflow)
+        );
+        // A delta of -1 from the start is out of range.
+        assert_eq!(decode_ids(&[0x01], true), Err(VarintError::Overflow));
+        let mut decoder = IdDecoder::new(&[0x01, 0x02], true);
+        assert!(decoder.next().unwrap().is_err());
+        assert_eq!(decoder.next(), None);
+    }
+}
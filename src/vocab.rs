@@ -0,0 +1,101 @@
+//! Exporting the table as the vocabulary of a tokenizer.
+
+use std::io::{self, Write};
+
+use crate::SymbolTable;
+
+/// The file formats for [`SymbolTable::export_vocab`](struct.SymbolTable.html#method.export_vocab).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VocabFormat {
+    /// A `vocab.txt` file, with one token per line, where the line number, starting at
+    /// 0, is the index of the token. Tokens can't contain line breaks.
+    Text,
+    /// A `vocab.json` file, with an object that maps each token to its index.
+    Json,
+}
+
+impl<'strings> SymbolTable<'strings> {
+    /// Writes every string with its index as a tokenizer vocabulary, in the formats used
+    /// by HuggingFace tokenizers. The index of each token is its
+    /// [`SymbolIndex`](crate::SymbolIndex). For the text format, this returns an
+    /// [`InvalidData`](io::ErrorKind::InvalidData) error if a token contains a line
+    /// break, as it can't be represented.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::{SymbolTable, VocabFormat};
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// for token in ["[PAD]", "[UNK]", "hello", "##ing"] {
+    ///     symbol_table.get(token);
+    /// }
+    ///
+    /// let mut text = Vec::new();
+    /// symbol_table.export_vocab(&mut text, VocabFormat::Text).unwrap();
+    /// assert_eq!(text, b"[PAD]\n[UNK]\nhello\n##ing\n");
+    ///
+    /// let mut json = Vec::new();
+    /// symbol_table.export_vocab(&mut json, VocabFormat::Json).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(json).unwrap(),
+    ///     r###"{"[PAD]":0,"[UNK]":1,"hello":2,"##ing":3}"###
+    /// );
+    /// ```
+    pub fn export_vocab<W: Write>(&self, mut writer: W, format: VocabFormat) -> io::Result<()> {
+        match format {
+            VocabFormat::Text => {
+                for string in self.iter() {
+                    if string.contains(['\n', '\r']) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("The token {string:?} contains a line break."),
+                        ));
+                    }
+                    writer.write_all(string.as_bytes())?;
+                    writer.write_all(b"\n")?;
+                }
+            }
+            VocabFormat::Json => {
+                let mut entry = String::new();
+                writer.write_all(b"{")?;
+                for (index, string) in self.iter().enumerate() {
+                    entry.clear();
+                    if index != 0 {
+                        entry.push(',');
+                    }
+                    crate::json::push_string(&mut entry, string);
+                    entry.push_str(&format!(":{index}"));
+                    writer.write_all(entry.as_bytes())?;
+                }
+                writer.write_all(b"}")?;
+            }
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_export_edge_cases() {
+        let symbol_table = SymbolTable::new();
+        let mut json = Vec::new();
+        symbol_table
+            .export_vocab(&mut json, VocabFormat::Json)
+            .unwrap();
+        assert_eq!(json, b"{}");
+
+        symbol_table.get("line\nbreak");
+        let mut json = Vec::new();
+        symbol_table
+            .export_vocab(&mut json, VocabFormat::Json)
+            .unwrap();
+        assert_eq!(json, br#"{"line\nbreak":0}"#);
+
+        let error = symbol_table
+            .export_vocab(Vec::new(), VocabFormat::Text)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
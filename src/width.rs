@@ -0,0 +1,220 @@
+//! The width of strings when displayed in a terminal, cached per symbol.
+
+use crate::{Symbol, SymbolIndex, SymbolTable};
+
+/// Characters that take up no columns, like combining marks and zero width spaces, as
+/// inclusive ranges of code points.
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x05BF, 0x05BF),
+    (0x05C1, 0x05C2),
+    (0x05C4, 0x05C5),
+    (0x05C7, 0x05C7),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x0670, 0x0670),
+    (0x06D6, 0x06DC),
+    (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8),
+    (0x06EA, 0x06ED),
+    (0x0900, 0x0902),
+    (0x093A, 0x093A),
+    (0x093C, 0x093C),
+    (0x0941, 0x0948),
+    (0x094D, 0x094D),
+    (0x0951, 0x0957),
+    (0x0E31, 0x0E31),
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x1160, 0x11FF),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x202A, 0x202E),
+    (0x2060, 0x2064),
+    (0x20D0, 0x20F0),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xFEFF, 0xFEFF),
+    (0xE0000, 0xE0FFF),
+];
+
+/// Characters that take up two columns, which are the East Asian wide and fullwidth
+/// characters, and emoji, as inclusive ranges of code points.
+const DOUBLE_WIDTH: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0),
+    (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267F, 0x267F),
+    (0x2693, 0x2693),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x26FD, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x274E, 0x274E),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x16FE0, 0x16FE4),
+    (0x17000, 0x18AFF),
+    (0x1B000, 0x1B2FF),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F200, 0x1F251),
+    (0x1F300, 0x1F320),
+    (0x1F32D, 0x1F335),
+    (0x1F337, 0x1F37C),
+    (0x1F37E, 0x1F393),
+    (0x1F3A0, 0x1F3CA),
+    (0x1F3CF, 0x1F3D3),
+    (0x1F3E0, 0x1F3F0),
+    (0x1F3F4, 0x1F3F4),
+    (0x1F3F8, 0x1F43E),
+    (0x1F440, 0x1F440),
+    (0x1F442, 0x1F4FC),
+    (0x1F4FF, 0x1F53D),
+    (0x1F54B, 0x1F54E),
+    (0x1F550, 0x1F567),
+    (0x1F57A, 0x1F57A),
+    (0x1F595, 0x1F596),
+    (0x1F5A4, 0x1F5A4),
+    (0x1F5FB, 0x1F64F),
+    (0x1F680, 0x1F6C5),
+    (0x1F6CC, 0x1F6CC),
+    (0x1F6D0, 0x1F6D2),
+    (0x1F6D5, 0x1F6D7),
+    (0x1F6EB, 0x1F6EC),
+    (0x1F6F4, 0x1F6FC),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F90C, 0x1F93A),
+    (0x1F93C, 0x1F945),
+    (0x1F947, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+fn in_table(table: &[(u32, u32)], ch: char) -> bool {
+    let code_point = ch as u32;
+    table
+        .binary_search_by(|&(start, end)| {
+            if end < code_point {
+                std::cmp::Ordering::Less
+            } else if start > code_point {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// The number of terminal columns of a character.
+fn char_width(ch: char) -> usize {
+    if ch.is_ascii() {
+        return usize::from(!ch.is_ascii_control());
+    }
+    if ch.is_control() || in_table(ZERO_WIDTH, ch) {
+        0
+    } else if in_table(DOUBLE_WIDTH, ch) {
+        2
+    } else {
+        1
+    }
+}
+
+fn str_width(string: &str) -> usize {
+    string.chars().map(char_width).sum()
+}
+
+impl<'strings> Symbol<'strings> {
+    /// The number of columns that the string takes up in a terminal, for aligning
+    /// columns of text. East Asian wide characters and emoji take two columns, and
+    /// combining marks and control characters take none. The width of a full symbol is
+    /// cached in the table, so it's only computed once per string. This uses a built-in
+    /// table of the common wide and zero width characters, rather than the full Unicode
+    /// data, and doesn't account for grapheme clusters like emoji sequences.
+    ///
+    /// ```
+    /// use gregtatum_symbol_table::SymbolTable;
+    ///
+    /// let symbol_table = SymbolTable::new();
+    /// assert_eq!(symbol_table.get("name").display_width(), 4);
+    /// assert_eq!(symbol_table.get("名前").display_width(), 4);
+    /// assert_eq!(symbol_table.get("e\u{301}").display_width(), 1);
+    /// ```
+    pub fn display_width(&self) -> usize {
+        if self.range.is_some() {
+            return str_width(self.str());
+        }
+        self.symbol_table.display_width(self.index)
+    }
+}
+
+impl<'strings> SymbolTable<'strings> {
+    fn display_width(&self, index: SymbolIndex) -> usize {
+        if let Some(Some(width)) = self.display_widths.borrow().get(index) {
+            return *width as usize;
+        }
+        let width = str_width(self.str(index));
+        let mut widths = self.display_widths.borrow_mut();
+        if widths.len() <= index {
+            widths.resize(index + 1, None);
+        }
+        widths[index] = Some(width as u32);
+        width
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display_width() {
+        let symbol_table = SymbolTable::new();
+        let mixed = symbol_table.get("a\tb🦀中\u{200B}");
+        assert_eq!(mixed.display_width(), 6);
+        assert_eq!(mixed.display_width(), 6, "The cached width is the same.");
+        assert_eq!(mixed.slice(3..7).unwrap().display_width(), 2);
+        assert_eq!(symbol_table.display_widths.borrow().as_slice(), [Some(6)]);
+    }
+}